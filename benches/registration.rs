@@ -0,0 +1,87 @@
+//! Compares the registration of an electorate by a batch with the individual transactions.
+//!
+//! Run with `cargo bench --bench registration`.
+
+extern crate exonum;
+extern crate exonum_testkit;
+extern crate vote_service as vote;
+
+use std::time::{Duration, Instant};
+
+use exonum::{
+    blockchain::Transaction,
+    crypto::{self, CryptoHash, Hash, PublicKey, SecretKey},
+};
+use exonum_testkit::{TestKit, TestKitBuilder};
+
+use vote::{
+    config::{VoteConfig, CONFIGURED_SCHEME},
+    schema::{ElectorEntry, VoteSchema},
+    service::VoteService,
+    transactions::{CreateElection, RegisterElector, RegisterElectorsBatch, MAX_BATCH_SIZE},
+};
+
+const ELECTORATE_SIZES: [usize; 3] = [100, 1_000, 5_000];
+
+fn main() {
+    println!("{:>10} {:>16} {:>16}", "electors", "individual/s", "batched/s");
+    for &size in &ELECTORATE_SIZES {
+        let individual = register(size, individual_transactions);
+        let batched = register(size, batch_transactions);
+        println!("{:>10} {:>16.0} {:>16.0}", size, per_second(size, individual), per_second(size, batched));
+    }
+}
+
+/// Registers the electorate of the given size in a single block and returns the time of the block.
+fn register<F>(size: usize, transactions: F) -> Duration
+where
+    F: Fn(&Hash, &(PublicKey, SecretKey), &[PublicKey]) -> Vec<Box<dyn Transaction>>,
+{
+    let registrar = crypto::gen_keypair();
+    let (mut testkit, election) = init_testkit(&registrar.0);
+    let electors: Vec<PublicKey> = (0..size).map(|_| crypto::gen_keypair().0).collect();
+    let transactions = transactions(&election, &registrar, &electors);
+
+    let start = Instant::now();
+    testkit.create_block_with_transactions(transactions);
+    let elapsed = start.elapsed();
+
+    let registered = VoteSchema::new(testkit.snapshot()).election(&election).unwrap().electorate_size();
+    assert_eq!(registered, size as u64);
+    elapsed
+}
+
+fn individual_transactions(election: &Hash, registrar: &(PublicKey, SecretKey), electors: &[PublicKey]) -> Vec<Box<dyn Transaction>> {
+    let (registrar, registrar_sec) = registrar;
+    electors.iter()
+        .map(|elector| RegisterElector::new(election, registrar, elector, "Elector", 1, registrar_sec))
+        .map(|tx| Box::new(tx) as Box<dyn Transaction>)
+        .collect()
+}
+
+fn batch_transactions(election: &Hash, registrar: &(PublicKey, SecretKey), electors: &[PublicKey]) -> Vec<Box<dyn Transaction>> {
+    let (registrar, registrar_sec) = registrar;
+    electors.chunks(MAX_BATCH_SIZE)
+        .enumerate()
+        .map(|(seed, chunk)| {
+            let entries = chunk.iter().map(|elector| ElectorEntry::new(elector, "Elector", 1)).collect();
+            RegisterElectorsBatch::new(election, registrar, entries, seed as u64, registrar_sec)
+        })
+        .map(|tx| Box::new(tx) as Box<dyn Transaction>)
+        .collect()
+}
+
+fn init_testkit(registrar: &PublicKey) -> (TestKit, Hash) {
+    let config = VoteConfig { registrars: vec![*registrar], ..VoteConfig::default() };
+    let mut testkit = TestKitBuilder::validator()
+        .with_service(VoteService::new(config))
+        .create();
+    let (author, author_sec) = crypto::gen_keypair();
+    let tx = CreateElection::new(&author, "Board", 10, 20, 30, 1, 1, CONFIGURED_SCHEME, 0, &author_sec);
+    testkit.create_block_with_transaction(tx.clone());
+    (testkit, tx.hash())
+}
+
+fn per_second(count: usize, elapsed: Duration) -> f64 {
+    count as f64 / (elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) / 1e9)
+}
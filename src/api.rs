@@ -1,72 +1,356 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use exonum::{
     api::{self, ServiceApiBuilder, ServiceApiState},
-    blockchain::{BlockProof, Transaction, Schema, TransactionSet, Schema as GeneralSchema},
+    blockchain::{BlockProof, Transaction, TransactionSet, Schema as GeneralSchema},
     crypto::{Hash, PublicKey},
     node::TransactionSend,
-    storage::{MapProof, ListProof, Snapshot},
+    storage::{proof_map_index::ProofMapKey, MapProof, ListProof, ProofMapIndex, Snapshot, StorageValue},
     helpers::Height,
-    explorer::{BlockchainExplorer, TransactionInfo}
 };
 
+use config::VotingScheme;
 use service::VOTE_SERVICE;
+use tally::{self, CandidateTally, IrvRound, Seats, WeightedBallot};
 use transactions::VoteTransactions;
-use schema::{Candidate, Elector, VoteSchema};
+use schema::{
+    history_id, last_checkpoint, sub_ring_bounds, Ballot, Commitment, Delegation, Election, ElectionPhase, Candidate, Elector, KeyShare, PartialDecryption, Revocation, TokenSession, TurnoutBucket, TurnoutCheckpoint, VoicesCheckpoint, VoteRecord, VoteSchema,
+};
+
+/// Number of the records on a page of a listing by default.
+const DEFAULT_PAGE_SIZE: u32 = 100;
+/// Maximum number of the records on a page of a listing.
+const MAX_PAGE_SIZE: u32 = 1000;
+/// Number of the records scanned for a filtered page per record of the page,
+/// the page is cut short with the key to continue the scan from in `next`.
+const SCANNED_PER_RECORD: usize = 10;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TransactionResponse {
     pub tx_hash: Hash,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ElectionQuery {
+    pub election_id: Hash,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResultsQuery {
+    pub election_id: Hash,
+    /// Height of the block to reconstruct the tally at.
+    pub height: Option<u64>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CandidateQuery {
+    pub election_id: Hash,
     pub pub_key: PublicKey,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ElectorQuery {
+    pub election_id: Hash,
+    pub pub_key: PublicKey,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReceiptQuery {
+    pub election_id: Hash,
+    pub pub_key: PublicKey,
+    /// Candidate whose vote history contains the ballot, the candidate of the elector record by default.
+    pub candidate: Option<PublicKey>,
+}
+
+/// Listing of the candidates of an election.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CandidatesQuery {
+    pub election_id: Hash,
+    /// Key of the first candidate of the page, the listing is in the key order.
+    pub from: Option<PublicKey>,
+    pub limit: Option<u32>,
+    pub name_prefix: Option<String>,
+}
+
+/// Listing of the electors of an election.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ElectorsQuery {
+    pub election_id: Hash,
+    /// Key of the first elector of the page, the listing is in the key order.
+    pub from: Option<PublicKey>,
+    pub limit: Option<u32>,
+    pub name_prefix: Option<String>,
+    pub has_vote: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Key to request the next page from, absent on the last page, the page before it may be short.
+    pub next: Option<PublicKey>,
+}
+
+/// Page of the records of an election index proven against the election record.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProofPageQuery<K> {
+    pub election_id: Hash,
+    /// Key of the first record of the page, the records are in the key order.
+    pub from: Option<K>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProofPage<K, V> {
+    /// Proof of the records of the page against the index hash of the election.
+    pub proof: MapProof<K, V>,
+    /// Key to request the next page from, absent on the last page.
+    pub next: Option<K>,
+}
+
+/// Page of the vote history of a candidate.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoryQuery {
+    pub election_id: Hash,
     pub pub_key: PublicKey,
+    /// Index of the first transaction of the page.
+    pub from: Option<u64>,
+    pub limit: Option<u32>,
 }
 
+/// Transactions of the vote history from the index `from` with the proof against the history root of the candidate.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VotingHistory {
+    pub from: u64,
+    pub length: u64,
     pub transactions: Vec<VoteTransactions>,
     pub history_proof: ListProof<Hash>,
 }
 
+/// Page of the revocations of the electors.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevocationsQuery {
+    pub election_id: Hash,
+    /// Index of the first revocation of the page.
+    pub from: Option<u64>,
+    pub limit: Option<u32>,
+}
+
+/// Revocations from the index `from` with the proof against `revocations_hash` of the election.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevocationLog {
+    pub from: u64,
+    pub length: u64,
+    pub revocations: Vec<Revocation>,
+    pub proof: ListProof<Revocation>,
+}
+
+/// Sub-ring of an `Anonymous` election.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RingQuery {
+    pub election_id: Hash,
+    pub index: u64,
+}
+
+/// Members of the sub-ring `index` out of `rings` sub-rings, with the proof against `ring_hash`
+/// of the election once the ring is frozen.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubRing {
+    pub index: u64,
+    pub rings: u64,
+    pub members: Vec<PublicKey>,
+    pub proof: Option<ListProof<PublicKey>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BallotLogQuery {
+    pub from: u64,
+    pub to: u64,
+}
+
+/// Range of the ballot log with the proof against the block state hash.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BallotLog {
+    pub length: u64,
+    pub transactions: Vec<Hash>,
+    pub proof: ListProof<Hash>,
+    pub block_proof: BlockProof,
+    pub to_table: MapProof<Hash, Hash>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CandidateInfo {
     pub candidate: Candidate,
+    pub delegated_voices: u64,
+    /// Decrypted votes of an encrypted election, set once the threshold number of the decryption shares is submitted.
+    pub decrypted_voices: Option<u64>,
     pub vote_percent: f32,
     pub proof: MapProof<PublicKey, Candidate>,
-    pub history: VotingHistory,
+    /// Proof of the history root against the history roots of the service state.
+    pub history_root_proof: MapProof<Hash, Hash>,
+}
+
+/// Votes of a candidate after a block with the proof of the checkpoints against the candidate record.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckpointedVoices {
+    pub pub_key: PublicKey,
+    pub voices: u64,
+    pub vote_percent: f32,
+    /// Number of the checkpoints of the candidate.
+    pub length: u64,
+    /// Proof of the last checkpoint at or before the height and of the next checkpoint if any,
+    /// or of the first checkpoint if the candidate is registered after the height.
+    pub proof: ListProof<VoicesCheckpoint>,
+}
+
+/// Tally reconstructed from the checkpoints as of the block at `height`.
+///
+/// The checkpoints are proven against the current state, the delegated and the encrypted votes aren't included.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoricalTally {
+    pub height: Height,
+    pub turnout: u64,
+    pub total_weight: u64,
+    /// Number of the turnout checkpoints of the election.
+    pub length: u64,
+    pub turnout_proof: ListProof<TurnoutCheckpoint>,
+    pub candidates: Vec<CheckpointedVoices>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VotingResults {
+    pub election: Election,
+    pub phase: ElectionPhase,
+    pub is_final: bool,
+    pub election_proof: MapProof<Hash, Election>,
     pub candidates: Vec<CandidateInfo>,
+    /// Withdrawn and disqualified candidates, they don't take seats.
+    pub withdrawn: Vec<CandidateInfo>,
+    /// Proof of all the candidates of the election, as many as `candidates_count` of the election.
+    pub candidates_proof: MapProof<PublicKey, Candidate>,
+    pub total_weight: u64,
+    /// Seated candidates, `None` for a `RankedChoice` election, see `v1/results/irv`.
+    pub seats: Option<Seats>,
+    /// Number of the committed votes which are not revealed yet.
+    pub unrevealed: u64,
+    pub block_proof: BlockProof,
+    pub to_table: MapProof<Hash, Hash>,
+    /// Proof of the history roots against the block state hash.
+    pub history_roots_to_table: MapProof<Hash, Hash>,
+    /// Tally at the height of the query.
+    pub historical: Option<HistoricalTally>,
+}
+
+/// Page of the delegations with the records resolving the delegated weight, proven against the election record.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DelegationPage {
+    pub delegations: ProofPage<PublicKey, Delegation>,
+    /// Proof of the records of the delegators and the delegates of the page.
+    pub electors_proof: MapProof<PublicKey, Elector>,
+    /// Proof of the approval ballots of the delegates of the page.
+    pub ballots_proof: MapProof<PublicKey, Ballot>,
+}
+
+/// Block of the elector's ballot with the proof of the vote record against the block state hash.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VoteLocation {
+    /// Actual key of the elector, the requested key may be replaced by `RotateKey`.
+    pub pub_key: PublicKey,
+    pub height: Height,
+    pub tx_hash: Hash,
+    pub proof: MapProof<PublicKey, VoteRecord>,
+    pub election_proof: MapProof<Hash, Election>,
+    pub block_proof: BlockProof,
+    pub to_table: MapProof<Hash, Hash>,
+}
+
+/// Participation in the election, the counters are proven by the election record.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ElectionStats {
+    pub election: Election,
+    pub election_proof: MapProof<Hash, Election>,
+    pub electorate_size: u64,
+    pub ballots_cast: u64,
+    pub turnout_percent: f32,
+    pub abstentions: u64,
+    /// Ballots cast by the buckets of block heights in the order of the heights.
+    pub buckets: Vec<TurnoutBucket>,
+    pub buckets_proof: MapProof<Hash, TurnoutBucket>,
+    pub block_proof: BlockProof,
+    pub to_table: MapProof<Hash, Hash>,
+}
+
+/// Proofs that the ballot of the elector is recorded in the elector record
+/// and counted in the vote history of the candidate.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VoteReceipt {
+    pub elector: Elector,
+    pub proof: MapProof<PublicKey, Elector>,
+    pub transaction: VoteTransactions,
+    pub candidate: Candidate,
+    pub candidate_proof: MapProof<PublicKey, Candidate>,
+    /// Index of the ballot in the vote history of the candidate.
+    pub position: u64,
+    pub history_length: u64,
+    pub history_proof: ListProof<Hash>,
+    pub election: Election,
+    pub election_proof: MapProof<Hash, Election>,
     pub block_proof: BlockProof,
     pub to_table: MapProof<Hash, Hash>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IrvResults {
+    pub election: Election,
+    pub phase: ElectionPhase,
+    pub is_final: bool,
+    pub election_proof: MapProof<Hash, Election>,
+    pub ballots_proof: MapProof<PublicKey, Ballot>,
+    pub delegations_proof: MapProof<PublicKey, Delegation>,
+    pub rounds: Vec<IrvRound>,
+    pub winner: Option<PublicKey>,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct PublicApi;
 
 impl PublicApi {
-    pub fn results(state: &ServiceApiState, _query: ()) -> api::Result<VotingResults> {
+    pub fn results(state: &ServiceApiState, query: ResultsQuery) -> api::Result<VotingResults> {
         let snapshot = state.snapshot();
         let general_schema = GeneralSchema::new(&snapshot);
 
         let max_height = general_schema.block_hashes_by_height().len() - 1;
 
         let schema = VoteSchema::new(state.snapshot());
-        let idx = schema.candidates();
+        let election = schema.election(&query.election_id)
+            .ok_or_else(|| api::Error::NotFound("Election not found".to_owned()))?;
+        let election_proof = schema.elections().get_proof(query.election_id);
+        let phase = election.phase(schema.height());
 
-        let total_votes_number: u64 = idx.iter()
-            .map(|c| c.1.voices()).sum();
+        let idx = schema.candidates(&query.election_id);
+        let delegated = Self::get_delegated_weights(&schema, &election);
+        let delegated_voices = Self::get_delegated_voices(&schema, &election, &delegated);
 
-        let candidates: Vec<CandidateInfo> = idx.iter()
-            .map(|c| Self::get_candidate_info(&schema, &general_schema, c.1, total_votes_number))
-            .collect();
+        // Percentages are computed over the total weight of the electors who have voted,
+        // directly or through a delegate, the void votes for the withdrawn candidates aren't included.
+        let total_weight = election.total_weight() + delegated.iter()
+            .filter(|(voter, _)| schema.elector(&query.election_id, voter)
+                .into_iter()
+                .any(|elector| schema.counts_ballot(&query.election_id, &elector)))
+            .map(|(_, weight)| weight)
+            .sum::<u64>();
+
+        let decrypted = Self::get_decrypted_voices(&schema, &election);
+
+        let (candidates, withdrawn): (Vec<CandidateInfo>, Vec<CandidateInfo>) = idx.iter()
+            .map(|c| {
+                let delegated = delegated_voices.get(&c.0).cloned().unwrap_or(0);
+                let decrypted = decrypted.as_ref().and_then(|voices| voices.get(&c.0).cloned());
+                Self::get_candidate_info(&schema, &query.election_id, c.1, delegated, decrypted, total_weight)
+            })
+            .partition(|info| info.candidate.is_active());
+        let candidates_proof = idx.get_multiproof(idx.keys());
+        let seats = Self::get_seats(&schema, &election, &delegated_voices, decrypted.as_ref());
+        let unrevealed = election.unrevealed();
 
         let block_proof = general_schema
             .block_and_precommits(Height(max_height))
@@ -74,42 +358,419 @@ impl PublicApi {
 
         let to_table: MapProof<Hash, Hash> =
             general_schema.get_proof_to_service_table(VOTE_SERVICE, 0);
+        let history_roots_to_table: MapProof<Hash, Hash> =
+            general_schema.get_proof_to_service_table(VOTE_SERVICE, 1);
+
+        let historical = match query.height {
+            Some(height) if height > max_height => {
+                return Err(api::Error::BadRequest("The block doesn't exist yet".to_owned()));
+            }
+            Some(height) => Some(Self::get_historical_tally(&schema, &query.election_id, Height(height))),
+            None => None,
+        };
+
+        // The encrypted votes aren't known until the sums are decrypted.
+        let is_final = phase == ElectionPhase::Closed
+            && (election.voting_scheme() != VotingScheme::Encrypted || decrypted.is_some());
 
         Ok(VotingResults {
+            election,
+            phase,
+            is_final,
+            election_proof,
             candidates,
+            withdrawn,
+            candidates_proof,
+            total_weight,
+            seats,
+            unrevealed,
             block_proof,
             to_table,
+            history_roots_to_table,
+            historical,
         })
     }
 
+    fn get_historical_tally(
+        schema: &VoteSchema<Box<dyn Snapshot>>,
+        election_id: &Hash,
+        height: Height,
+    ) -> HistoricalTally {
+        let checkpoints = schema.turnout_checkpoints(election_id);
+        let length = checkpoints.len();
+        let index = last_checkpoint(length, |index| checkpoints.get(index).unwrap().height(), height.0);
+        let (turnout, total_weight) = index
+            .and_then(|index| checkpoints.get(index))
+            .map_or((0, 0), |checkpoint| (checkpoint.turnout(), checkpoint.total_weight()));
+        let turnout_proof = Self::get_checkpoint_proof(length, index, |from, to| checkpoints.get_range_proof(from, to));
+
+        let candidates = schema.candidates(election_id).keys()
+            .map(|pub_key| {
+                let checkpoints = schema.voices_checkpoints(election_id, &pub_key);
+                let length = checkpoints.len();
+                let index = last_checkpoint(length, |index| checkpoints.get(index).unwrap().height(), height.0);
+                let voices = index
+                    .and_then(|index| checkpoints.get(index))
+                    .map_or(0, |checkpoint| checkpoint.voices());
+                let vote_percent = if total_weight == 0 {
+                    0.0
+                } else {
+                    voices as f64 * 100.0 / total_weight as f64
+                };
+                CheckpointedVoices {
+                    pub_key,
+                    voices,
+                    vote_percent: vote_percent as f32,
+                    length,
+                    proof: Self::get_checkpoint_proof(length, index, |from, to| checkpoints.get_range_proof(from, to)),
+                }
+            })
+            .collect();
+
+        HistoricalTally {
+            height,
+            turnout,
+            total_weight,
+            length,
+            turnout_proof,
+            candidates,
+        }
+    }
+
+    /// Proves the checkpoint found by `last_checkpoint` with the next one,
+    /// which shows that the checkpoint is the last one at the height.
+    fn get_checkpoint_proof<V, F>(length: u64, index: Option<u64>, range_proof: F) -> ListProof<V>
+    where
+        F: Fn(u64, u64) -> ListProof<V>,
+    {
+        // The checkpoints are never empty, the first one is made at the registration.
+        let from = index.unwrap_or(0);
+        let to = if index.is_some() { length.min(from + 2) } else { 1 };
+        range_proof(from, to)
+    }
+
+    pub fn stats(state: &ServiceApiState, query: ElectionQuery) -> api::Result<ElectionStats> {
+        let snapshot = state.snapshot();
+        let general_schema = GeneralSchema::new(&snapshot);
+        let max_height = general_schema.block_hashes_by_height().len() - 1;
+
+        let schema = VoteSchema::new(state.snapshot());
+        let election = schema.election(&query.election_id)
+            .ok_or_else(|| api::Error::NotFound("Election not found".to_owned()))?;
+        let election_proof = schema.elections().get_proof(query.election_id);
+
+        let electorate_size = election.electorate_size();
+        let ballots_cast = election.turnout();
+        let turnout_percent = if electorate_size == 0 {
+            0.0
+        } else {
+            ballots_cast as f64 * 100.0 / electorate_size as f64
+        };
+
+        let turnout_buckets = schema.turnout_buckets(&query.election_id);
+        let buckets_proof = turnout_buckets.get_multiproof(turnout_buckets.keys());
+        let mut buckets: Vec<TurnoutBucket> = turnout_buckets.values().collect();
+        buckets.sort_by_key(|bucket| bucket.height());
+
+        let block_proof = general_schema
+            .block_and_precommits(Height(max_height))
+            .unwrap();
+        let to_table: MapProof<Hash, Hash> =
+            general_schema.get_proof_to_service_table(VOTE_SERVICE, 0);
+
+        Ok(ElectionStats {
+            electorate_size,
+            ballots_cast,
+            turnout_percent: turnout_percent as f32,
+            // Every registered elector casts one ballot at most.
+            abstentions: electorate_size.saturating_sub(ballots_cast),
+            election,
+            election_proof,
+            buckets,
+            buckets_proof,
+            block_proof,
+            to_table,
+        })
+    }
+
+    pub fn irv_results(state: &ServiceApiState, query: ElectionQuery) -> api::Result<IrvResults> {
+        let schema = VoteSchema::new(state.snapshot());
+        let election = schema.election(&query.election_id)
+            .ok_or_else(|| api::Error::NotFound("Election not found".to_owned()))?;
+        if election.voting_scheme() != VotingScheme::RankedChoice {
+            return Err(api::Error::BadRequest("Election is not ranked-choice".to_owned()));
+        }
+        let election_proof = schema.elections().get_proof(query.election_id);
+        let phase = election.phase(schema.height());
+
+        let candidates: Vec<PublicKey> = schema.candidates(&query.election_id).values()
+            .filter(Candidate::is_active)
+            .map(|candidate| *candidate.pub_key())
+            .collect();
+        let delegated = Self::get_delegated_weights(&schema, &election);
+        let ballots = Self::get_weighted_ballots(&schema, &query.election_id, &delegated);
+        let ballots_idx = schema.ballots(&query.election_id);
+        let ballots_proof = ballots_idx.get_multiproof(ballots_idx.keys());
+        let delegations_proof = Self::get_delegations_proof(&schema, &query.election_id);
+
+        let outcome = tally::instant_runoff(&candidates, &ballots);
+
+        Ok(IrvResults {
+            election,
+            phase,
+            is_final: phase == ElectionPhase::Closed,
+            election_proof,
+            ballots_proof,
+            delegations_proof,
+            rounds: outcome.rounds,
+            winner: outcome.winner,
+        })
+    }
+
+    fn get_seats(
+        schema: &VoteSchema<Box<dyn Snapshot>>,
+        election: &Election,
+        delegated_voices: &BTreeMap<PublicKey, u64>,
+        decrypted: Option<&BTreeMap<PublicKey, u64>>,
+    ) -> Option<Seats> {
+        // The withdrawn candidates don't take seats, their votes are void.
+        let candidates: Vec<Candidate> = schema.candidates(election.id()).values()
+            .filter(Candidate::is_active)
+            .collect();
+        match election.voting_scheme() {
+            // The instant-runoff goes through all the ballots, it's run by `v1/results/irv` only.
+            VotingScheme::RankedChoice => None,
+            VotingScheme::Plurality | VotingScheme::Approval | VotingScheme::CommitReveal
+            | VotingScheme::Anonymous | VotingScheme::Token => {
+                let tallies: Vec<CandidateTally> = candidates.iter()
+                    .map(|c| CandidateTally {
+                        pub_key: *c.pub_key(),
+                        votes: c.voices() + delegated_voices.get(c.pub_key()).cloned().unwrap_or(0),
+                    })
+                    .collect();
+                Some(tally::top_candidates(&tallies, election.seats()))
+            }
+            VotingScheme::Encrypted => {
+                let tallies: Vec<CandidateTally> = candidates.iter()
+                    .map(|c| CandidateTally {
+                        pub_key: *c.pub_key(),
+                        votes: decrypted.and_then(|voices| voices.get(c.pub_key()).cloned()).unwrap_or(0),
+                    })
+                    .collect();
+                Some(tally::top_candidates(&tallies, election.seats()))
+            }
+        }
+    }
+
+    /// Returns the decrypted sums of the encrypted votes.
+    ///
+    /// Returns `None` until the threshold number of trustees has submitted the decryption shares,
+    /// then the sums are decrypted on chain into the voices of the candidates.
+    fn get_decrypted_voices(schema: &VoteSchema<Box<dyn Snapshot>>, election: &Election) -> Option<BTreeMap<PublicKey, u64>> {
+        if election.voting_scheme() != VotingScheme::Encrypted || !election.decrypted() {
+            return None;
+        }
+        Some(schema.candidates(election.id()).values().map(|candidate| (*candidate.pub_key(), candidate.voices())).collect())
+    }
+
+    fn get_weighted_ballots(
+        schema: &VoteSchema<Box<dyn Snapshot>>,
+        election_id: &Hash,
+        delegated: &BTreeMap<PublicKey, u64>,
+    ) -> Vec<WeightedBallot> {
+        schema.ballots(election_id).values()
+            .map(|ballot| {
+                let elector = schema.elector(election_id, ballot.elector()).expect("Elector of the ballot doesn't exist.");
+                let weight = elector.weight() + delegated.get(ballot.elector()).cloned().unwrap_or(0);
+                WeightedBallot { weight, choices: ballot.choices() }
+            })
+            .collect()
+    }
+
+    /// Returns the weight delegated to the electors who have voted.
+    fn get_delegated_weights(schema: &VoteSchema<Box<dyn Snapshot>>, election: &Election) -> BTreeMap<PublicKey, u64> {
+        let election_id = election.id();
+        tally::delegated_weights(
+            election,
+            schema.delegations(election_id).keys(),
+            |elector| schema.delegation(election_id, elector).map(|d| *d.to()),
+            |elector| schema.elector(election_id, elector),
+        )
+    }
+
+    /// Returns the voices given to the candidates by the delegated weight.
+    fn get_delegated_voices(
+        schema: &VoteSchema<Box<dyn Snapshot>>,
+        election: &Election,
+        delegated: &BTreeMap<PublicKey, u64>,
+    ) -> BTreeMap<PublicKey, u64> {
+        let mut voices = BTreeMap::new();
+        for (voter, weight) in delegated {
+            let choices = match election.voting_scheme() {
+                VotingScheme::Approval => schema.ballot(election.id(), voter)
+                    .expect("Approval ballot doesn't exist.")
+                    .choices(),
+                _ => vec![*schema.elector(election.id(), voter).unwrap().candidate()],
+            };
+            for candidate in choices {
+                *voices.entry(candidate).or_insert(0) += weight;
+            }
+        }
+        voices
+    }
+
+    fn get_delegations_proof(schema: &VoteSchema<Box<dyn Snapshot>>, election_id: &Hash) -> MapProof<PublicKey, Delegation> {
+        let delegations = schema.delegations(election_id);
+        delegations.get_multiproof(delegations.keys())
+    }
+
+    pub fn get_delegations(state: &ServiceApiState, query: ProofPageQuery<PublicKey>) -> api::Result<DelegationPage> {
+        let schema = Self::election_schema(state, &query.election_id)?;
+        let election = schema.election(&query.election_id).unwrap();
+        let idx = schema.delegations(&query.election_id);
+        let delegations = Self::get_proof_page(&idx, query.from, query.limit)?;
+
+        // The pages together prove all the electors in the delegation chains, which resolve the delegated weight.
+        let records = match query.from {
+            Some(ref key) => idx.iter_from(key),
+            None => idx.iter(),
+        };
+        let page: Vec<Delegation> = records
+            .take_while(|(key, _)| Some(key) != delegations.next.as_ref())
+            .map(|(_, delegation)| delegation)
+            .collect();
+        let electors: BTreeSet<PublicKey> = page.iter()
+            .flat_map(|delegation| vec![*delegation.from(), *delegation.to()])
+            .collect();
+        let electors_proof = schema.electorate(&query.election_id).get_multiproof(electors);
+        // The delegated weight of an approval election goes to all the choices of the voter.
+        let delegates: BTreeSet<PublicKey> = if election.voting_scheme() == VotingScheme::Approval {
+            page.iter().map(|delegation| *delegation.to()).collect()
+        } else {
+            BTreeSet::new()
+        };
+        let ballots_proof = schema.ballots(&query.election_id).get_multiproof(delegates);
+
+        Ok(DelegationPage { delegations, electors_proof, ballots_proof })
+    }
+
     fn get_candidate_info(
-        schema: &VoteSchema<Box<Snapshot>>,
-        general_schema: &GeneralSchema<&Box<Snapshot>>,
+        schema: &VoteSchema<Box<dyn Snapshot>>,
+        election_id: &Hash,
         candidate: Candidate,
-        total_votes_number: u64,
+        delegated_voices: u64,
+        decrypted_voices: Option<u64>,
+        total_weight: u64,
     ) -> CandidateInfo {
-        let history = schema.vote_history(&candidate.pub_key());
-        let history_proof = history.get_range_proof(0, history.len());
+        let history_root_proof = schema.history_roots().get_proof(history_id(election_id, candidate.pub_key()));
 
-        let transactions: Vec<VoteTransactions> = history
-            .iter()
-            .map(|record| general_schema.transactions().get(&record).unwrap())
-            .map(|raw| VoteTransactions::tx_from_raw(raw).unwrap())
-            .collect::<Vec<_>>();
-
-        let percent = candidate.voices() as f64 / total_votes_number as f64 * 100.0;
+        // Nothing is counted yet, e.g. before the commit-reveal votes are revealed.
+        let percent = if total_weight == 0 {
+            0.0
+        } else {
+            let voices = decrypted_voices.unwrap_or_else(|| candidate.voices()) + delegated_voices;
+            voices as f64 / total_weight as f64 * 100.0
+        };
 
-        let to_candidate_proof = schema.candidates().get_proof(*candidate.pub_key());
+        let to_candidate_proof = schema.candidates(election_id).get_proof(*candidate.pub_key());
 
         CandidateInfo {
             candidate,
-            history: VotingHistory {
-                transactions,
-                history_proof,
-            },
+            delegated_voices,
+            decrypted_voices,
             vote_percent: percent as f32,
             proof: to_candidate_proof,
+            history_root_proof,
+        }
+    }
+
+    pub fn get_history(state: &ServiceApiState, query: HistoryQuery) -> api::Result<VotingHistory> {
+        let snapshot = state.snapshot();
+        let general_schema = GeneralSchema::new(&snapshot);
+
+        let schema = VoteSchema::new(state.snapshot());
+        // The history moves along with the rotated key of the candidate.
+        let pub_key = schema.resolve_key(&query.election_id, &query.pub_key);
+        if schema.candidate(&query.election_id, &pub_key).is_none() {
+            return Err(api::Error::NotFound("Candidate not found".to_owned()));
         }
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if limit == 0 || limit > MAX_PAGE_SIZE {
+            return Err(api::Error::BadRequest(format!("The limit must be from 1 to {}", MAX_PAGE_SIZE)));
+        }
+
+        let history = schema.vote_history(&query.election_id, &pub_key);
+        let length = history.len();
+        let from = query.from.unwrap_or(0);
+        if from >= length {
+            return Err(api::Error::BadRequest("The history is shorter".to_owned()));
+        }
+        let to = length.min(from + u64::from(limit));
+        let history_proof = history.get_range_proof(from, to);
+
+        let transactions = (from..to)
+            .filter_map(|index| history.get(index))
+            .map(|tx_hash| general_schema.transactions().get(&tx_hash).unwrap())
+            .map(|raw| VoteTransactions::tx_from_raw(raw).unwrap())
+            .collect();
+
+        Ok(VotingHistory {
+            from,
+            length,
+            transactions,
+            history_proof,
+        })
+    }
+
+    pub fn get_revocations(state: &ServiceApiState, query: RevocationsQuery) -> api::Result<RevocationLog> {
+        let schema = VoteSchema::new(state.snapshot());
+        if schema.election(&query.election_id).is_none() {
+            return Err(api::Error::NotFound("Election not found".to_owned()));
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if limit == 0 || limit > MAX_PAGE_SIZE {
+            return Err(api::Error::BadRequest(format!("The limit must be from 1 to {}", MAX_PAGE_SIZE)));
+        }
+
+        let log = schema.revocations(&query.election_id);
+        let length = log.len();
+        let from = query.from.unwrap_or(0);
+        if from >= length {
+            return Err(api::Error::BadRequest("The audit trail is shorter".to_owned()));
+        }
+        let to = length.min(from + u64::from(limit));
+
+        Ok(RevocationLog {
+            from,
+            length,
+            revocations: (from..to).filter_map(|index| log.get(index)).collect(),
+            proof: log.get_range_proof(from, to),
+        })
+    }
+
+    pub fn get_ring(state: &ServiceApiState, query: RingQuery) -> api::Result<SubRing> {
+        let schema = VoteSchema::new(state.snapshot());
+        let election = match schema.election(&query.election_id) {
+            Some(election) => election,
+            None => return Err(api::Error::NotFound("Election not found".to_owned())),
+        };
+        if election.voting_scheme() != VotingScheme::Anonymous {
+            return Err(api::Error::BadRequest("The election isn't anonymous".to_owned()));
+        }
+
+        let ring_len = schema.ring_len(&query.election_id);
+        let ring_size = u64::from(election.ring_size());
+        let (start, end) = sub_ring_bounds(ring_len, ring_size, query.index)
+            .ok_or_else(|| api::Error::NotFound("Sub-ring not found".to_owned()))?;
+        let members = schema.sub_ring(&query.election_id, query.index).unwrap_or_default();
+        let ring = schema.ring(&query.election_id);
+        let proof = if ring.is_empty() { None } else { Some(ring.get_range_proof(start, end)) };
+        Ok(SubRing {
+            index: query.index,
+            rings: (ring_len / ring_size).max(1),
+            members,
+            proof,
+        })
     }
 
     pub fn post_candidate(state: &ServiceApiState, query: VoteTransactions) -> api::Result<TransactionResponse> {
@@ -119,61 +780,355 @@ impl PublicApi {
         Ok(TransactionResponse { tx_hash })
     }
 
+    pub fn get_election(state: &ServiceApiState, query: ElectionQuery) -> api::Result<Election> {
+        let schema = VoteSchema::new(state.snapshot());
+        schema.election(&query.election_id)
+            .ok_or_else(|| api::Error::NotFound("Election not found".to_owned()))
+    }
+
+    pub fn get_elections(state: &ServiceApiState, _query: ()) -> api::Result<Vec<Election>> {
+        let snapshot = state.snapshot();
+        let schema = VoteSchema::new(snapshot);
+        let idx = schema.elections();
+        let elections = idx.values().collect();
+        Ok(elections)
+    }
+
+    pub fn get_registrars(state: &ServiceApiState, _query: ()) -> api::Result<Vec<PublicKey>> {
+        let schema = VoteSchema::new(state.snapshot());
+        schema.config()
+            .map(|config| config.registrars)
+            .ok_or_else(|| api::Error::InternalError("Invalid vote service configuration".into()))
+    }
+
     pub fn get_elector(state: &ServiceApiState, query: ElectorQuery) -> api::Result<Elector> {
         let schema = VoteSchema::new(state.snapshot());
-        schema.elector(&query.pub_key)
+        schema.elector(&query.election_id, &query.pub_key)
             .ok_or_else(|| api::Error::NotFound("Elector not found".to_owned()))
     }
 
+    pub fn get_token_session(state: &ServiceApiState, query: ElectorQuery) -> api::Result<TokenSession> {
+        let schema = VoteSchema::new(state.snapshot());
+        schema.token_session(&query.election_id, &query.pub_key)
+            .ok_or_else(|| api::Error::NotFound("Token session not found".to_owned()))
+    }
+
+    pub fn get_commitments(state: &ServiceApiState, query: ProofPageQuery<PublicKey>) -> api::Result<ProofPage<PublicKey, Commitment>> {
+        let schema = Self::election_schema(state, &query.election_id)?;
+        Self::get_proof_page(&schema.commitments(&query.election_id), query.from, query.limit)
+    }
+
+    pub fn get_key_shares(state: &ServiceApiState, query: ProofPageQuery<PublicKey>) -> api::Result<ProofPage<PublicKey, KeyShare>> {
+        let schema = Self::election_schema(state, &query.election_id)?;
+        Self::get_proof_page(&schema.key_shares(&query.election_id), query.from, query.limit)
+    }
+
+    pub fn get_decryptions(
+        state: &ServiceApiState,
+        query: ProofPageQuery<PublicKey>,
+    ) -> api::Result<ProofPage<PublicKey, PartialDecryption>> {
+        let schema = Self::election_schema(state, &query.election_id)?;
+        Self::get_proof_page(&schema.decryptions(&query.election_id), query.from, query.limit)
+    }
+
+    pub fn get_key_images(state: &ServiceApiState, query: ProofPageQuery<Hash>) -> api::Result<ProofPage<Hash, Hash>> {
+        let schema = Self::election_schema(state, &query.election_id)?;
+        Self::get_proof_page(&schema.key_images(&query.election_id), query.from, query.limit)
+    }
+
+    pub fn get_tokens(state: &ServiceApiState, query: ProofPageQuery<PublicKey>) -> api::Result<ProofPage<PublicKey, Hash>> {
+        let schema = Self::election_schema(state, &query.election_id)?;
+        Self::get_proof_page(&schema.tokens(&query.election_id), query.from, query.limit)
+    }
+
+    pub fn get_spent_tokens(state: &ServiceApiState, query: ProofPageQuery<PublicKey>) -> api::Result<ProofPage<PublicKey, Hash>> {
+        let schema = Self::election_schema(state, &query.election_id)?;
+        Self::get_proof_page(&schema.spent_tokens(&query.election_id), query.from, query.limit)
+    }
+
+    fn election_schema(state: &ServiceApiState, election_id: &Hash) -> api::Result<VoteSchema<Box<dyn Snapshot>>> {
+        let schema = VoteSchema::new(state.snapshot());
+        if schema.election(election_id).is_none() {
+            return Err(api::Error::NotFound("Election not found".to_owned()));
+        }
+        Ok(schema)
+    }
+
+    /// Proves up to `limit` records starting from the key `from`.
+    fn get_proof_page<K, V>(
+        idx: &ProofMapIndex<&dyn Snapshot, K, V>,
+        from: Option<K>,
+        limit: Option<u32>,
+    ) -> api::Result<ProofPage<K, V>>
+    where
+        K: ProofMapKey<Output = K>,
+        V: StorageValue,
+    {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if limit == 0 || limit > MAX_PAGE_SIZE {
+            return Err(api::Error::BadRequest(format!("The limit must be from 1 to {}", MAX_PAGE_SIZE)));
+        }
+
+        let mut keys = match from {
+            Some(ref key) => idx.keys_from(key),
+            None => idx.keys(),
+        };
+        let page: Vec<K> = keys.by_ref().take(limit as usize).collect();
+        let next = keys.next();
+        Ok(ProofPage { proof: idx.get_multiproof(page), next })
+    }
+
     pub fn get_candidate(state: &ServiceApiState, query: CandidateQuery) -> api::Result<Candidate> {
         let schema = VoteSchema::new(state.snapshot());
-        schema.candidate(&query.pub_key)
+        schema.candidate(&query.election_id, &query.pub_key)
             .ok_or_else(|| api::Error::NotFound("Candidate not found".to_owned()))
     }
 
-    pub fn get_block_number(state: &ServiceApiState, query: ElectorQuery) -> api::Result<Height> {
-        let core_schema = Schema::new(state.snapshot());
-        let tx_list = core_schema.transactions();
+    pub fn get_block_number(state: &ServiceApiState, query: ElectorQuery) -> api::Result<VoteLocation> {
+        let snapshot = state.snapshot();
+        let general_schema = GeneralSchema::new(&snapshot);
 
-        let tx = tx_list.iter()
-            .map(|row| (row.0, VoteTransactions::tx_from_raw(row.1)))
-            .find(|tx| {
-                if let Ok(tx) = &tx.1 {
-                    match &tx {
-                        VoteTransactions::Vote(vote) => &query.pub_key == vote.elector(),
-                        _ => false,
-                    }
-                } else {
-                    false
-                }
-            }).map(|tx| tx.0)
-            .and_then(|hash| BlockchainExplorer::new(state.blockchain()).transaction(&hash))
-            .ok_or(api::Error::NotFound("Transaction not found".to_owned()))?;
+        let schema = VoteSchema::new(state.snapshot());
+        let pub_key = schema.resolve_key(&query.election_id, &query.pub_key);
+        let record = schema.vote_record(&query.election_id, &pub_key)
+            .ok_or_else(|| api::Error::NotFound("Transaction not found".to_owned()))?;
+        let proof = schema.vote_records(&query.election_id).get_proof(pub_key);
+        let election_proof = schema.elections().get_proof(query.election_id);
 
-        if let TransactionInfo::Committed(tx) = tx {
-            Ok(tx.location().block_height())
-        } else {
-            Err(api::Error::NotFound("Block not found".to_owned()))
+        let max_height = general_schema.block_hashes_by_height().len() - 1;
+        let block_proof = general_schema
+            .block_and_precommits(Height(max_height))
+            .unwrap();
+        let to_table: MapProof<Hash, Hash> =
+            general_schema.get_proof_to_service_table(VOTE_SERVICE, 0);
+
+        Ok(VoteLocation {
+            pub_key,
+            height: Height(record.height()),
+            tx_hash: *record.tx_hash(),
+            proof,
+            election_proof,
+            block_proof,
+            to_table,
+        })
+    }
+
+    pub fn get_receipt(state: &ServiceApiState, query: ReceiptQuery) -> api::Result<VoteReceipt> {
+        let snapshot = state.snapshot();
+        let general_schema = GeneralSchema::new(&snapshot);
+
+        let schema = VoteSchema::new(state.snapshot());
+        let election = schema.election(&query.election_id)
+            .ok_or_else(|| api::Error::NotFound("Election not found".to_owned()))?;
+        let pub_key = schema.resolve_key(&query.election_id, &query.pub_key);
+        let elector = schema.elector(&query.election_id, &pub_key)
+            .ok_or_else(|| api::Error::NotFound("Elector not found".to_owned()))?;
+        if elector.has_vote() {
+            return Err(api::Error::NotFound("The elector hasn't voted".to_owned()));
         }
+        // The ballots with several candidates don't set the candidate of the elector record.
+        let candidate = query.candidate.map(|key| schema.resolve_key(&query.election_id, &key));
+        let candidate_key = match (candidate, *elector.candidate()) {
+            (None, key) if key == PublicKey::zero() => {
+                return Err(api::Error::BadRequest("The candidate of the ballot must be given".to_owned()));
+            }
+            (Some(key), elected) if elected != PublicKey::zero() && key != elected => {
+                return Err(api::Error::NotFound("The ballot isn't counted for the candidate".to_owned()));
+            }
+            (Some(key), _) | (None, key) => key,
+        };
+        let candidate = schema.candidate(&query.election_id, &candidate_key)
+            .ok_or_else(|| api::Error::NotFound("Candidate not found".to_owned()))?;
+
+        // A committed ballot is counted when it is revealed.
+        let revealed = election.voting_scheme() == VotingScheme::CommitReveal;
+        let history = schema.vote_history(&query.election_id, &candidate_key);
+        let (position, transaction) = history.iter()
+            .enumerate()
+            .filter(|(_, tx_hash)| revealed || tx_hash == elector.ballot())
+            .filter_map(|(position, tx_hash)| {
+                let raw = general_schema.transactions().get(&tx_hash)?;
+                Some((position as u64, VoteTransactions::tx_from_raw(raw).ok()?))
+            })
+            .find(|(_, transaction)| match *transaction {
+                VoteTransactions::RevealVote(ref reveal) => reveal.elector() == &pub_key,
+                _ => !revealed,
+            })
+            .ok_or_else(|| api::Error::NotFound("The ballot isn't in the vote history of the candidate".to_owned()))?;
+        let history_length = history.len();
+        let history_proof = history.get_proof(position);
+
+        let proof = schema.electorate(&query.election_id).get_proof(pub_key);
+        let candidate_proof = schema.candidates(&query.election_id).get_proof(candidate_key);
+        let election_proof = schema.elections().get_proof(query.election_id);
+
+        let max_height = general_schema.block_hashes_by_height().len() - 1;
+        let block_proof = general_schema
+            .block_and_precommits(Height(max_height))
+            .unwrap();
+        let to_table: MapProof<Hash, Hash> =
+            general_schema.get_proof_to_service_table(VOTE_SERVICE, 0);
+
+        Ok(VoteReceipt {
+            elector,
+            proof,
+            transaction,
+            candidate,
+            candidate_proof,
+            position,
+            history_length,
+            history_proof,
+            election,
+            election_proof,
+            block_proof,
+            to_table,
+        })
     }
 
-    pub fn get_candidates(state: &ServiceApiState, _query: ()) -> api::Result<Vec<Candidate>> {
+    pub fn get_ballot_log(state: &ServiceApiState, query: BallotLogQuery) -> api::Result<BallotLog> {
+        let snapshot = state.snapshot();
+        let general_schema = GeneralSchema::new(&snapshot);
+
+        let schema = VoteSchema::new(state.snapshot());
+        let log = schema.ballot_log();
+        let length = log.len();
+        if query.from >= query.to || query.to > length {
+            return Err(api::Error::BadRequest("Invalid range of the ballot log".to_owned()));
+        }
+        let transactions = (query.from..query.to).filter_map(|index| log.get(index)).collect();
+        let proof = log.get_range_proof(query.from, query.to);
+
+        let max_height = general_schema.block_hashes_by_height().len() - 1;
+        let block_proof = general_schema
+            .block_and_precommits(Height(max_height))
+            .unwrap();
+        let to_table: MapProof<Hash, Hash> =
+            general_schema.get_proof_to_service_table(VOTE_SERVICE, 2);
+
+        Ok(BallotLog {
+            length,
+            transactions,
+            proof,
+            block_proof,
+            to_table,
+        })
+    }
+
+    pub fn get_candidates(state: &ServiceApiState, query: CandidatesQuery) -> api::Result<Page<Candidate>> {
         let snapshot = state.snapshot();
         let schema = VoteSchema::new(snapshot);
-        let idx = schema.candidates();
-        let candidates = idx.values().collect();
-        Ok(candidates)
+        let idx = schema.candidates(&query.election_id);
+        let name_prefix = query.name_prefix.unwrap_or_default();
+        Self::get_page(&idx, query.from, query.limit, |candidate| candidate.name().starts_with(&name_prefix))
+    }
+
+    pub fn get_electors(state: &ServiceApiState, query: ElectorsQuery) -> api::Result<Page<Elector>> {
+        let snapshot = state.snapshot();
+        let schema = VoteSchema::new(snapshot);
+        let idx = schema.electorate(&query.election_id);
+        let name_prefix = query.name_prefix.unwrap_or_default();
+        let has_vote = query.has_vote;
+        Self::get_page(&idx, query.from, query.limit, |elector| {
+            let vote_matches = match has_vote {
+                Some(value) => elector.has_vote() == value,
+                None => true,
+            };
+            vote_matches && elector.name().starts_with(&name_prefix)
+        })
+    }
+
+    /// Returns up to `limit` matching values starting from the key `from`.
+    ///
+    /// At most `limit * SCANNED_PER_RECORD` records are scanned, so a page of a rare filter
+    /// may be short or empty while `next` points to the rest of the index.
+    fn get_page<V, F>(
+        idx: &ProofMapIndex<&dyn Snapshot, PublicKey, V>,
+        from: Option<PublicKey>,
+        limit: Option<u32>,
+        filter: F,
+    ) -> api::Result<Page<V>>
+    where
+        V: StorageValue,
+        F: Fn(&V) -> bool,
+    {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if limit == 0 || limit > MAX_PAGE_SIZE {
+            return Err(api::Error::BadRequest(format!("The limit must be from 1 to {}", MAX_PAGE_SIZE)));
+        }
+
+        let iter = match from {
+            Some(ref key) => idx.iter_from(key),
+            None => idx.iter(),
+        };
+        let limit = limit as usize;
+        let mut items = Vec::new();
+        let mut next = None;
+        for (scanned, (key, value)) in iter.enumerate() {
+            if scanned == limit * SCANNED_PER_RECORD {
+                next = Some(key);
+                break;
+            }
+            if filter(&value) {
+                if items.len() == limit {
+                    next = Some(key);
+                    break;
+                }
+                items.push(value);
+            }
+        }
+        Ok(Page { items, next })
     }
 
     pub fn wire(builder: &mut ServiceApiBuilder) {
         builder.public_scope()
             .endpoint("v1/results", Self::results)
+            .endpoint("v1/stats", Self::stats)
+            .endpoint("v1/results/irv", Self::irv_results)
+            .endpoint("v1/results/delegations", Self::get_delegations)
+            .endpoint("v1/election", Self::get_election)
+            .endpoint("v1/elections", Self::get_elections)
+            .endpoint("v1/registrars", Self::get_registrars)
             .endpoint("v1/elector", Self::get_elector)
+            .endpoint("v1/elector/receipt", Self::get_receipt)
+            .endpoint("v1/elector/revocations", Self::get_revocations)
             .endpoint("v1/candidate", Self::get_candidate)
             .endpoint("v1/candidates", Self::get_candidates)
+            .endpoint("v1/candidate/history", Self::get_history)
+            .endpoint("v1/electors", Self::get_electors)
             .endpoint("v1/vote/block", Self::get_block_number)
+            .endpoint("v1/ballots", Self::get_ballot_log)
+            .endpoint("v1/election/ring", Self::get_ring)
+            .endpoint("v1/token/session", Self::get_token_session)
+            .endpoint("v1/election/commitments", Self::get_commitments)
+            .endpoint("v1/election/key_shares", Self::get_key_shares)
+            .endpoint("v1/election/decryptions", Self::get_decryptions)
+            .endpoint("v1/election/key_images", Self::get_key_images)
+            .endpoint("v1/token/issued", Self::get_tokens)
+            .endpoint("v1/token/spent", Self::get_spent_tokens)
+            .endpoint_mut("v1/election", Self::post_candidate)
             .endpoint_mut("v1/candidate", Self::post_candidate)
             .endpoint_mut("v1/elector", Self::post_candidate)
-            .endpoint_mut("v1/vote", Self::post_candidate);
+            .endpoint_mut("v1/elector/register", Self::post_candidate)
+            .endpoint_mut("v1/vote", Self::post_candidate)
+            .endpoint_mut("v1/vote/change", Self::post_candidate)
+            .endpoint_mut("v1/vote/revoke", Self::post_candidate)
+            .endpoint_mut("v1/vote/ranked", Self::post_candidate)
+            .endpoint_mut("v1/vote/approval", Self::post_candidate)
+            .endpoint_mut("v1/vote/commit", Self::post_candidate)
+            .endpoint_mut("v1/vote/reveal", Self::post_candidate)
+            .endpoint_mut("v1/delegate", Self::post_candidate)
+            .endpoint_mut("v1/vote/encrypted", Self::post_candidate)
+            .endpoint_mut("v1/vote/anonymous", Self::post_candidate)
+            .endpoint_mut("v1/token/commit", Self::post_candidate)
+            .endpoint_mut("v1/token/issue", Self::post_candidate)
+            .endpoint_mut("v1/vote/token", Self::post_candidate)
+            .endpoint_mut("v1/election/key", Self::post_candidate)
+            .endpoint_mut("v1/election/key/complaint", Self::post_candidate)
+            .endpoint_mut("v1/election/decryption", Self::post_candidate)
+            .endpoint_mut("v1/candidate/withdraw", Self::post_candidate)
+            .endpoint_mut("v1/candidate/disqualify", Self::post_candidate)
+            .endpoint_mut("v1/elector/revoke", Self::post_candidate)
+            .endpoint_mut("v1/key/rotate", Self::post_candidate)
+            .endpoint_mut("v1/elector/register/batch", Self::post_candidate);
     }
-}
\ No newline at end of file
+}
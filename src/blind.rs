@@ -0,0 +1,182 @@
+//! Clause blind Schnorr signatures of the registrars over the voting tokens.
+//!
+//! A token is the public key of a one-time key pair, the ballot signed by the key spends the token.
+//! The registrar signs the token without seeing it, so the spent token can't be linked to the elector
+//! it was issued to. The tokens are signed by the issuing key `X = x * B` of the registrar for the election,
+//! which is derived from the service key of the registrar, the service key itself never signs blindly.
+//! The issuance:
+//!
+//! 1. The registrar picks the fresh random nonces `k_0, k_1` for the elector and commits to them
+//!    with `R_i = k_i * B` in `CommitToken`.
+//! 2. The elector blinds the token for both commitments: picks `a_i, b_i`, computes
+//!    `R'_i = R_i + a_i * B + b_i * X`, `c'_i = H(R'_i, X, election, token)` and sends the blinded
+//!    challenges `c_i = c'_i + b_i`.
+//! 3. The registrar picks the clause `j` at random and responds with `s = k_j + c_j * x` in `IssueToken`,
+//!    the signature of the token is `(R'_j, s + a_j)`.
+//!
+//! The plain blind Schnorr signature is forged from concurrent sessions by solving the ROS problem,
+//! answering one of two clauses chosen by the registrar defeats the attack (Fuchsbauer, Plouviez and Seurin).
+//! The nonces are used once: `Session::respond` consumes them and the chain accepts a single response
+//! to the commitments for the elector.
+
+use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, edwards::EdwardsPoint, scalar::Scalar, traits::Identity};
+use exonum::crypto::{Hash, PublicKey, SecretKey};
+
+use elgamal::random_scalar;
+use lsag::{hash_to_edwards, hash_to_scalar, key_point, scalar_from_hash};
+
+/// Number of the clauses of a session, the registrar answers one of them.
+pub const CLAUSES: usize = 2;
+
+/// Issuing key of a registrar for an election.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssuingKey {
+    secret: Scalar,
+}
+
+impl IssuingKey {
+    /// Derives the issuing key of the election from the service key of the registrar.
+    pub fn new(registrar_key: &SecretKey, election: &Hash) -> Self {
+        IssuingKey {
+            secret: hash_to_scalar(&[b"blind-issuing-key", &registrar_key[..32], election.as_ref()]),
+        }
+    }
+
+    /// Returns the public issuing key registered by `CommitToken`.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::new((ED25519_BASEPOINT_POINT * self.secret).compress().to_bytes())
+    }
+}
+
+/// Nonces of the registrar for the token of an elector.
+#[derive(Debug)]
+pub struct Session {
+    nonces: [Scalar; CLAUSES],
+}
+
+impl Session {
+    /// Picks the fresh random nonces.
+    pub fn random() -> Self {
+        Session { nonces: [random_scalar(), random_scalar()] }
+    }
+
+    /// Returns the commitments to the nonces for `CommitToken`.
+    pub fn commitments(&self) -> Vec<Hash> {
+        self.nonces.iter()
+            .map(|nonce| Hash::new((ED25519_BASEPOINT_POINT * nonce).compress().to_bytes()))
+            .collect()
+    }
+
+    /// Signs the blinded challenge of a clause chosen at random, returns the clause and the response.
+    ///
+    /// The session is consumed, so the nonces never answer another challenge.
+    /// Returns `None` if the challenges aren't canonical scalars.
+    pub fn respond(self, issuing_key: &IssuingKey, challenges: &[Hash]) -> Option<(u8, Hash)> {
+        if challenges.len() != CLAUSES {
+            return None;
+        }
+        let challenges: Vec<Scalar> = challenges.iter().map(scalar_from_hash).collect::<Option<_>>()?;
+        let clause = random_scalar().to_bytes()[0] as usize % CLAUSES;
+        let response = self.nonces[clause] + challenges[clause] * issuing_key.secret;
+        Some((clause as u8, Hash::new(response.to_bytes())))
+    }
+}
+
+/// Blinding of a token by the elector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Blinding {
+    pub nonces: Vec<EdwardsPoint>,
+    pub factors: Vec<Scalar>,
+    pub challenges: Vec<Scalar>,
+}
+
+impl Blinding {
+    /// Blinds the token for the commitments of the registrar.
+    ///
+    /// Returns `None` if the issuing key or a commitment isn't a valid point,
+    /// or the number of the commitments isn't `CLAUSES`.
+    pub fn new(issuing_key: &PublicKey, commitments: &[Hash], election: &Hash, token: &PublicKey) -> Option<Self> {
+        if commitments.len() != CLAUSES {
+            return None;
+        }
+        let key = key_point(issuing_key)?;
+        let mut blinding = Blinding { nonces: Vec::new(), factors: Vec::new(), challenges: Vec::new() };
+        for commitment in commitments {
+            let commitment = hash_to_edwards(commitment)?;
+            let (factor, shift) = (random_scalar(), random_scalar());
+            let nonce = commitment + ED25519_BASEPOINT_POINT * factor + key * shift;
+            blinding.challenges.push(token_challenge(&nonce, issuing_key, election, token) + shift);
+            blinding.nonces.push(nonce);
+            blinding.factors.push(factor);
+        }
+        Some(blinding)
+    }
+
+    /// Returns the blinded challenges of the clauses to be signed by the registrar.
+    pub fn challenges(&self) -> Vec<Hash> {
+        self.challenges.iter().map(|challenge| Hash::new(challenge.to_bytes())).collect()
+    }
+
+    /// Unblinds the response of the registrar to the clause, returns the nonce and the response of the token signature.
+    pub fn unblind(&self, clause: u8, response: &Hash) -> Option<(Hash, Hash)> {
+        let clause = clause as usize;
+        let (nonce, factor) = (self.nonces.get(clause)?, self.factors.get(clause)?);
+        let response = scalar_from_hash(response)? + factor;
+        Some((Hash::new(nonce.compress().to_bytes()), Hash::new(response.to_bytes())))
+    }
+}
+
+/// Checks that the issuing key is a point of the prime order subgroup other than the identity
+/// and differs from the service key of the registrar.
+pub fn verify_issuing_key(issuing_key: &PublicKey, registrar: &PublicKey) -> bool {
+    match key_point(issuing_key) {
+        Some(key) => issuing_key != registrar && key.is_torsion_free() && key != EdwardsPoint::identity(),
+        None => false,
+    }
+}
+
+/// Checks that the commitments of the registrar are valid points.
+pub fn verify_commitments(commitments: &[Hash]) -> bool {
+    commitments.len() == CLAUSES && commitments.iter().all(|commitment| hash_to_edwards(commitment).is_some())
+}
+
+/// Verifies the response of the registrar to the blinded challenge of the clause.
+pub fn verify_blind_response(issuing_key: &PublicKey, commitments: &[Hash], challenges: &[Hash], clause: u8, response: &Hash) -> bool {
+    if commitments.len() != CLAUSES || challenges.len() != CLAUSES {
+        return false;
+    }
+    let clause = clause as usize;
+    let values = (
+        key_point(issuing_key),
+        commitments.get(clause).and_then(hash_to_edwards),
+        challenges.get(clause).and_then(scalar_from_hash),
+        scalar_from_hash(response),
+    );
+    match values {
+        (Some(key), Some(commitment), Some(challenge), Some(response)) => {
+            ED25519_BASEPOINT_POINT * response == commitment + key * challenge
+        }
+        _ => false,
+    }
+}
+
+/// Verifies the unblinded signature of the token by the issuing key.
+pub fn verify_token(issuing_key: &PublicKey, election: &Hash, token: &PublicKey, nonce: &Hash, response: &Hash) -> bool {
+    match (key_point(issuing_key), hash_to_edwards(nonce), scalar_from_hash(response)) {
+        (Some(key), Some(nonce), Some(response)) => {
+            let challenge = token_challenge(&nonce, issuing_key, election, token);
+            ED25519_BASEPOINT_POINT * response == nonce + key * challenge
+        }
+        _ => false,
+    }
+}
+
+fn token_challenge(nonce: &EdwardsPoint, issuing_key: &PublicKey, election: &Hash, token: &PublicKey) -> Scalar {
+    hash_to_scalar(&[
+        b"blind-token",
+        nonce.compress().as_bytes(),
+        issuing_key.as_ref(),
+        election.as_ref(),
+        token.as_ref(),
+    ])
+}
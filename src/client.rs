@@ -0,0 +1,450 @@
+//! Verification of the voting results and ballot receipts without running a node.
+//!
+//! The results returned by `v1/results` of any node are checked against the consensus keys
+//! of the validators: the precommits of the block, the proof of the election table against
+//! the state hash of the block, the election record, the candidate records and the roots of their vote histories.
+//! The results must list all the candidates of the election, the delegated and the decrypted votes
+//! are recomputed from the proven records.
+//! The pages of `v1/candidate/history` are then checked against the verified candidate records.
+//! The receipts of `v1/elector/receipt` are checked the same way up to the ballot in the vote history.
+
+use std::collections::{BTreeMap, HashSet};
+
+use exonum::{
+    blockchain::{Block, BlockProof, Blockchain, Transaction},
+    crypto::{CryptoHash, Hash, PublicKey},
+    helpers::Height,
+    messages::Message,
+    storage::MapProof,
+};
+use serde_json;
+
+use api::{DelegationPage, HistoricalTally, VoteReceipt, VotingHistory, VotingResults};
+use config::VotingScheme;
+use schema::{history_id, Candidate, Election, ElectionPhase};
+use service::VOTE_SERVICE;
+use tally;
+use transactions::VoteTransactions;
+
+#[derive(Debug, Fail, PartialEq)]
+pub enum Error {
+    #[fail(display = "The results can't be parsed: {}", _0)]
+    InvalidJson(String),
+
+    #[fail(display = "The precommit isn't signed by a validator of the block")]
+    InvalidPrecommit,
+
+    #[fail(display = "The block isn't signed by the majority of the validators")]
+    NotEnoughPrecommits,
+
+    #[fail(display = "Invalid proof of the election table")]
+    InvalidTableProof,
+
+    #[fail(display = "Invalid proof of the election")]
+    InvalidElectionProof,
+
+    #[fail(display = "Invalid proof of the candidate {:?}", _0)]
+    InvalidCandidateProof(PublicKey),
+
+    #[fail(display = "The candidates of the results don't match the candidates of the election")]
+    IncompleteCandidates,
+
+    #[fail(display = "Invalid proof of the delegations")]
+    InvalidDelegationProof,
+
+    #[fail(display = "Invalid delegated votes of the candidate {:?}", _0)]
+    InvalidDelegatedVoices(PublicKey),
+
+    #[fail(display = "Invalid decrypted votes of the candidate {:?}", _0)]
+    InvalidDecryptedVoices(PublicKey),
+
+    #[fail(display = "Invalid vote history of the candidate {:?}", _0)]
+    InvalidHistoryProof(PublicKey),
+
+    #[fail(display = "Invalid checkpoints of the tally at the height")]
+    InvalidCheckpointProof,
+
+    #[fail(display = "Invalid proof of the elector {:?}", _0)]
+    InvalidElectorProof(PublicKey),
+
+    #[fail(display = "The ballot doesn't match the elector record")]
+    InvalidBallot,
+}
+
+/// Results whose proofs are checked up to the validator signatures.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedTally {
+    pub election: Hash,
+    pub height: Height,
+    pub phase: ElectionPhase,
+    /// Votes of the active candidates from the candidate records with the delegated votes, in the order of the results.
+    pub voices: Vec<(PublicKey, u64)>,
+    /// Tally at the height of the query.
+    pub historical: Option<VerifiedCheckpoint>,
+}
+
+/// Ballot of the elector counted for the candidate in the block at `height`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedReceipt {
+    pub election: Hash,
+    pub height: Height,
+    pub elector: PublicKey,
+    pub candidate: PublicKey,
+    pub tx_hash: Hash,
+}
+
+/// Tally as of the block at `height` whose checkpoints are checked against the verified records.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedCheckpoint {
+    pub height: Height,
+    pub turnout: u64,
+    pub total_weight: u64,
+    pub voices: Vec<(PublicKey, u64)>,
+}
+
+/// Parses the results and all the pages of `v1/results/delegations` in JSON and verifies them
+/// with the consensus keys of the validators.
+pub fn verify_results_json(json: &str, delegations: &[String], validators: &[PublicKey]) -> Result<VerifiedTally, Error> {
+    let results: VotingResults = serde_json::from_str(json).map_err(|e| Error::InvalidJson(e.to_string()))?;
+    let delegations = delegations.iter()
+        .map(|page| serde_json::from_str(page).map_err(|e| Error::InvalidJson(e.to_string())))
+        .collect::<Result<_, _>>()?;
+    verify_results(results, delegations, validators)
+}
+
+/// Verifies the results with the consensus keys of the validators, the keys are in the order of the validator ids.
+///
+/// The delegated votes are recomputed from all the pages of the delegations taken at the block of the results.
+pub fn verify_results(
+    results: VotingResults,
+    delegations: Vec<DelegationPage>,
+    validators: &[PublicKey],
+) -> Result<VerifiedTally, Error> {
+    verify_block(&results.block_proof, validators)?;
+    let block = results.block_proof.block;
+
+    let elections_root = table_root(results.to_table, &block, 0)?;
+    let history_roots = table_root(results.history_roots_to_table, &block, 1)?;
+
+    let election = results.election;
+    verify_election(&election, results.election_proof, elections_root)?;
+
+    let mut listed = verify_candidate_list(results.candidates_proof, &election)?;
+    let delegated = verify_delegated_voices(&election, delegations)?;
+    // The sums of the encrypted votes are decrypted on chain into the voices of the candidates.
+    let decrypted = election.voting_scheme() == VotingScheme::Encrypted && election.decrypted();
+
+    let mut voices = Vec::new();
+    let mut candidates = Vec::new();
+    for info in results.candidates.into_iter().chain(results.withdrawn) {
+        let candidate = info.candidate;
+        let pub_key = *candidate.pub_key();
+        verify_candidate(&candidate, info.proof, &election)?;
+        if listed.remove(&pub_key) != Some(candidate.hash()) {
+            return Err(Error::IncompleteCandidates);
+        }
+        if info.delegated_voices != delegated.get(&pub_key).cloned().unwrap_or(0) {
+            return Err(Error::InvalidDelegatedVoices(pub_key));
+        }
+        if info.decrypted_voices != Some(candidate.voices()).filter(|_| decrypted) {
+            return Err(Error::InvalidDecryptedVoices(pub_key));
+        }
+
+        let root_proof = info.history_root_proof.check().map_err(|_| Error::InvalidHistoryProof(pub_key))?;
+        let proven = root_proof.entries().into_iter()
+            .any(|(key, value)| *key == history_id(election.id(), &pub_key) && value == candidate.history_hash());
+        if root_proof.merkle_root() != history_roots || !proven {
+            return Err(Error::InvalidHistoryProof(pub_key));
+        }
+
+        candidates.push(candidate);
+    }
+    if !listed.is_empty() {
+        return Err(Error::IncompleteCandidates);
+    }
+    // The votes of the withdrawn candidates are void.
+    for candidate in candidates.iter().filter(|candidate| candidate.is_active()) {
+        let delegated = delegated.get(candidate.pub_key()).cloned().unwrap_or(0);
+        voices.push((*candidate.pub_key(), candidate.voices() + delegated));
+    }
+
+    let historical = match results.historical {
+        Some(tally) => Some(verify_checkpoint(tally, &election, &candidates, block.height())?),
+        None => None,
+    };
+
+    Ok(VerifiedTally {
+        election: *election.id(),
+        height: block.height(),
+        // The phase in the response isn't proven, it follows from the proven election and block.
+        phase: election.phase(block.height().next()),
+        voices,
+        historical,
+    })
+}
+
+/// Verifies the receipt of the elector with the consensus keys of the validators.
+pub fn verify_receipt_json(json: &str, validators: &[PublicKey]) -> Result<VerifiedReceipt, Error> {
+    let receipt: VoteReceipt = serde_json::from_str(json).map_err(|e| Error::InvalidJson(e.to_string()))?;
+    verify_receipt(receipt, validators)
+}
+
+/// Verifies that the ballot is signed by the elector, recorded in the elector record
+/// and appended to the vote history of the candidate counted in the block.
+pub fn verify_receipt(receipt: VoteReceipt, validators: &[PublicKey]) -> Result<VerifiedReceipt, Error> {
+    verify_block(&receipt.block_proof, validators)?;
+    let block = receipt.block_proof.block;
+    let elections_root = table_root(receipt.to_table, &block, 0)?;
+    let election = receipt.election;
+    verify_election(&election, receipt.election_proof, elections_root)?;
+
+    let elector = receipt.elector;
+    let pub_key = *elector.pub_key();
+    let proof = receipt.proof.check().map_err(|_| Error::InvalidElectorProof(pub_key))?;
+    let proven = proof.entries().into_iter()
+        .any(|(key, value)| *key == pub_key && value.hash() == elector.hash());
+    if proof.merkle_root() != *election.electorate_hash() || !proven {
+        return Err(Error::InvalidElectorProof(pub_key));
+    }
+
+    let candidate = receipt.candidate;
+    verify_candidate(&candidate, receipt.candidate_proof, &election)?;
+
+    // The elector record keeps the hash of the committed ballot, the revealed one is signed by the elector.
+    let revealed = match receipt.transaction {
+        VoteTransactions::RevealVote(ref reveal) => reveal.elector() == &pub_key,
+        _ => false,
+    };
+    let transaction: Box<dyn Transaction> = receipt.transaction.into();
+    let tx_hash = transaction.hash();
+    let chosen = *elector.candidate() == PublicKey::zero() || elector.candidate() == candidate.pub_key();
+    if !transaction.verify() || !(revealed || tx_hash == *elector.ballot()) || !chosen {
+        return Err(Error::InvalidBallot);
+    }
+
+    let entries = receipt.history_proof.validate(*candidate.history_hash(), receipt.history_length)
+        .map_err(|_| Error::InvalidHistoryProof(*candidate.pub_key()))?;
+    let counted = entries.len() == 1 && entries[0].0 == receipt.position && *entries[0].1 == tx_hash;
+    if !counted {
+        return Err(Error::InvalidHistoryProof(*candidate.pub_key()));
+    }
+
+    Ok(VerifiedReceipt {
+        election: *election.id(),
+        height: block.height(),
+        elector: pub_key,
+        candidate: *candidate.pub_key(),
+        tx_hash,
+    })
+}
+
+/// Returns the root of the service table proven against the state hash of the block.
+fn table_root(to_table: MapProof<Hash, Hash>, block: &Block, table: usize) -> Result<Hash, Error> {
+    let to_table = to_table.check().map_err(|_| Error::InvalidTableProof)?;
+    if to_table.merkle_root() != *block.state_hash() {
+        return Err(Error::InvalidTableProof);
+    }
+    let table_key = Blockchain::service_table_unique_key(VOTE_SERVICE, table);
+    to_table.entries().into_iter()
+        .find(|(key, _)| **key == table_key)
+        .map(|(_, root)| *root)
+        .ok_or(Error::InvalidTableProof)
+}
+
+fn verify_election(election: &Election, proof: MapProof<Hash, Election>, elections_root: Hash) -> Result<(), Error> {
+    let proof = proof.check().map_err(|_| Error::InvalidElectionProof)?;
+    let proven = proof.entries().into_iter()
+        .any(|(key, value)| key == election.id() && value.hash() == election.hash());
+    if proof.merkle_root() != elections_root || !proven {
+        return Err(Error::InvalidElectionProof);
+    }
+    Ok(())
+}
+
+fn verify_candidate(candidate: &Candidate, proof: MapProof<PublicKey, Candidate>, election: &Election) -> Result<(), Error> {
+    let pub_key = *candidate.pub_key();
+    let proof = proof.check().map_err(|_| Error::InvalidCandidateProof(pub_key))?;
+    let proven = proof.entries().into_iter()
+        .any(|(key, value)| *key == pub_key && value.hash() == candidate.hash());
+    if proof.merkle_root() != *election.candidates_hash() || !proven {
+        return Err(Error::InvalidCandidateProof(pub_key));
+    }
+    Ok(())
+}
+
+/// Returns the hashes of all the candidates of the election by the keys.
+///
+/// The proof of the candidates must be as long as the number of the candidates in the election record,
+/// so none of them can be left out of the results.
+fn verify_candidate_list(proof: MapProof<PublicKey, Candidate>, election: &Election) -> Result<BTreeMap<PublicKey, Hash>, Error> {
+    let proof = proof.check().map_err(|_| Error::IncompleteCandidates)?;
+    let listed: BTreeMap<PublicKey, Hash> = proof.entries().into_iter()
+        .filter(|(key, value)| *key == value.pub_key())
+        .map(|(key, value)| (*key, value.hash()))
+        .collect();
+    if proof.merkle_root() != *election.candidates_hash() || listed.len() as u64 != election.candidates_count() {
+        return Err(Error::IncompleteCandidates);
+    }
+    Ok(listed)
+}
+
+/// Recomputes the votes given to the candidates by the delegated weight.
+///
+/// The pages of the delegations must cover `delegations_count` of the election record,
+/// the records of the electors in the delegation chains are proven against the electorate.
+fn verify_delegated_voices(election: &Election, pages: Vec<DelegationPage>) -> Result<BTreeMap<PublicKey, u64>, Error> {
+    let mut delegates = BTreeMap::new();
+    let mut electors = BTreeMap::new();
+    let mut ballots = BTreeMap::new();
+    for page in pages {
+        let delegations = page.delegations.proof.check().map_err(|_| Error::InvalidDelegationProof)?;
+        if delegations.merkle_root() != *election.delegations_hash() {
+            return Err(Error::InvalidDelegationProof);
+        }
+        delegates.extend(delegations.entries().into_iter()
+            .filter(|(key, value)| *key == value.from())
+            .map(|(key, value)| (*key, *value.to())));
+
+        let records = page.electors_proof.check().map_err(|_| Error::InvalidDelegationProof)?;
+        if records.merkle_root() != *election.electorate_hash() {
+            return Err(Error::InvalidDelegationProof);
+        }
+        electors.extend(records.entries().into_iter()
+            .filter(|(key, value)| *key == value.pub_key())
+            .map(|(key, value)| (*key, value.clone())));
+
+        let proven = page.ballots_proof.check().map_err(|_| Error::InvalidDelegationProof)?;
+        if proven.merkle_root() != *election.ballots_hash() {
+            return Err(Error::InvalidDelegationProof);
+        }
+        ballots.extend(proven.entries().into_iter().map(|(key, ballot)| (*key, ballot.choices())));
+    }
+    let complete = delegates.iter().all(|(from, to)| electors.contains_key(from) && electors.contains_key(to));
+    if delegates.len() as u64 != election.delegations_count() || !complete {
+        return Err(Error::InvalidDelegationProof);
+    }
+
+    let weights = tally::delegated_weights(
+        election,
+        delegates.keys().cloned(),
+        |elector| delegates.get(elector).cloned(),
+        |elector| electors.get(elector).cloned(),
+    );
+
+    let mut voices = BTreeMap::new();
+    for (voter, weight) in weights {
+        let choices = match election.voting_scheme() {
+            VotingScheme::Approval => ballots.get(&voter).cloned().ok_or(Error::InvalidDelegationProof)?,
+            _ => vec![*electors[&voter].candidate()],
+        };
+        for candidate in choices {
+            *voices.entry(candidate).or_insert(0) += weight;
+        }
+    }
+    Ok(voices)
+}
+
+/// Checks that the block is signed by the majority of the validators.
+fn verify_block(block_proof: &BlockProof, validators: &[PublicKey]) -> Result<(), Error> {
+    let block = &block_proof.block;
+    let block_hash = block.hash();
+
+    let mut signed = HashSet::new();
+    for precommit in &block_proof.precommits {
+        let key = validators.get(precommit.validator().0 as usize).ok_or(Error::InvalidPrecommit)?;
+        if precommit.height() != block.height() || precommit.block_hash() != &block_hash
+            || !precommit.verify_signature(key) {
+            return Err(Error::InvalidPrecommit);
+        }
+        signed.insert(precommit.validator());
+    }
+    if signed.len() < validators.len() * 2 / 3 + 1 {
+        return Err(Error::NotEnoughPrecommits);
+    }
+    Ok(())
+}
+
+/// Verifies the tally at the height against the verified election and candidate records,
+/// the checkpoints are proven by the current block so the height can't be later than it.
+fn verify_checkpoint(
+    tally: HistoricalTally,
+    election: &Election,
+    candidates: &[Candidate],
+    proven_height: Height,
+) -> Result<VerifiedCheckpoint, Error> {
+    let height = tally.height;
+    if height > proven_height {
+        return Err(Error::InvalidCheckpointProof);
+    }
+
+    let entries = tally.turnout_proof.validate(*election.turnout_checkpoints_hash(), tally.length)
+        .map_err(|_| Error::InvalidCheckpointProof)?;
+    let heights: Vec<(u64, u64)> = entries.iter().map(|(index, checkpoint)| (*index, checkpoint.height())).collect();
+    let (turnout, total_weight) = match last_proven(&heights, tally.length, height)? {
+        Some(position) => (entries[position].1.turnout(), entries[position].1.total_weight()),
+        None => (0, 0),
+    };
+    if (turnout, total_weight) != (tally.turnout, tally.total_weight) || tally.candidates.len() != candidates.len() {
+        return Err(Error::InvalidCheckpointProof);
+    }
+
+    let mut voices = Vec::new();
+    for checkpointed in tally.candidates {
+        let candidate = candidates.iter()
+            .find(|candidate| *candidate.pub_key() == checkpointed.pub_key)
+            .ok_or(Error::InvalidCheckpointProof)?;
+        let entries = checkpointed.proof.validate(*candidate.checkpoints_hash(), checkpointed.length)
+            .map_err(|_| Error::InvalidCheckpointProof)?;
+        let heights: Vec<(u64, u64)> = entries.iter().map(|(index, checkpoint)| (*index, checkpoint.height())).collect();
+        let proven = last_proven(&heights, checkpointed.length, height)?
+            .map_or(0, |position| entries[position].1.voices());
+        if proven != checkpointed.voices {
+            return Err(Error::InvalidCheckpointProof);
+        }
+        voices.push((checkpointed.pub_key, proven));
+    }
+
+    Ok(VerifiedCheckpoint {
+        height,
+        turnout,
+        total_weight,
+        voices,
+    })
+}
+
+/// Returns the position of the last checkpoint at or before the height among the proven
+/// `(index, height)` pairs, the proof must show that the adjacent next checkpoint is made later.
+fn last_proven(entries: &[(u64, u64)], length: u64, height: Height) -> Result<Option<usize>, Error> {
+    match entries {
+        [(0, first), ..] if *first > height.0 => Ok(None),
+        [(index, first)] if *first <= height.0 && *index + 1 == length => Ok(Some(0)),
+        [(index, first), (next_index, next)] if *next_index == *index + 1 && *first <= height.0 && *next > height.0 => {
+            Ok(Some(0))
+        }
+        _ => Err(Error::InvalidCheckpointProof),
+    }
+}
+
+/// Parses a page of `v1/candidate/history` and verifies it against the verified candidate record.
+pub fn verify_history_json(candidate: &Candidate, json: &str) -> Result<Vec<Box<dyn Transaction>>, Error> {
+    let history: VotingHistory = serde_json::from_str(json).map_err(|e| Error::InvalidJson(e.to_string()))?;
+    verify_history(candidate, history)
+}
+
+/// Verifies a page of the vote history against the history hash of the candidate
+/// and returns the transactions of the page.
+pub fn verify_history(candidate: &Candidate, history: VotingHistory) -> Result<Vec<Box<dyn Transaction>>, Error> {
+    let pub_key = *candidate.pub_key();
+    let entries = history.history_proof.validate(*candidate.history_hash(), history.length)
+        .map_err(|_| Error::InvalidHistoryProof(pub_key))?;
+
+    let transactions: Vec<Box<dyn Transaction>> = history.transactions.into_iter().map(Into::into).collect();
+    let matches = entries.len() == transactions.len() && entries.iter()
+        .zip(&transactions)
+        .zip(history.from..)
+        .all(|(((index, hash), tx), expected)| *index == expected && **hash == tx.hash());
+    if !matches {
+        return Err(Error::InvalidHistoryProof(pub_key));
+    }
+    Ok(transactions)
+}
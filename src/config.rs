@@ -0,0 +1,225 @@
+use exonum::{
+    crypto::PublicKey,
+    encoding::serialize::FromHex,
+    helpers::fabric::{keys, CommandExtension, Context, Argument},
+};
+use failure;
+use toml::Value;
+
+use service::SERVICE_NAME;
+
+/// Scheme used to count the votes of an election.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum VotingScheme {
+    /// Every elector votes for a single candidate, the candidate with most votes wins.
+    Plurality = 0,
+    /// Every elector ranks the candidates, the winner is found by the instant-runoff.
+    RankedChoice = 1,
+    /// Every elector approves up to `max_choices` candidates, the most approved candidates win.
+    Approval = 2,
+    /// Plurality with secret ballots: electors commit to the vote and reveal it in the tallying phase.
+    CommitReveal = 3,
+    /// Ballots are encrypted, only the sums of the votes are decrypted by the validators
+    /// after the election is closed. Every elector chooses up to `max_choices` candidates.
+    Encrypted = 4,
+    /// Plurality with anonymous ballots signed by a linkable ring signature over a sub-ring of the electorate.
+    Anonymous = 5,
+    /// Plurality with ballots signed by one-time keys, certified by the blind signature of a registrar.
+    Token = 6,
+}
+
+impl VotingScheme {
+    pub fn from_u8(value: u8) -> Option<VotingScheme> {
+        match value {
+            0 => Some(VotingScheme::Plurality),
+            1 => Some(VotingScheme::RankedChoice),
+            2 => Some(VotingScheme::Approval),
+            3 => Some(VotingScheme::CommitReveal),
+            4 => Some(VotingScheme::Encrypted),
+            5 => Some(VotingScheme::Anonymous),
+            6 => Some(VotingScheme::Token),
+            _ => None,
+        }
+    }
+}
+
+/// Value of the `scheme` field of `CreateElection` standing for the scheme of the service configuration.
+pub const CONFIGURED_SCHEME: u8 = 255;
+
+impl Default for VotingScheme {
+    fn default() -> Self {
+        VotingScheme::Plurality
+    }
+}
+
+/// What happens to the votes for a withdrawn or disqualified candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum WithdrawnVotes {
+    /// The votes stay cast but aren't counted.
+    Void = 0,
+    /// The ballots are revoked, so the electors may vote again.
+    Return = 1,
+}
+
+impl WithdrawnVotes {
+    pub fn from_u8(value: u8) -> Option<WithdrawnVotes> {
+        match value {
+            0 => Some(WithdrawnVotes::Void),
+            1 => Some(WithdrawnVotes::Return),
+            _ => None,
+        }
+    }
+}
+
+impl Default for WithdrawnVotes {
+    fn default() -> Self {
+        WithdrawnVotes::Void
+    }
+}
+
+/// What happens to the cast vote of an elector whose eligibility is revoked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum RevokedVotes {
+    /// The vote cast before the revocation is counted.
+    Count = 0,
+    /// The vote and the delegation of the elector are taken back.
+    Void = 1,
+}
+
+impl RevokedVotes {
+    pub fn from_u8(value: u8) -> Option<RevokedVotes> {
+        match value {
+            0 => Some(RevokedVotes::Count),
+            1 => Some(RevokedVotes::Void),
+            _ => None,
+        }
+    }
+}
+
+impl Default for RevokedVotes {
+    fn default() -> Self {
+        RevokedVotes::Count
+    }
+}
+
+/// Configuration of the vote service.
+///
+/// The configuration is stored in the actual blockchain configuration
+/// and can be changed by the validators through the configuration service.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoteConfig {
+    /// Public keys which are allowed to register electors.
+    pub registrars: Vec<PublicKey>,
+    /// Minimal length of the candidate name in characters.
+    pub min_candidate_name_len: u32,
+    /// Maximal length of the candidate name in characters.
+    pub max_candidate_name_len: u32,
+    /// Scheme of the elections created with this configuration.
+    pub voting_scheme: VotingScheme,
+    /// Minimal number of blocks between the election creation and the voting phase.
+    pub min_registration_period: u64,
+    /// Minimal number of blocks in the voting phase.
+    pub min_voting_period: u64,
+    /// Number of blocks in a bucket of the turnout statistics.
+    pub turnout_bucket_size: u64,
+    /// Number of the last blocks of the registration in which the trustees of an `Encrypted` election
+    /// may complain about the dealings of the election key, but not deal.
+    pub key_complaint_period: u64,
+    /// Number of the electors in a sub-ring of an `Anonymous` election. The ring signature of a ballot
+    /// covers a single sub-ring, so its size is the anonymity set of the ballot.
+    pub ring_size: u32,
+    /// Whether the votes for a withdrawn candidate are void or returned to the electors
+    /// in the elections created with this configuration.
+    pub withdrawn_votes: WithdrawnVotes,
+    /// Whether the cast vote of a revoked elector is counted or void in the elections created with this configuration.
+    pub revoked_votes: RevokedVotes,
+}
+
+impl Default for VoteConfig {
+    fn default() -> Self {
+        VoteConfig {
+            registrars: Vec::new(),
+            min_candidate_name_len: 1,
+            max_candidate_name_len: 256,
+            voting_scheme: VotingScheme::default(),
+            min_registration_period: 1,
+            min_voting_period: 1,
+            turnout_bucket_size: 10,
+            key_complaint_period: 2,
+            ring_size: 16,
+            withdrawn_votes: WithdrawnVotes::default(),
+            revoked_votes: RevokedVotes::default(),
+        }
+    }
+}
+
+impl VoteConfig {
+    pub fn is_registrar(&self, pub_key: &PublicKey) -> bool {
+        self.registrars.contains(pub_key)
+    }
+
+    /// Reads the service configuration from the node configuration.
+    pub fn from_context(context: &Context) -> Result<VoteConfig, failure::Error> {
+        let node_config = context.get(keys::NODE_CONFIG)?;
+        match node_config.services_configs.get(SERVICE_NAME) {
+            Some(value) => Ok(value.clone().try_into()?),
+            None => Ok(VoteConfig::default()),
+        }
+    }
+}
+
+/// Adds the vote service configuration to the common configuration template.
+pub struct GenerateCommonConfig;
+
+impl CommandExtension for GenerateCommonConfig {
+    fn args(&self) -> Vec<Argument> {
+        vec![
+            Argument::new_named(
+                "VOTE_REGISTRARS",
+                false,
+                "Public keys of the elector registrars.",
+                None,
+                "vote-registrar",
+                true,
+            ),
+        ]
+    }
+
+    fn execute(&self, mut context: Context) -> Result<Context, failure::Error> {
+        let registrars = context.arg_multiple::<String>("VOTE_REGISTRARS")
+            .unwrap_or_default()
+            .iter()
+            .map(PublicKey::from_hex)
+            .collect::<Result<Vec<_>, _>>()?;
+        let config = VoteConfig { registrars, ..VoteConfig::default() };
+
+        let mut services_config = context.get(keys::SERVICES_CONFIG).unwrap_or_default();
+        services_config.insert(SERVICE_NAME.to_owned(), Value::try_from(config)?);
+        context.set(keys::SERVICES_CONFIG, services_config);
+        Ok(context)
+    }
+}
+
+/// Copies the vote service configuration from the common configuration to the node configuration.
+pub struct Finalize;
+
+impl CommandExtension for Finalize {
+    fn args(&self) -> Vec<Argument> {
+        Vec::new()
+    }
+
+    fn execute(&self, mut context: Context) -> Result<Context, failure::Error> {
+        let common_config = context.get(keys::COMMON_CONFIG)?;
+        let mut node_config = context.get(keys::NODE_CONFIG)?;
+
+        if let Some(value) = common_config.services_config.get(SERVICE_NAME) {
+            node_config.services_configs.insert(SERVICE_NAME.to_owned(), value.clone());
+        }
+        context.set(keys::NODE_CONFIG, node_config);
+        Ok(context)
+    }
+}
@@ -0,0 +1,242 @@
+//! Distributed generation of the election key shared by the trustees with a threshold.
+//!
+//! The trustees of an election are the validators at its creation. Every trustee deals
+//! a polynomial `f_i` of degree `t - 1`: it publishes the Feldman commitments `C_ik = a_ik * G`
+//! to the coefficients and the shares `f_i(j)` encrypted for every trustee `j`. A share is
+//! encrypted by adding the pad derived from the Diffie-Hellman key `e_i * P_j` of the ephemeral
+//! key `E_i = e_i * B` of the dealing and the Ed25519 key `P_j` of the trustee. The trustee
+//! whose share doesn't match the commitments complains by revealing the Diffie-Hellman key
+//! with a proof, and the dealer is disqualified.
+//!
+//! The election key is `Y = sum(C_i0)` over the qualified dealers, nobody knows its secret.
+//! The secret share of the trustee `j` is `x_j = sum(f_i(j))` and its public share
+//! `X_j = sum(C_ik * j^k)` is computed from the commitments, so the decryption shares
+//! `x_j * A` are checked against it. Any `t` decryption shares are combined with the
+//! Lagrange coefficients into the decryption `y * A`.
+//!
+//! Trustee `j` has the index `j + 1` in the polynomials, the index `0` is the secret.
+
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT,
+    edwards::EdwardsPoint,
+    ristretto::RistrettoPoint,
+    scalar::Scalar,
+    traits::Identity,
+};
+use exonum::crypto::{Hash, PublicKey, SecretKey};
+
+use elgamal::{self, EqualityProof, BASEPOINT};
+use lsag::{hash_to_edwards, hash_to_scalar, key_point, scalar_from_hash, secret_scalar};
+
+/// Dealing of a trustee's polynomial.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dealing {
+    /// Commitments to the coefficients, the first one is the key share of the dealer.
+    pub commitments: Vec<RistrettoPoint>,
+    /// Ephemeral key of the share encryption.
+    pub ephemeral: EdwardsPoint,
+    /// Encrypted shares of the trustees in the order of the trustees.
+    pub shares: Vec<Scalar>,
+    /// Proof of the knowledge of the secret of the first commitment.
+    pub proof: EqualityProof,
+}
+
+impl Dealing {
+    /// Deals the polynomial of the trustee for the election.
+    ///
+    /// The polynomial and the ephemeral key are derived from the service secret key,
+    /// so the trustee doesn't need to keep any additional state.
+    /// Returns `None` if a trustee key isn't a valid point.
+    pub fn new(
+        service_key: &SecretKey,
+        election: &Hash,
+        dealer: &PublicKey,
+        trustees: &[PublicKey],
+        threshold: usize,
+    ) -> Option<Self> {
+        let coefficients: Vec<Scalar> = (0..threshold as u64)
+            .map(|k| match k {
+                0 => elgamal::election_secret(service_key, election),
+                _ => hash_to_scalar(&[b"dealing-coefficient", &service_key[..], election.as_ref(), &k.to_le_bytes()]),
+            })
+            .collect();
+        let commitments = coefficients.iter().map(|coefficient| BASEPOINT * coefficient).collect();
+
+        let ephemeral_secret = hash_to_scalar(&[b"dealing-ephemeral", &service_key[..], election.as_ref()]);
+        let mut shares = Vec::new();
+        for (index, trustee) in trustees.iter().enumerate() {
+            let shared = key_point(trustee)? * ephemeral_secret;
+            let share = evaluate(&coefficients, index as u64 + 1);
+            shares.push(share + share_pad(&shared, election, dealer, trustee));
+        }
+
+        let context = elgamal::proof_context(election, dealer, None);
+        let proof = EqualityProof::new(&coefficients[0], &BASEPOINT, &context);
+        Some(Dealing {
+            commitments,
+            ephemeral: ED25519_BASEPOINT_POINT * ephemeral_secret,
+            shares,
+            proof,
+        })
+    }
+
+    /// Restores the dealing from its message representation.
+    ///
+    /// Returns `None` if a value isn't a valid point or a canonical scalar, or the ephemeral key
+    /// isn't in the prime order subgroup.
+    pub fn from_hashes(commitments: &[Hash], ephemeral: &Hash, shares: &[Hash], challenge: &Hash, response: &Hash) -> Option<Self> {
+        let ephemeral = hash_to_edwards(ephemeral)?;
+        if !ephemeral.is_torsion_free() {
+            return None;
+        }
+        Some(Dealing {
+            commitments: commitments.iter().map(elgamal::hash_to_point).collect::<Option<_>>()?,
+            ephemeral,
+            shares: shares.iter().map(scalar_from_hash).collect::<Option<_>>()?,
+            proof: EqualityProof::from_hashes(challenge, response)?,
+        })
+    }
+
+    /// Returns the message representation of the dealing: the commitments, the ephemeral key,
+    /// the encrypted shares, the challenge and the response of the proof.
+    pub fn to_hashes(&self) -> (Vec<Hash>, Hash, Vec<Hash>, Hash, Hash) {
+        let (challenge, response) = self.proof.to_hashes();
+        (
+            self.commitments.iter().map(elgamal::point_to_hash).collect(),
+            Hash::new(self.ephemeral.compress().to_bytes()),
+            self.shares.iter().map(elgamal::scalar_to_hash).collect(),
+            challenge,
+            response,
+        )
+    }
+
+    /// Verifies the proof of the knowledge of the dealer's key share.
+    pub fn verify(&self, election: &Hash, dealer: &PublicKey) -> bool {
+        let context = elgamal::proof_context(election, dealer, None);
+        match self.commitments.first() {
+            Some(key) => self.proof.verify(key, &BASEPOINT, key, &context),
+            None => false,
+        }
+    }
+}
+
+/// Proof that the revealed Diffie-Hellman key `S = x * E` matches the trustee key `P = x * B`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SharedKeyProof {
+    pub challenge: Scalar,
+    pub response: Scalar,
+}
+
+impl SharedKeyProof {
+    pub fn new(service_key: &SecretKey, ephemeral: &EdwardsPoint, context: &Hash) -> Self {
+        let secret = secret_scalar(service_key);
+        let nonce = hash_to_scalar(&[b"shared-key-nonce", secret.as_bytes(), ephemeral.compress().as_bytes(), context.as_ref()]);
+        let key = ED25519_BASEPOINT_POINT * secret;
+        let shared = ephemeral * secret;
+        let commitments = [ED25519_BASEPOINT_POINT * nonce, ephemeral * nonce];
+        let challenge = shared_key_challenge(&[key, *ephemeral, shared, commitments[0], commitments[1]], context);
+        SharedKeyProof { challenge, response: nonce + challenge * secret }
+    }
+
+    pub fn verify(&self, trustee: &PublicKey, ephemeral: &EdwardsPoint, shared: &EdwardsPoint, context: &Hash) -> bool {
+        let key = match key_point(trustee) {
+            Some(key) => key,
+            None => return false,
+        };
+        let commitments = [
+            ED25519_BASEPOINT_POINT * self.response - key * self.challenge,
+            ephemeral * self.response - shared * self.challenge,
+        ];
+        let challenge = shared_key_challenge(&[key, *ephemeral, *shared, commitments[0], commitments[1]], context);
+        challenge == self.challenge
+    }
+
+    /// Restores the proof from its message representation.
+    pub fn from_hashes(challenge: &Hash, response: &Hash) -> Option<Self> {
+        Some(SharedKeyProof { challenge: scalar_from_hash(challenge)?, response: scalar_from_hash(response)? })
+    }
+
+    /// Returns the message representation of the proof, the challenge and the response.
+    pub fn to_hashes(&self) -> (Hash, Hash) {
+        (elgamal::scalar_to_hash(&self.challenge), elgamal::scalar_to_hash(&self.response))
+    }
+}
+
+/// Returns the Diffie-Hellman key of the trustee for the ephemeral key of a dealing.
+pub fn shared_key(service_key: &SecretKey, ephemeral: &EdwardsPoint) -> EdwardsPoint {
+    ephemeral * secret_scalar(service_key)
+}
+
+/// Decrypts the share of the trustee with the Diffie-Hellman key.
+pub fn decrypt_share(shared: &EdwardsPoint, encrypted: &Scalar, election: &Hash, dealer: &PublicKey, trustee: &PublicKey) -> Scalar {
+    encrypted - share_pad(shared, election, dealer, trustee)
+}
+
+/// Checks the share of the trustee with the given index against the commitments of the dealing.
+pub fn verify_share(commitments: &[RistrettoPoint], index: u64, share: &Scalar) -> bool {
+    BASEPOINT * share == evaluate_commitments(commitments, index)
+}
+
+/// Returns the secret share of the trustee, the sum of its shares of the qualified dealings.
+///
+/// The dealings are given by the dealer, the ephemeral key and the encrypted share of the trustee.
+pub fn secret_share(service_key: &SecretKey, election: &Hash, trustee: &PublicKey, dealings: &[(PublicKey, EdwardsPoint, Scalar)]) -> Scalar {
+    dealings.iter().fold(Scalar::zero(), |sum, (dealer, ephemeral, encrypted)| {
+        let shared = shared_key(service_key, ephemeral);
+        sum + decrypt_share(&shared, encrypted, election, dealer, trustee)
+    })
+}
+
+/// Returns the public share of the trustee with the given index for the commitments of the qualified dealings.
+pub fn public_share(dealings: &[Vec<RistrettoPoint>], index: u64) -> RistrettoPoint {
+    dealings.iter()
+        .map(|commitments| evaluate_commitments(commitments, index))
+        .fold(RistrettoPoint::identity(), |sum, point| sum + point)
+}
+
+/// Combines the decryption shares of the trustees with the given indices into the decryption.
+///
+/// The shares of any `t` distinct trustees give the same result.
+pub fn combine(shares: &[(u64, RistrettoPoint)]) -> RistrettoPoint {
+    let indices: Vec<u64> = shares.iter().map(|(index, _)| *index).collect();
+    shares.iter()
+        .map(|(index, share)| share * lagrange_coefficient(&indices, *index))
+        .fold(RistrettoPoint::identity(), |sum, point| sum + point)
+}
+
+/// Returns the Lagrange coefficient of the index for the interpolation at zero.
+fn lagrange_coefficient(indices: &[u64], index: u64) -> Scalar {
+    let x = Scalar::from_u64(index);
+    let (numerator, denominator) = indices.iter()
+        .filter(|&&other| other != index)
+        .map(|&other| Scalar::from_u64(other))
+        .fold((Scalar::one(), Scalar::one()), |(numerator, denominator), other| {
+            (numerator * other, denominator * (other - x))
+        });
+    numerator * denominator.invert()
+}
+
+/// Evaluates the polynomial at the index.
+fn evaluate(coefficients: &[Scalar], index: u64) -> Scalar {
+    let x = Scalar::from_u64(index);
+    coefficients.iter().rev().fold(Scalar::zero(), |sum, coefficient| sum * x + coefficient)
+}
+
+/// Evaluates the committed polynomial at the index in the exponent.
+fn evaluate_commitments(commitments: &[RistrettoPoint], index: u64) -> RistrettoPoint {
+    let x = Scalar::from_u64(index);
+    commitments.iter().rev().fold(RistrettoPoint::identity(), |sum, commitment| sum * x + commitment)
+}
+
+fn share_pad(shared: &EdwardsPoint, election: &Hash, dealer: &PublicKey, trustee: &PublicKey) -> Scalar {
+    hash_to_scalar(&[b"share-pad", shared.compress().as_bytes(), election.as_ref(), dealer.as_ref(), trustee.as_ref()])
+}
+
+fn shared_key_challenge(points: &[EdwardsPoint], context: &Hash) -> Scalar {
+    let mut data = b"shared-key".to_vec();
+    data.extend_from_slice(context.as_ref());
+    for point in points {
+        data.extend_from_slice(point.compress().as_bytes());
+    }
+    hash_to_scalar(&[&data])
+}
@@ -0,0 +1,365 @@
+//! Exponential ElGamal encryption of the ballots.
+//!
+//! A value `m` is encrypted under the election key `Y` as `(r * G, m * G + r * Y)`
+//! in the Ristretto group. The sum of ciphertexts encrypts the sum of the values,
+//! so the votes for a candidate are added up without decrypting individual ballots.
+//!
+//! The election key is shared by the trustees of the election with a threshold, see `dkg`.
+//! After the election is closed every trustee publishes a decryption share of the summed votes,
+//! the sum is decrypted once the shares of the threshold number of trustees are known.
+//! Every step is accompanied by a non-interactive zero-knowledge proof made with
+//! the Fiat-Shamir heuristic.
+//!
+//! Points and scalars are carried in messages as `Hash`, they have the same size.
+
+use std::collections::HashMap;
+
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT,
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+    traits::Identity,
+};
+use exonum::crypto::{self, hash, Hash, PublicKey, SecretKey};
+
+use lsag::{hash_to_scalar, scalar_from_hash};
+use schema::EncryptedChoice;
+
+/// Generator of the group.
+pub const BASEPOINT: RistrettoPoint = RISTRETTO_BASEPOINT_POINT;
+
+/// Encrypted value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ciphertext {
+    pub a: RistrettoPoint,
+    pub b: RistrettoPoint,
+}
+
+impl Ciphertext {
+    /// Encryption of zero without randomness, the neutral element of the addition.
+    pub fn zero() -> Self {
+        Ciphertext { a: RistrettoPoint::identity(), b: RistrettoPoint::identity() }
+    }
+
+    /// Encrypts the value with the randomness `r`.
+    pub fn encrypt(key: &RistrettoPoint, value: u64, r: &Scalar) -> Self {
+        Ciphertext {
+            a: RISTRETTO_BASEPOINT_POINT * r,
+            b: RISTRETTO_BASEPOINT_POINT * Scalar::from_u64(value) + key * r,
+        }
+    }
+
+    /// Returns the encryption of the sum of the values.
+    pub fn add(&self, other: &Ciphertext) -> Self {
+        Ciphertext { a: self.a + other.a, b: self.b + other.b }
+    }
+
+    /// Returns the encryption of the value multiplied by the weight.
+    pub fn scale(&self, weight: u64) -> Self {
+        let weight = Scalar::from_u64(weight);
+        Ciphertext { a: self.a * weight, b: self.b * weight }
+    }
+
+    /// Restores the ciphertext from its message representation.
+    pub fn from_hashes(a: &Hash, b: &Hash) -> Option<Self> {
+        Some(Ciphertext { a: hash_to_point(a)?, b: hash_to_point(b)? })
+    }
+
+    /// Returns the message representation of the ciphertext.
+    pub fn to_hashes(&self) -> (Hash, Hash) {
+        (point_to_hash(&self.a), point_to_hash(&self.b))
+    }
+}
+
+/// Proof that a ciphertext encrypts a value from `0` to `max`.
+///
+/// The proof is a disjunction of Chaum-Pedersen proofs, one for every possible value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeProof {
+    pub challenges: Vec<Scalar>,
+    pub responses: Vec<Scalar>,
+}
+
+impl RangeProof {
+    /// Proves that `ciphertext` is `Ciphertext::encrypt(key, value, r)`.
+    pub fn new(key: &RistrettoPoint, ciphertext: &Ciphertext, value: u64, r: &Scalar, max: u64, context: &Hash) -> Self {
+        assert!(value <= max, "The value is out of the range.");
+        // The nonces are derived from the secret randomness and the statement, like in RFC 6979.
+        let statement = points_bytes(&[*key, ciphertext.a, ciphertext.b]);
+        let secret = [r.as_bytes() as &[u8], &statement].concat();
+        let nonce = hash_to_scalar(&[b"range-nonce", &secret, context.as_ref()]);
+
+        let mut challenges = Vec::new();
+        let mut responses = Vec::new();
+        let mut commitments = Vec::new();
+        for option in 0..=max {
+            if option == value {
+                challenges.push(Scalar::zero());
+                responses.push(Scalar::zero());
+                commitments.push((RISTRETTO_BASEPOINT_POINT * nonce, key * nonce));
+            } else {
+                // The proofs for the other values are simulated.
+                let index = option.to_le_bytes();
+                let challenge = hash_to_scalar(&[b"range-challenge", &secret, context.as_ref(), &index]);
+                let response = hash_to_scalar(&[b"range-response", &secret, context.as_ref(), &index]);
+                commitments.push(range_commitments(key, ciphertext, option, &challenge, &response));
+                challenges.push(challenge);
+                responses.push(response);
+            }
+        }
+
+        let total = range_challenge(key, ciphertext, &commitments, context);
+        let simulated = challenges.iter().fold(Scalar::zero(), |sum, c| sum + c);
+        let challenge = total - simulated;
+        challenges[value as usize] = challenge;
+        responses[value as usize] = nonce + challenge * r;
+        RangeProof { challenges, responses }
+    }
+
+    /// Verifies the proof for a ciphertext encrypted with the key.
+    pub fn verify(&self, key: &RistrettoPoint, ciphertext: &Ciphertext, max: u64, context: &Hash) -> bool {
+        let options = max as usize + 1;
+        if self.challenges.len() != options || self.responses.len() != options {
+            return false;
+        }
+        let commitments: Vec<_> = (0..=max)
+            .map(|option| {
+                let i = option as usize;
+                range_commitments(key, ciphertext, option, &self.challenges[i], &self.responses[i])
+            })
+            .collect();
+        let total = self.challenges.iter().fold(Scalar::zero(), |sum, c| sum + c);
+        total == range_challenge(key, ciphertext, &commitments, context)
+    }
+
+    /// Restores the proof from its message representation.
+    pub fn from_hashes(challenges: &[Hash], responses: &[Hash]) -> Option<Self> {
+        Some(RangeProof {
+            challenges: challenges.iter().map(hash_to_scalar_canonical).collect::<Option<_>>()?,
+            responses: responses.iter().map(hash_to_scalar_canonical).collect::<Option<_>>()?,
+        })
+    }
+
+    /// Returns the message representation of the proof, the challenges and the responses.
+    pub fn to_hashes(&self) -> (Vec<Hash>, Vec<Hash>) {
+        (
+            self.challenges.iter().map(scalar_to_hash).collect(),
+            self.responses.iter().map(scalar_to_hash).collect(),
+        )
+    }
+}
+
+/// Commitments of the Chaum-Pedersen proof that the ciphertext encrypts `value`.
+fn range_commitments(
+    key: &RistrettoPoint,
+    ciphertext: &Ciphertext,
+    value: u64,
+    challenge: &Scalar,
+    response: &Scalar,
+) -> (RistrettoPoint, RistrettoPoint) {
+    let message = ciphertext.b - RISTRETTO_BASEPOINT_POINT * Scalar::from_u64(value);
+    (
+        RISTRETTO_BASEPOINT_POINT * response - ciphertext.a * challenge,
+        key * response - message * challenge,
+    )
+}
+
+fn range_challenge(
+    key: &RistrettoPoint,
+    ciphertext: &Ciphertext,
+    commitments: &[(RistrettoPoint, RistrettoPoint)],
+    context: &Hash,
+) -> Scalar {
+    let mut points = vec![*key, ciphertext.a, ciphertext.b];
+    for (t1, t2) in commitments {
+        points.push(*t1);
+        points.push(*t2);
+    }
+    points_challenge(b"range", &points, context)
+}
+
+/// Proof of the discrete logarithm equality `share = x * base` and `key = x * G`.
+///
+/// With the base `G` and the same key and share it proves the knowledge of the secret key.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EqualityProof {
+    pub challenge: Scalar,
+    pub response: Scalar,
+}
+
+impl EqualityProof {
+    pub fn new(secret: &Scalar, base: &RistrettoPoint, context: &Hash) -> Self {
+        let statement = points_bytes(&[*base]);
+        let nonce = hash_to_scalar(&[b"equality-nonce", secret.as_bytes(), &statement, context.as_ref()]);
+        let key = RISTRETTO_BASEPOINT_POINT * secret;
+        let share = base * secret;
+        let commitments = [RISTRETTO_BASEPOINT_POINT * nonce, base * nonce];
+        let challenge = points_challenge(b"equality", &[key, *base, share, commitments[0], commitments[1]], context);
+        EqualityProof { challenge, response: nonce + challenge * secret }
+    }
+
+    pub fn verify(&self, key: &RistrettoPoint, base: &RistrettoPoint, share: &RistrettoPoint, context: &Hash) -> bool {
+        let commitments = [
+            RISTRETTO_BASEPOINT_POINT * self.response - key * self.challenge,
+            base * self.response - share * self.challenge,
+        ];
+        let challenge = points_challenge(b"equality", &[*key, *base, *share, commitments[0], commitments[1]], context);
+        challenge == self.challenge
+    }
+
+    /// Restores the proof from its message representation.
+    pub fn from_hashes(challenge: &Hash, response: &Hash) -> Option<Self> {
+        Some(EqualityProof {
+            challenge: hash_to_scalar_canonical(challenge)?,
+            response: hash_to_scalar_canonical(response)?,
+        })
+    }
+
+    /// Returns the message representation of the proof, the challenge and the response.
+    pub fn to_hashes(&self) -> (Hash, Hash) {
+        (scalar_to_hash(&self.challenge), scalar_to_hash(&self.response))
+    }
+}
+
+/// Secret of the trustee's key share for the election, the free coefficient of its dealing.
+///
+/// The secret is derived from the service secret key of the trustee,
+/// so the trustee doesn't need to keep any additional state.
+pub fn election_secret(service_key: &SecretKey, election: &Hash) -> Scalar {
+    hash_to_scalar(&[b"election-secret", &service_key[..], election.as_ref()])
+}
+
+/// Returns the public key share of the secret.
+pub fn key_share(secret: &Scalar) -> RistrettoPoint {
+    RISTRETTO_BASEPOINT_POINT * secret
+}
+
+/// Returns the decryption share of the trustee's secret share for the ciphertext.
+pub fn decryption_share(secret: &Scalar, ciphertext: &Ciphertext) -> RistrettoPoint {
+    ciphertext.a * secret
+}
+
+/// Table of the baby steps for the decryption of the values up to `max`.
+///
+/// The table takes `sqrt(max)` additions to build, so it is built once and reused
+/// for every ciphertext decrypted with the same bound.
+#[derive(Debug)]
+pub struct BabySteps {
+    max: u64,
+    step: u64,
+    table: HashMap<[u8; 32], u64>,
+}
+
+impl BabySteps {
+    /// Builds the table for the values up to `max`.
+    pub fn new(max: u64) -> Self {
+        let step = ((max as f64).sqrt() as u64 + 1).max(1);
+        let mut table = HashMap::new();
+        let mut point = RistrettoPoint::identity();
+        for value in 0..step {
+            table.entry(point.compress().to_bytes()).or_insert(value);
+            point += RISTRETTO_BASEPOINT_POINT;
+        }
+        BabySteps { max, step, table }
+    }
+}
+
+/// Decrypts the ciphertext with the decryption `y * A`, see `dkg::combine`.
+///
+/// The value is found by the baby-step giant-step search in about `sqrt(max)` additions.
+/// Returns `None` if the value is greater than the bound of the `baby_steps`.
+pub fn decrypt(ciphertext: &Ciphertext, decryption: &RistrettoPoint, baby_steps: &BabySteps) -> Option<u64> {
+    let step = baby_steps.step;
+    let giant_step = RISTRETTO_BASEPOINT_POINT * Scalar::from_u64(step);
+    let mut point = ciphertext.b - decryption;
+    for giant in 0..=step {
+        if let Some(baby) = baby_steps.table.get(&point.compress().to_bytes()) {
+            let value = giant * step + baby;
+            return if value <= baby_steps.max { Some(value) } else { None };
+        }
+        point -= giant_step;
+    }
+    None
+}
+
+/// Returns a random scalar for the encryption.
+pub fn random_scalar() -> Scalar {
+    let (_, secret_key) = crypto::gen_keypair();
+    hash_to_scalar(&[b"random", &secret_key[..]])
+}
+
+/// Returns the context binding a proof to the election and the parties of the transaction.
+pub fn proof_context(election: &Hash, signer: &PublicKey, candidate: Option<&PublicKey>) -> Hash {
+    let mut data = election.as_ref().to_vec();
+    data.extend_from_slice(signer.as_ref());
+    if let Some(candidate) = candidate {
+        data.extend_from_slice(candidate.as_ref());
+    }
+    hash(&data)
+}
+
+/// Encrypts a ballot which chooses the candidates from `chosen`.
+///
+/// Every candidate of the election gets an encrypted choice with a proof that it is 0 or 1.
+/// The returned proof shows that the ballot chooses at most `max_choices` candidates.
+pub fn encrypt_ballot(
+    key: &RistrettoPoint,
+    election: &Hash,
+    elector: &PublicKey,
+    candidates: &[PublicKey],
+    chosen: &[PublicKey],
+    max_choices: u64,
+) -> (Vec<EncryptedChoice>, RangeProof) {
+    let mut total = Ciphertext::zero();
+    let mut total_r = Scalar::zero();
+    let mut choices = Vec::new();
+    for candidate in candidates {
+        let value = chosen.contains(candidate) as u64;
+        let r = random_scalar();
+        let ciphertext = Ciphertext::encrypt(key, value, &r);
+        let context = proof_context(election, elector, Some(candidate));
+        let proof = RangeProof::new(key, &ciphertext, value, &r, 1, &context);
+
+        let (a, b) = ciphertext.to_hashes();
+        let (challenges, responses) = proof.to_hashes();
+        choices.push(EncryptedChoice::new(candidate, &a, &b, &challenges, &responses));
+        total = total.add(&ciphertext);
+        total_r += r;
+    }
+
+    let context = proof_context(election, elector, None);
+    let proof = RangeProof::new(key, &total, chosen.len() as u64, &total_r, max_choices, &context);
+    (choices, proof)
+}
+
+pub fn point_to_hash(point: &RistrettoPoint) -> Hash {
+    Hash::new(point.compress().to_bytes())
+}
+
+/// Decodes a point, returns `None` if the bytes are not a valid point encoding.
+pub fn hash_to_point(hash: &Hash) -> Option<RistrettoPoint> {
+    let mut bytes = [0; 32];
+    bytes.copy_from_slice(hash.as_ref());
+    CompressedRistretto(bytes).decompress()
+}
+
+pub fn scalar_to_hash(scalar: &Scalar) -> Hash {
+    Hash::new(scalar.to_bytes())
+}
+
+/// Decodes a scalar, returns `None` if the bytes are not reduced modulo the group order.
+pub fn hash_to_scalar_canonical(hash: &Hash) -> Option<Scalar> {
+    scalar_from_hash(hash)
+}
+
+fn points_challenge(domain: &[u8], points: &[RistrettoPoint], context: &Hash) -> Scalar {
+    hash_to_scalar(&[domain, context.as_ref(), &points_bytes(points)])
+}
+
+fn points_bytes(points: &[RistrettoPoint]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(points.len() * 32);
+    for point in points {
+        data.extend_from_slice(point.compress().as_bytes());
+    }
+    data
+}
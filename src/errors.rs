@@ -17,6 +17,168 @@ pub enum Error {
 
     #[fail(display = "The voter has already voted.")]
     AlreadyVoted = 4,
+
+    #[fail(display = "Election doesn't exist")]
+    ElectionNotFound = 5,
+
+    #[fail(display = "Invalid election phase heights")]
+    InvalidPhaseHeights = 6,
+
+    #[fail(display = "Registration is closed")]
+    RegistrationClosed = 7,
+
+    #[fail(display = "Voting has not started yet")]
+    VotingNotStarted = 8,
+
+    #[fail(display = "Voting is over")]
+    VotingClosed = 9,
+
+    #[fail(display = "The signer is not a registrar")]
+    UnknownRegistrar = 10,
+
+    #[fail(display = "Electors must be registered by a registrar")]
+    SelfRegistrationDisabled = 11,
+
+    #[fail(display = "Invalid candidate name length")]
+    InvalidCandidateName = 12,
+
+    #[fail(display = "The voter has not voted yet.")]
+    NotVoted = 13,
+
+    #[fail(display = "The voter already backs this candidate.")]
+    SameCandidate = 14,
+
+    #[fail(display = "The transaction doesn't match the voting scheme of the election")]
+    WrongVotingScheme = 15,
+
+    #[fail(display = "Invalid ballot")]
+    InvalidBallot = 16,
+
+    #[fail(display = "Invalid number of seats or choices")]
+    InvalidElectionParams = 17,
+
+    #[fail(display = "Too many choices in the ballot")]
+    TooManyChoices = 18,
+
+    #[fail(display = "Elector weight must be positive")]
+    InvalidWeight = 19,
+
+    #[fail(display = "Elector can't delegate to itself")]
+    SelfDelegation = 20,
+
+    #[fail(display = "Delegation would create a cycle")]
+    DelegationCycle = 21,
+
+    #[fail(display = "Reveal phase has not started yet")]
+    RevealNotStarted = 22,
+
+    #[fail(display = "Reveal phase is over")]
+    RevealClosed = 23,
+
+    #[fail(display = "The vote is already revealed")]
+    AlreadyRevealed = 24,
+
+    #[fail(display = "The reveal doesn't match the commitment")]
+    InvalidReveal = 25,
+
+    #[fail(display = "The signer is not a trustee of the election")]
+    NotTrustee = 26,
+
+    #[fail(display = "The key share is already submitted")]
+    KeyShareExists = 27,
+
+    #[fail(display = "Invalid zero-knowledge proof")]
+    InvalidProof = 28,
+
+    #[fail(display = "Not enough trustees have dealt the election key")]
+    EncryptionKeyNotReady = 29,
+
+    #[fail(display = "Decryption starts after the election is closed")]
+    DecryptionNotStarted = 30,
+
+    #[fail(display = "The decryption shares are already submitted")]
+    DecryptionExists = 31,
+
+    #[fail(display = "Decryption shares don't match the candidates")]
+    InvalidDecryption = 32,
+
+    #[fail(display = "Invalid ring signature")]
+    InvalidRingSignature = 33,
+
+    #[fail(display = "The token is already issued to the elector")]
+    TokenIssued = 34,
+
+    #[fail(display = "The token is already spent")]
+    TokenSpent = 35,
+
+    #[fail(display = "Invalid token signature")]
+    InvalidToken = 36,
+
+    #[fail(display = "The candidate is withdrawn")]
+    CandidateWithdrawn = 37,
+
+    #[fail(display = "The candidates can't be withdrawn after the voting")]
+    WithdrawalClosed = 38,
+
+    #[fail(display = "The eligibility of the elector is revoked")]
+    ElectorRevoked = 39,
+
+    #[fail(display = "Unknown revocation reason")]
+    UnknownRevocationReason = 40,
+
+    #[fail(display = "Neither an elector nor a candidate has the key")]
+    UnknownKey = 41,
+
+    #[fail(display = "The new key is already in use")]
+    KeyInUse = 42,
+
+    #[fail(display = "Duplicate electors in the batch")]
+    DuplicateElectors = 43,
+
+    #[fail(display = "The vote service configuration is invalid")]
+    InvalidConfig = 44,
+
+    #[fail(display = "Unknown voting scheme")]
+    UnknownVotingScheme = 45,
+
+    #[fail(display = "The key share of the dealer doesn't exist")]
+    KeyShareNotFound = 46,
+
+    #[fail(display = "The dealer is disqualified")]
+    DealerDisqualified = 47,
+
+    #[fail(display = "The complained key share is valid")]
+    KeyShareValid = 48,
+
+    #[fail(display = "The votes are already decrypted")]
+    DecryptionComplete = 49,
+
+    #[fail(display = "The total weight of the encrypted ballots is too large")]
+    EncryptedWeightExceeded = 50,
+
+    #[fail(display = "The ring of the anonymous election is frozen for the voting")]
+    RingFrozen = 51,
+
+    #[fail(display = "The token nonces are already committed for the elector")]
+    TokenSessionExists = 52,
+
+    #[fail(display = "Invalid issuing key")]
+    InvalidIssuingKey = 53,
+
+    #[fail(display = "The token nonces are not committed for the elector")]
+    TokenNotCommitted = 54,
+
+    #[fail(display = "The candidate is disqualified")]
+    CandidateDisqualified = 55,
+
+    #[fail(display = "The keys can't be rotated during the voting of an anonymous or a token election")]
+    KeyRotationClosed = 56,
+
+    #[fail(display = "The batch has too many entries")]
+    BatchTooLarge = 57,
+
+    #[fail(display = "The key is replaced by a key rotation")]
+    KeyReplaced = 58,
 }
 
 impl From<Error> for ExecutionError {
@@ -24,4 +186,19 @@ impl From<Error> for ExecutionError {
         let description = format!("{}", value);
         ExecutionError::with_description(value as u8, description)
     }
-}
\ No newline at end of file
+}
+
+/// Error rejecting the entries of a batch, which are reported by their zero-based indices.
+#[derive(Debug)]
+pub struct EntriesError {
+    pub error: Error,
+    pub entries: Vec<usize>,
+}
+
+impl From<EntriesError> for ExecutionError {
+    fn from(value: EntriesError) -> ExecutionError {
+        let entries: Vec<String> = value.entries.iter().map(usize::to_string).collect();
+        let description = format!("{} at the entries {}", value.error, entries.join(", "));
+        ExecutionError::with_description(value.error as u8, description)
+    }
+}
@@ -6,15 +6,29 @@ extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
+extern crate toml;
 extern crate exonum_testkit;
+extern crate curve25519_dalek;
+extern crate exonum_sodiumoxide;
 
 use service::SERVICE_NAME;
-use exonum::{helpers::fabric::Context, blockchain::Service, helpers::fabric};
+use config::VoteConfig;
+use exonum::{
+    helpers::fabric::{Context, Command, CommandExtension, CommandName},
+    blockchain::Service, helpers::fabric,
+};
 
+pub mod config;
 pub mod errors;
 pub mod service;
 pub mod schema;
 pub mod api;
+pub mod blind;
+pub mod client;
+pub mod dkg;
+pub mod elgamal;
+pub mod lsag;
+pub mod tally;
 pub mod transactions;
 
 #[derive(Debug)]
@@ -25,7 +39,19 @@ impl fabric::ServiceFactory for ServiceFactory {
         SERVICE_NAME
     }
 
-    fn make_service(&mut self, _: &Context) -> Box<dyn Service> {
-        Box::new(service::VoteService)
+    fn command(&mut self, command: CommandName) -> Option<Box<dyn CommandExtension>> {
+        if command == fabric::GenerateCommonConfig.name() {
+            Some(Box::new(config::GenerateCommonConfig))
+        } else if command == fabric::Finalize.name() {
+            Some(Box::new(config::Finalize))
+        } else {
+            None
+        }
+    }
+
+    fn make_service(&mut self, context: &Context) -> Box<dyn Service> {
+        let config = VoteConfig::from_context(context)
+            .expect("Invalid vote service configuration.");
+        Box::new(service::VoteService::new(config))
     }
 }
\ No newline at end of file
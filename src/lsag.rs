@@ -0,0 +1,204 @@
+//! Linkable ring signatures over the Ed25519 keys of the electorate.
+//!
+//! A signature proves that the message is signed by the owner of one of the keys
+//! of the ring without revealing which one. Every signature carries the key image
+//! `I = x * Hp(P)` of the signer's key pair `(x, P)`, the image is the same for all
+//! the signatures of a key in the same scope, so a second signature is detected
+//! without linking it to the key.
+//!
+//! The scheme is LSAG by Liu, Wei and Wong. Points and scalars are carried in messages as `Hash`.
+
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT,
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    scalar::Scalar,
+    traits::Identity,
+};
+use exonum::crypto::{hash, Hash, PublicKey, SecretKey};
+use exonum_sodiumoxide::crypto::hash::sha512;
+
+/// Linkable ring signature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RingSignature {
+    pub key_image: EdwardsPoint,
+    pub challenge: Scalar,
+    pub responses: Vec<Scalar>,
+}
+
+impl RingSignature {
+    /// Signs the message with the secret key on behalf of the ring.
+    ///
+    /// The scope separates the key images, the signatures of a key in different scopes
+    /// can't be linked. Returns `None` if the public key of the signer isn't in the ring
+    /// or the ring contains an invalid key.
+    pub fn sign(message: &Hash, scope: &Hash, ring: &[PublicKey], secret_key: &SecretKey) -> Option<Self> {
+        let points = ring_points(ring)?;
+        let signer = ring.iter().position(|key| key == &public_key(secret_key))?;
+        let secret = secret_scalar(secret_key);
+        let key_image = hash_to_point(scope, &ring[signer]) * secret;
+        let prefix = ring_prefix(message, scope, ring, &key_image);
+
+        let size = ring.len();
+        let mut challenges = vec![Scalar::zero(); size];
+        let mut responses = vec![Scalar::zero(); size];
+
+        // The nonces are derived from the secret and the signed data.
+        let nonce_data = [secret.as_bytes() as &[u8], prefix.as_ref()].concat();
+        let nonce = hash_to_scalar(&[b"lsag-nonce", &nonce_data]);
+        let base = hash_to_point(scope, &ring[signer]);
+        challenges[(signer + 1) % size] = ring_challenge(&prefix, &(ED25519_BASEPOINT_POINT * nonce), &(base * nonce));
+
+        let mut i = (signer + 1) % size;
+        while i != signer {
+            let index = (i as u64).to_le_bytes();
+            responses[i] = hash_to_scalar(&[b"lsag-response", &nonce_data, &index]);
+            let (l, r) = ring_commitments(scope, &ring[i], &points[i], &key_image, &challenges[i], &responses[i]);
+            challenges[(i + 1) % size] = ring_challenge(&prefix, &l, &r);
+            i = (i + 1) % size;
+        }
+        responses[signer] = nonce - challenges[signer] * secret;
+
+        Some(RingSignature { key_image, challenge: challenges[0], responses })
+    }
+
+    /// Verifies the signature of the message by a member of the ring.
+    pub fn verify(&self, message: &Hash, scope: &Hash, ring: &[PublicKey]) -> bool {
+        if ring.is_empty() || self.responses.len() != ring.len() {
+            return false;
+        }
+        // The image with a small order component would allow a key to sign twice with different images.
+        if !self.key_image.is_torsion_free() || self.key_image == EdwardsPoint::identity() {
+            return false;
+        }
+        let points = match ring_points(ring) {
+            Some(points) => points,
+            None => return false,
+        };
+
+        let prefix = ring_prefix(message, scope, ring, &self.key_image);
+        let mut challenge = self.challenge;
+        for (i, response) in self.responses.iter().enumerate() {
+            let (l, r) = ring_commitments(scope, &ring[i], &points[i], &self.key_image, &challenge, response);
+            challenge = ring_challenge(&prefix, &l, &r);
+        }
+        challenge == self.challenge
+    }
+
+    /// Restores the signature from its message representation.
+    pub fn from_hashes(key_image: &Hash, challenge: &Hash, responses: &[Hash]) -> Option<Self> {
+        Some(RingSignature {
+            key_image: hash_to_edwards(key_image)?,
+            challenge: scalar_from_hash(challenge)?,
+            responses: responses.iter().map(scalar_from_hash).collect::<Option<_>>()?,
+        })
+    }
+
+    /// Returns the message representation of the signature, the key image, the challenge and the responses.
+    pub fn to_hashes(&self) -> (Hash, Hash, Vec<Hash>) {
+        (
+            Hash::new(self.key_image.compress().to_bytes()),
+            Hash::new(self.challenge.to_bytes()),
+            self.responses.iter().map(|response| Hash::new(response.to_bytes())).collect(),
+        )
+    }
+}
+
+/// Returns the key image of the key pair in the scope.
+pub fn key_image(scope: &Hash, secret_key: &SecretKey) -> Hash {
+    let image = hash_to_point(scope, &public_key(secret_key)) * secret_scalar(secret_key);
+    Hash::new(image.compress().to_bytes())
+}
+
+fn ring_commitments(
+    scope: &Hash,
+    key: &PublicKey,
+    point: &EdwardsPoint,
+    key_image: &EdwardsPoint,
+    challenge: &Scalar,
+    response: &Scalar,
+) -> (EdwardsPoint, EdwardsPoint) {
+    (
+        ED25519_BASEPOINT_POINT * response + point * challenge,
+        hash_to_point(scope, key) * response + key_image * challenge,
+    )
+}
+
+fn ring_prefix(message: &Hash, scope: &Hash, ring: &[PublicKey], key_image: &EdwardsPoint) -> Hash {
+    let mut data = Vec::with_capacity(32 * (ring.len() + 3));
+    data.extend_from_slice(message.as_ref());
+    data.extend_from_slice(scope.as_ref());
+    for key in ring {
+        data.extend_from_slice(key.as_ref());
+    }
+    data.extend_from_slice(key_image.compress().as_bytes());
+    hash(&data)
+}
+
+fn ring_challenge(prefix: &Hash, l: &EdwardsPoint, r: &EdwardsPoint) -> Scalar {
+    hash_to_scalar(&[b"lsag-challenge", prefix.as_ref(), l.compress().as_bytes(), r.compress().as_bytes()])
+}
+
+fn ring_points(ring: &[PublicKey]) -> Option<Vec<EdwardsPoint>> {
+    ring.iter().map(key_point).collect()
+}
+
+/// Returns the Edwards point of the public key.
+pub fn key_point(key: &PublicKey) -> Option<EdwardsPoint> {
+    compressed(key.as_ref()).decompress()
+}
+
+/// Maps the key into a point of the prime order subgroup by try-and-increment.
+fn hash_to_point(scope: &Hash, key: &PublicKey) -> EdwardsPoint {
+    let mut data = [scope.as_ref(), key.as_ref(), &[0]].concat();
+    for counter in 0..=255 {
+        *data.last_mut().unwrap() = counter;
+        let candidate = compressed(hash(&data).as_ref());
+        if let Some(point) = candidate.decompress().map(|point| point.mul_by_cofactor()) {
+            if point != EdwardsPoint::identity() {
+                return point;
+            }
+        }
+    }
+    unreachable!("A half of the hashes are valid points.")
+}
+
+/// Returns the Ed25519 secret scalar of the key, `P = x * B`.
+pub fn secret_scalar(secret_key: &SecretKey) -> Scalar {
+    let digest = sha512::hash(&secret_key[..32]);
+    let mut bytes = [0; 32];
+    bytes.copy_from_slice(&digest.0[..32]);
+    bytes[0] &= 248;
+    bytes[31] &= 127;
+    bytes[31] |= 64;
+    Scalar::from_bytes_mod_order(bytes)
+}
+
+fn public_key(secret_key: &SecretKey) -> PublicKey {
+    PublicKey::from_slice(&secret_key[32..]).unwrap()
+}
+
+fn compressed(bytes: &[u8]) -> CompressedEdwardsY {
+    let mut point = [0; 32];
+    point.copy_from_slice(bytes);
+    CompressedEdwardsY(point)
+}
+
+/// Restores the point from its message representation.
+pub fn hash_to_edwards(hash: &Hash) -> Option<EdwardsPoint> {
+    compressed(hash.as_ref()).decompress()
+}
+
+/// Restores the canonical scalar from its message representation.
+pub fn scalar_from_hash(hash: &Hash) -> Option<Scalar> {
+    let mut bytes = [0; 32];
+    bytes.copy_from_slice(hash.as_ref());
+    Scalar::from_canonical_bytes(bytes)
+}
+
+/// Hashes the data into a uniformly distributed scalar.
+pub fn hash_to_scalar(parts: &[&[u8]]) -> Scalar {
+    let digest = sha512::hash(&parts.concat());
+    let mut wide = [0; 64];
+    wide.copy_from_slice(&digest.0);
+    Scalar::from_bytes_mod_order_wide(&wide)
+}
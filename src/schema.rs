@@ -1,24 +1,506 @@
+use curve25519_dalek::{ristretto::RistrettoPoint, traits::Identity};
 use exonum::{
-    crypto::{PublicKey, Hash},
-    storage::{Fork, Snapshot, ProofMapIndex, ProofListIndex},
+    blockchain::Schema,
+    crypto::{hash, PublicKey, Hash},
+    helpers::Height,
+    storage::{Fork, Snapshot, KeySetIndex, MapIndex, ProofMapIndex, ProofListIndex},
 };
+use serde_json;
 
+use config::{RevokedVotes, VoteConfig, VotingScheme, WithdrawnVotes};
+use dkg;
+use elgamal::{self, Ciphertext};
+use service::SERVICE_NAME;
+
+const ELECTION_INDEX: &str = "vote.election";
 const CANDIDATE_INDEX: &str = "vote.candidate";
 const ELECTORATE_INDEX: &str = "vote.electorate";
 const VOTE_HISTORY: &str = "vote.history";
+const BALLOT_INDEX: &str = "vote.ballot";
+const DELEGATION_INDEX: &str = "vote.delegation";
+const COMMITMENT_INDEX: &str = "vote.commitment";
+const KEY_SHARE_INDEX: &str = "vote.key_share";
+const DECRYPTION_INDEX: &str = "vote.decryption";
+const KEY_IMAGE_INDEX: &str = "vote.key_image";
+const TOKEN_INDEX: &str = "vote.token";
+const SPENT_TOKEN_INDEX: &str = "vote.spent_token";
+const TOKEN_SESSION_INDEX: &str = "vote.token_session";
+const ISSUING_KEY_INDEX: &str = "vote.issuing_key";
+const VOTE_RECORD_INDEX: &str = "vote.record";
+const HISTORY_ROOT_INDEX: &str = "vote.history_root";
+const BALLOT_LOG: &str = "vote.ballot_log";
+const BALLOT_POSITION_INDEX: &str = "vote.ballot_position";
+const TURNOUT_BUCKET_INDEX: &str = "vote.turnout_bucket";
+const TURNOUT_CHECKPOINT_INDEX: &str = "vote.turnout_checkpoint";
+const VOICES_CHECKPOINT: &str = "vote.voices_checkpoint";
+const REVOCATION_INDEX: &str = "vote.revocation";
+const KEY_ALIAS_INDEX: &str = "vote.key_alias";
+const RING_INDEX: &str = "vote.ring";
+const BACKER_INDEX: &str = "vote.backer";
+
+encoding_struct! {
+    struct Election {
+        id: &Hash,
+        author: &PublicKey,
+        name: &str,
+        scheme: u8,
+        voting_height: u64,
+        tallying_height: u64,
+        closing_height: u64,
+        seats: u32,
+        max_choices: u32,
+        /// Number of the registered electors.
+        electorate_size: u64,
+        /// Number of the counted ballots.
+        turnout: u64,
+        /// Total weight of the counted ballots, the delegated weight isn't included.
+        total_weight: u64,
+        /// Number of the registered candidates.
+        candidates_count: u64,
+        /// Number of the delegations.
+        delegations_count: u64,
+        /// Number of the committed votes of a `CommitReveal` election which are not revealed yet.
+        unrevealed: u64,
+        /// Service keys of the trustees sharing the key of an `Encrypted` election,
+        /// the validators at the creation of the election.
+        trustees: &[Hash],
+        /// Number of the trustees needed to decrypt the votes.
+        threshold: u32,
+        /// Height from which the trustees may only complain about the dealings, till the voting.
+        dealing_height: u64,
+        /// Whether the sums of the encrypted votes are decrypted into the voices of the candidates.
+        decrypted: bool,
+        /// Number of the electors in a sub-ring of an `Anonymous` election, see `sub_ring_bounds`.
+        ring_size: u32,
+        /// What happens to the votes for a withdrawn candidate, see `WithdrawnVotes`.
+        withdrawn_mode: u8,
+        /// What happens to the cast vote of a revoked elector, see `RevokedVotes`.
+        revoked_mode: u8,
+        /// Number of the blocks in a turnout bucket.
+        turnout_bucket_size: u64,
+        candidates_hash: &Hash,
+        electorate_hash: &Hash,
+        ballots_hash: &Hash,
+        delegations_hash: &Hash,
+        commitments_hash: &Hash,
+        key_shares_hash: &Hash,
+        decryptions_hash: &Hash,
+        key_images_hash: &Hash,
+        tokens_hash: &Hash,
+        spent_tokens_hash: &Hash,
+        token_sessions_hash: &Hash,
+        issuing_keys_hash: &Hash,
+        vote_records_hash: &Hash,
+        turnout_buckets_hash: &Hash,
+        turnout_checkpoints_hash: &Hash,
+        revocations_hash: &Hash,
+        key_aliases_hash: &Hash,
+        ring_hash: &Hash,
+    }
+}
+
+/// Stage of the election lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ElectionPhase {
+    /// Candidates and electors may be registered.
+    Registration,
+    /// Electors may vote.
+    Voting,
+    /// Voting is over, the results are not final yet.
+    Tallying,
+    /// The results are final.
+    Closed,
+}
+
+/// Counters of the election record changed by the transactions.
+struct Counters {
+    electorate_size: u64,
+    turnout: u64,
+    total_weight: u64,
+    candidates_count: u64,
+    delegations_count: u64,
+    unrevealed: u64,
+    decrypted: bool,
+}
+
+impl Election {
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_hashes(
+        self,
+        candidates_hash: &Hash,
+        electorate_hash: &Hash,
+        ballots_hash: &Hash,
+        delegations_hash: &Hash,
+        commitments_hash: &Hash,
+        key_shares_hash: &Hash,
+        decryptions_hash: &Hash,
+        key_images_hash: &Hash,
+        tokens_hash: &Hash,
+        spent_tokens_hash: &Hash,
+        token_sessions_hash: &Hash,
+        issuing_keys_hash: &Hash,
+        vote_records_hash: &Hash,
+        turnout_buckets_hash: &Hash,
+        turnout_checkpoints_hash: &Hash,
+        revocations_hash: &Hash,
+        key_aliases_hash: &Hash,
+        ring_hash: &Hash,
+    ) -> Self {
+        Election::new(
+            self.id(),
+            self.author(),
+            self.name(),
+            self.scheme(),
+            self.voting_height(),
+            self.tallying_height(),
+            self.closing_height(),
+            self.seats(),
+            self.max_choices(),
+            self.electorate_size(),
+            self.turnout(),
+            self.total_weight(),
+            self.candidates_count(),
+            self.delegations_count(),
+            self.unrevealed(),
+            self.trustees(),
+            self.threshold(),
+            self.dealing_height(),
+            self.decrypted(),
+            self.ring_size(),
+            self.withdrawn_mode(),
+            self.revoked_mode(),
+            self.turnout_bucket_size(),
+            candidates_hash,
+            electorate_hash,
+            ballots_hash,
+            delegations_hash,
+            commitments_hash,
+            key_shares_hash,
+            decryptions_hash,
+            key_images_hash,
+            tokens_hash,
+            spent_tokens_hash,
+            token_sessions_hash,
+            issuing_keys_hash,
+            vote_records_hash,
+            turnout_buckets_hash,
+            turnout_checkpoints_hash,
+            revocations_hash,
+            key_aliases_hash,
+            ring_hash,
+        )
+    }
+
+    /// Counts the registered elector.
+    pub fn register_elector(self) -> Self {
+        self.update_counters(|counters| counters.electorate_size += 1)
+    }
+
+    /// Counts the electors registered by a batch.
+    pub fn register_electors(self, count: u64) -> Self {
+        self.update_counters(|counters| counters.electorate_size += count)
+    }
+
+    /// Takes the revoked elector out of the electorate size.
+    pub fn deregister_elector(self) -> Self {
+        self.update_counters(|counters| counters.electorate_size -= 1)
+    }
+
+    /// Counts the ballot of the given weight.
+    pub fn count_ballot(self, weight: u64) -> Self {
+        self.update_counters(|counters| {
+            counters.turnout += 1;
+            counters.total_weight += weight;
+        })
+    }
+
+    /// Takes back the counted ballot of the given weight.
+    pub fn uncount_ballot(self, weight: u64) -> Self {
+        self.update_counters(|counters| {
+            counters.turnout -= 1;
+            counters.total_weight -= weight;
+        })
+    }
+
+    /// Returns the first height of the turnout bucket containing the given height.
+    pub fn turnout_bucket(&self, height: Height) -> u64 {
+        let bucket_size = self.turnout_bucket_size().max(1);
+        let offset = height.0.saturating_sub(self.voting_height());
+        self.voting_height() + offset / bucket_size * bucket_size
+    }
+
+    /// Counts the registered candidate.
+    pub fn register_candidate(self) -> Self {
+        self.update_counters(|counters| counters.candidates_count += 1)
+    }
+
+    /// Counts the new delegation.
+    pub fn add_delegation(self) -> Self {
+        self.update_counters(|counters| counters.delegations_count += 1)
+    }
+
+    /// Takes the removed delegation out of the count.
+    pub fn remove_delegation(self) -> Self {
+        self.update_counters(|counters| counters.delegations_count -= 1)
+    }
+
+    /// Counts the committed vote as unrevealed.
+    pub fn commit_vote(self) -> Self {
+        self.update_counters(|counters| counters.unrevealed += 1)
+    }
+
+    /// Takes the revealed or revoked commitment out of the unrevealed votes.
+    pub fn settle_commitment(self) -> Self {
+        self.update_counters(|counters| counters.unrevealed -= 1)
+    }
+
+    /// Marks the encrypted votes as decrypted.
+    pub fn with_decryption(self) -> Self {
+        self.update_counters(|counters| counters.decrypted = true)
+    }
+
+    /// Returns the service keys of the trustees.
+    pub fn trustee_keys(&self) -> Vec<PublicKey> {
+        hashes_to_keys(self.trustees())
+    }
+
+    /// Returns the index of the trustee in the polynomials of the dealings, see `dkg`.
+    pub fn trustee_index(&self, trustee: &PublicKey) -> Option<u64> {
+        self.trustee_keys().iter().position(|key| key == trustee).map(|index| index as u64 + 1)
+    }
+
+    fn update_counters<F: FnOnce(&mut Counters)>(self, update: F) -> Self {
+        let mut counters = Counters {
+            electorate_size: self.electorate_size(),
+            turnout: self.turnout(),
+            total_weight: self.total_weight(),
+            candidates_count: self.candidates_count(),
+            delegations_count: self.delegations_count(),
+            unrevealed: self.unrevealed(),
+            decrypted: self.decrypted(),
+        };
+        update(&mut counters);
+        Election::new(
+            self.id(),
+            self.author(),
+            self.name(),
+            self.scheme(),
+            self.voting_height(),
+            self.tallying_height(),
+            self.closing_height(),
+            self.seats(),
+            self.max_choices(),
+            counters.electorate_size,
+            counters.turnout,
+            counters.total_weight,
+            counters.candidates_count,
+            counters.delegations_count,
+            counters.unrevealed,
+            self.trustees(),
+            self.threshold(),
+            self.dealing_height(),
+            counters.decrypted,
+            self.ring_size(),
+            self.withdrawn_mode(),
+            self.revoked_mode(),
+            self.turnout_bucket_size(),
+            self.candidates_hash(),
+            self.electorate_hash(),
+            self.ballots_hash(),
+            self.delegations_hash(),
+            self.commitments_hash(),
+            self.key_shares_hash(),
+            self.decryptions_hash(),
+            self.key_images_hash(),
+            self.tokens_hash(),
+            self.spent_tokens_hash(),
+            self.token_sessions_hash(),
+            self.issuing_keys_hash(),
+            self.vote_records_hash(),
+            self.turnout_buckets_hash(),
+            self.turnout_checkpoints_hash(),
+            self.revocations_hash(),
+            self.key_aliases_hash(),
+            self.ring_hash(),
+        )
+    }
+
+    pub fn voting_scheme(&self) -> VotingScheme {
+        VotingScheme::from_u8(self.scheme()).expect("Unknown voting scheme.")
+    }
+
+    pub fn withdrawn_votes(&self) -> WithdrawnVotes {
+        WithdrawnVotes::from_u8(self.withdrawn_mode()).expect("Unknown withdrawn votes mode.")
+    }
+
+    pub fn revoked_votes(&self) -> RevokedVotes {
+        RevokedVotes::from_u8(self.revoked_mode()).expect("Unknown revoked votes mode.")
+    }
+
+    /// Checks that the vote of the elector is counted in the tally.
+    pub fn counts_vote(&self, elector: &Elector) -> bool {
+        // A hidden vote is counted once it is revealed.
+        !elector.has_vote()
+            && (self.voting_scheme() != VotingScheme::CommitReveal || elector.candidate() != &PublicKey::zero())
+    }
+
+    /// Returns the phase of the election for a block at the given height.
+    pub fn phase(&self, height: Height) -> ElectionPhase {
+        let height = height.0;
+        if height < self.voting_height() {
+            ElectionPhase::Registration
+        } else if height < self.tallying_height() {
+            ElectionPhase::Voting
+        } else if height < self.closing_height() {
+            ElectionPhase::Tallying
+        } else {
+            ElectionPhase::Closed
+        }
+    }
+}
 
 encoding_struct! {
     struct Candidate {
         pub_key: &PublicKey,
         name: &str,
         history_hash: &Hash,
+        /// Root of the candidate's votes by heights, see `VoicesCheckpoint`.
+        checkpoints_hash: &Hash,
         voices: u64,
+        /// See `CandidateStatus`.
+        status: u8,
+        /// Sum of the encrypted votes, see `elgamal::Ciphertext`.
+        encrypted_a: &Hash,
+        encrypted_b: &Hash,
     }
 }
 
 impl Candidate {
-    pub fn add_voice(self, history_hash: &Hash) -> Self {
-        Candidate::new(self.pub_key(), self.name(), history_hash, self.voices() + 1)
+    /// Creates a candidate without votes.
+    pub fn registered(pub_key: &PublicKey, name: &str, history_hash: &Hash) -> Self {
+        let (encrypted_a, encrypted_b) = Ciphertext::zero().to_hashes();
+        let status = CandidateStatus::Active as u8;
+        Candidate::new(pub_key, name, history_hash, &Hash::zero(), 0, status, &encrypted_a, &encrypted_b)
+    }
+
+    /// Adds the voice of an elector with the given weight.
+    pub fn add_voice(self, history_hash: &Hash, weight: u64) -> Self {
+        Candidate::new(
+            self.pub_key(),
+            self.name(),
+            history_hash,
+            self.checkpoints_hash(),
+            self.voices() + weight,
+            self.status(),
+            self.encrypted_a(),
+            self.encrypted_b(),
+        )
+    }
+
+    /// Takes back the voice of an elector with the given weight.
+    pub fn remove_voice(self, history_hash: &Hash, weight: u64) -> Self {
+        Candidate::new(
+            self.pub_key(),
+            self.name(),
+            history_hash,
+            self.checkpoints_hash(),
+            self.voices() - weight,
+            self.status(),
+            self.encrypted_a(),
+            self.encrypted_b(),
+        )
+    }
+
+    /// Returns the sum of the encrypted votes for the candidate.
+    pub fn encrypted_votes(&self) -> Ciphertext {
+        Ciphertext::from_hashes(self.encrypted_a(), self.encrypted_b()).expect("Invalid encrypted votes.")
+    }
+
+    /// Adds the encrypted vote to the sum.
+    pub fn add_encrypted_vote(self, history_hash: &Hash, vote: &Ciphertext) -> Self {
+        let (encrypted_a, encrypted_b) = self.encrypted_votes().add(vote).to_hashes();
+        Candidate::new(
+            self.pub_key(),
+            self.name(),
+            history_hash,
+            self.checkpoints_hash(),
+            self.voices(),
+            self.status(),
+            &encrypted_a,
+            &encrypted_b,
+        )
+    }
+
+    fn with_checkpoints(self, checkpoints_hash: &Hash) -> Self {
+        Candidate::new(
+            self.pub_key(),
+            self.name(),
+            self.history_hash(),
+            checkpoints_hash,
+            self.voices(),
+            self.status(),
+            self.encrypted_a(),
+            self.encrypted_b(),
+        )
+    }
+
+    /// Withdraws the candidate with the given status and the new history root.
+    pub fn deactivate(self, history_hash: &Hash, status: CandidateStatus) -> Self {
+        Candidate::new(
+            self.pub_key(),
+            self.name(),
+            history_hash,
+            self.checkpoints_hash(),
+            self.voices(),
+            status as u8,
+            self.encrypted_a(),
+            self.encrypted_b(),
+        )
+    }
+
+    /// Moves the candidate to the new key with the history moved along.
+    pub fn rekey(self, pub_key: &PublicKey, history_hash: &Hash) -> Self {
+        Candidate::new(
+            pub_key,
+            self.name(),
+            history_hash,
+            self.checkpoints_hash(),
+            self.voices(),
+            self.status(),
+            self.encrypted_a(),
+            self.encrypted_b(),
+        )
+    }
+
+    pub fn candidate_status(&self) -> CandidateStatus {
+        CandidateStatus::from_u8(self.status()).expect("Unknown candidate status.")
+    }
+
+    /// Returns true unless the candidate is withdrawn or disqualified.
+    pub fn is_active(&self) -> bool {
+        self.candidate_status() == CandidateStatus::Active
+    }
+}
+
+/// Whether the candidate takes part in the election.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum CandidateStatus {
+    Active = 0,
+    /// Withdrawn by the candidate.
+    Withdrawn = 1,
+    /// Disqualified by a registrar.
+    Disqualified = 2,
+}
+
+impl CandidateStatus {
+    pub fn from_u8(value: u8) -> Option<CandidateStatus> {
+        match value {
+            0 => Some(CandidateStatus::Active),
+            1 => Some(CandidateStatus::Withdrawn),
+            2 => Some(CandidateStatus::Disqualified),
+            _ => None,
+        }
     }
 }
 
@@ -27,15 +509,348 @@ encoding_struct! {
         pub_key: &PublicKey,
         name: &str,
         has_vote: bool,
+        candidate: &PublicKey,
+        ballot: &Hash,
+        weight: u64,
+        /// The eligibility of the elector is revoked by a registrar, see `Revocation`.
+        revoked: bool,
     }
 }
 
 impl Elector {
-    pub fn vote(self) -> Self {
-        Elector::new(self.pub_key(), self.name(), false)
+    /// Creates an elector who has not voted yet.
+    pub fn registered(pub_key: &PublicKey, name: &str, weight: u64) -> Self {
+        Elector::new(pub_key, name, true, &PublicKey::zero(), &Hash::zero(), weight, false)
+    }
+
+    /// Records the candidate backed by the elector and the hash of the ballot transaction.
+    pub fn vote(self, candidate: &PublicKey, ballot: &Hash) -> Self {
+        Elector::new(self.pub_key(), self.name(), false, candidate, ballot, self.weight(), self.revoked())
+    }
+
+    /// Returns the elector's voice.
+    pub fn revoke(self) -> Self {
+        Elector::new(self.pub_key(), self.name(), true, &PublicKey::zero(), &Hash::zero(), self.weight(), self.revoked())
+    }
+
+    /// Moves the elector to the new key.
+    pub fn rekey(self, pub_key: &PublicKey) -> Self {
+        Elector::new(pub_key, self.name(), self.has_vote(), self.candidate(), self.ballot(), self.weight(), self.revoked())
+    }
+
+    /// Revokes the eligibility of the elector, the cast vote is kept.
+    pub fn deregister(self) -> Self {
+        Elector::new(self.pub_key(), self.name(), self.has_vote(), self.candidate(), self.ballot(), self.weight(), true)
+    }
+}
+
+encoding_struct! {
+    /// Elector registered by `RegisterElectorsBatch`.
+    struct ElectorEntry {
+        pub_key: &PublicKey,
+        name: &str,
+        weight: u64,
+    }
+}
+
+/// Why the eligibility of an elector is revoked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum RevocationReason {
+    /// The elector has left the organisation.
+    Left = 0,
+    /// The elector is registered twice under different keys.
+    Duplicate = 1,
+    /// The elector doesn't meet the eligibility rules.
+    Ineligible = 2,
+    /// The key of the elector is compromised.
+    Compromised = 3,
+    Other = 255,
+}
+
+impl RevocationReason {
+    pub fn from_u8(value: u8) -> Option<RevocationReason> {
+        match value {
+            0 => Some(RevocationReason::Left),
+            1 => Some(RevocationReason::Duplicate),
+            2 => Some(RevocationReason::Ineligible),
+            3 => Some(RevocationReason::Compromised),
+            255 => Some(RevocationReason::Other),
+            _ => None,
+        }
+    }
+}
+
+encoding_struct! {
+    /// Entry of the audit trail of the revoked electors.
+    struct Revocation {
+        elector: &PublicKey,
+        registrar: &PublicKey,
+        /// See `RevocationReason`.
+        reason: u8,
+        height: u64,
+        tx_hash: &Hash,
+        /// The cast vote of the elector is taken back.
+        voided: bool,
+    }
+}
+
+encoding_struct! {
+    /// Ballot with the candidates chosen by the elector.
+    ///
+    /// The order of the candidates is the order of the elector's preference.
+    struct Ballot {
+        elector: &PublicKey,
+        candidates: &[Hash],
+    }
+}
+
+impl Ballot {
+    /// Returns the public keys of the chosen candidates.
+    pub fn choices(&self) -> Vec<PublicKey> {
+        hashes_to_keys(self.candidates())
+    }
+}
+
+/// Converts the candidate keys into a list which can be stored in a message.
+///
+/// Exonum encodes lists of `Hash` only, a public key has the same size.
+pub fn keys_to_hashes(keys: &[PublicKey]) -> Vec<Hash> {
+    keys.iter()
+        .map(|key| Hash::from_slice(key.as_ref()).unwrap())
+        .collect()
+}
+
+/// Converts a list created by `keys_to_hashes` back into the candidate keys.
+pub fn hashes_to_keys(hashes: &[Hash]) -> Vec<PublicKey> {
+    hashes.iter()
+        .map(|hash| PublicKey::from_slice(hash.as_ref()).unwrap())
+        .collect()
+}
+
+encoding_struct! {
+    /// Delegation of the voting power of an elector to another elector.
+    struct Delegation {
+        from: &PublicKey,
+        to: &PublicKey,
+    }
+}
+
+encoding_struct! {
+    /// Hidden vote of an elector, see `vote_commitment`.
+    struct Commitment {
+        elector: &PublicKey,
+        /// Key of the elector at the commitment, which the commitment is bound to.
+        committer: &PublicKey,
+        commitment: &Hash,
+        revealed: bool,
+    }
+}
+
+impl Commitment {
+    pub fn reveal(self) -> Self {
+        Commitment::new(self.elector(), self.committer(), self.commitment(), true)
+    }
+}
+
+/// Returns the commitment of the elector to the vote for the candidate in the election.
+///
+/// The salt is a random value kept secret by the elector until the reveal. The commitment is bound
+/// to the election and the elector, so it can't be copied and revealed by another elector.
+pub fn vote_commitment(election_id: &Hash, elector: &PublicKey, candidate: &PublicKey, salt: &Hash) -> Hash {
+    let mut data = election_id.as_ref().to_vec();
+    data.extend_from_slice(elector.as_ref());
+    data.extend_from_slice(candidate.as_ref());
+    data.extend_from_slice(salt.as_ref());
+    hash(&data)
+}
+
+encoding_struct! {
+    /// Encrypted choice of a candidate with the proof that it is 0 or 1.
+    struct EncryptedChoice {
+        candidate: &PublicKey,
+        a: &Hash,
+        b: &Hash,
+        challenges: &[Hash],
+        responses: &[Hash],
     }
 }
 
+encoding_struct! {
+    /// Dealing of the election key by a trustee, see `dkg::Dealing`.
+    struct KeyShare {
+        validator: &PublicKey,
+        /// Commitments to the coefficients of the polynomial, the first one is the key share.
+        commitments: &[Hash],
+        ephemeral: &Hash,
+        /// Shares of the trustees encrypted with the ephemeral key, in the order of the trustees.
+        shares: &[Hash],
+        /// Whether the dealer is disqualified by a complaint of a trustee.
+        disqualified: bool,
+    }
+}
+
+impl KeyShare {
+    /// Returns the key share of the dealer.
+    pub fn key(&self) -> Hash {
+        self.commitments()[0]
+    }
+
+    /// Returns the commitments to the coefficients of the polynomial.
+    pub fn commitment_points(&self) -> Vec<RistrettoPoint> {
+        self.commitments().iter()
+            .map(|commitment| elgamal::hash_to_point(commitment).expect("Invalid commitment."))
+            .collect()
+    }
+
+    pub fn disqualify(self) -> Self {
+        KeyShare::new(self.validator(), self.commitments(), self.ephemeral(), self.shares(), true)
+    }
+}
+
+encoding_struct! {
+    /// Decryption share of a trustee for the encrypted votes of a candidate,
+    /// with the proof that it matches the public share of the trustee.
+    struct DecryptionShare {
+        candidate: &PublicKey,
+        share: &Hash,
+        challenge: &Hash,
+        response: &Hash,
+    }
+}
+
+encoding_struct! {
+    /// Decryption shares of a trustee for all the candidates of an election.
+    struct PartialDecryption {
+        validator: &PublicKey,
+        shares: Vec<DecryptionShare>,
+    }
+}
+
+encoding_struct! {
+    /// Nonces committed by a registrar for the token of an elector, see `blind`.
+    struct TokenSession {
+        registrar: &PublicKey,
+        issuing_key: &PublicKey,
+        commitments: &[Hash],
+        tx_hash: &Hash,
+    }
+}
+
+encoding_struct! {
+    /// Transaction which cast the current ballot of an elector and the height of its block.
+    struct VoteRecord {
+        tx_hash: &Hash,
+        height: u64,
+        /// Height of the block which counted the ballot in the turnout, the changed ballot stays counted in it.
+        counted_height: u64,
+    }
+}
+
+encoding_struct! {
+    /// Number of the ballots cast in the blocks from `height` to the start of the next bucket.
+    struct TurnoutBucket {
+        height: u64,
+        ballots: u64,
+    }
+}
+
+encoding_struct! {
+    /// Votes of a candidate after the block at `height`.
+    struct VoicesCheckpoint {
+        height: u64,
+        voices: u64,
+    }
+}
+
+encoding_struct! {
+    /// Turnout of an election after the block at `height`.
+    struct TurnoutCheckpoint {
+        height: u64,
+        turnout: u64,
+        total_weight: u64,
+    }
+}
+
+/// Returns the index of the last checkpoint made at or before the given height.
+///
+/// The checkpoints are sorted by height, `height_at` returns the height of the checkpoint by index.
+pub fn last_checkpoint<F: Fn(u64) -> u64>(len: u64, height_at: F, height: u64) -> Option<u64> {
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if height_at(middle) <= height {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    low.checked_sub(1)
+}
+
+/// Returns the key of the turnout bucket starting at the given height.
+pub fn turnout_bucket_id(height: u64) -> Hash {
+    hash(&height.to_be_bytes())
+}
+
+/// Returns the message of the anonymous ballot signed by the ring signature.
+///
+/// The ballot key is a one-time key which signs the transaction.
+pub fn anonymous_ballot(election: &Hash, candidate: &PublicKey, ballot_key: &PublicKey) -> Hash {
+    let mut data = election.as_ref().to_vec();
+    data.extend_from_slice(candidate.as_ref());
+    data.extend_from_slice(ballot_key.as_ref());
+    hash(&data)
+}
+
+/// Returns the range of the ring positions forming the sub-ring with the given index.
+///
+/// The ring is split into the sub-rings of `ring_size` electors in the order of the ring,
+/// the last sub-ring takes the remainder, so a sub-ring has from `ring_size` to `2 * ring_size - 1`
+/// members unless the whole ring is smaller. Returns `None` if there is no such sub-ring.
+pub fn sub_ring_bounds(ring_len: u64, ring_size: u64, index: u64) -> Option<(u64, u64)> {
+    let ring_size = ring_size.max(1);
+    let count = (ring_len / ring_size).max(1);
+    if ring_len == 0 || index >= count {
+        return None;
+    }
+    let start = index * ring_size;
+    let end = if index + 1 == count { ring_len } else { start + ring_size };
+    Some((start, end))
+}
+
+encoding_struct! {
+    /// Replacement of the key of an elector or a candidate, see `key_rotation`.
+    struct KeyAlias {
+        old_key: &PublicKey,
+        new_key: &PublicKey,
+        /// Zero unless the old key is replaced by a registrar.
+        registrar: &PublicKey,
+        height: u64,
+        tx_hash: &Hash,
+    }
+}
+
+/// Returns the message signed by the new key of a rotation.
+pub fn key_rotation(election: &Hash, old_key: &PublicKey, new_key: &PublicKey) -> Hash {
+    let mut data = election.as_ref().to_vec();
+    data.extend_from_slice(old_key.as_ref());
+    data.extend_from_slice(new_key.as_ref());
+    hash(&data)
+}
+
+/// Key of the candidate's vote history in the `vote.history` family.
+fn history_key(election_id: &Hash, pub_key: &PublicKey) -> Vec<u8> {
+    let mut key = election_id.as_ref().to_vec();
+    key.extend_from_slice(pub_key.as_ref());
+    key
+}
+
+/// Returns the key of the candidate's vote history in the history roots.
+pub fn history_id(election_id: &Hash, pub_key: &PublicKey) -> Hash {
+    hash(&history_key(election_id, pub_key))
+}
+
 pub struct VoteSchema<T> {
     view: T,
 }
@@ -49,42 +864,506 @@ impl<T> VoteSchema<T> {
 impl<T: AsRef<dyn Snapshot>> VoteSchema<T> {
     pub fn state_hash(&self) -> Vec<Hash> {
         vec![
-            self.candidates().merkle_root(),
-            self.electorate().merkle_root()
+            self.elections().merkle_root(),
+            self.history_roots().merkle_root(),
+            self.ballot_log().merkle_root(),
         ]
     }
 
-    pub fn candidates(&self) -> ProofMapIndex<&dyn Snapshot, PublicKey, Candidate> {
-        ProofMapIndex::new(CANDIDATE_INDEX, self.view.as_ref())
+    /// Returns the actual configuration of the service.
+    ///
+    /// Returns `None` if the configuration accepted by the validators can't be parsed,
+    /// the transactions depending on it are rejected until it is replaced.
+    pub fn config(&self) -> Option<VoteConfig> {
+        let configuration = Schema::new(self.view.as_ref()).actual_configuration();
+        match configuration.services.get(SERVICE_NAME) {
+            Some(value) => serde_json::from_value(value.clone()).ok(),
+            None => Some(VoteConfig::default()),
+        }
+    }
+
+    /// Returns the height of the block which is being created.
+    pub fn height(&self) -> Height {
+        Height(Schema::new(self.view.as_ref()).block_hashes_by_height().len())
+    }
+
+    /// Returns the height of the block with the committed transaction.
+    pub fn tx_height(&self, tx_hash: &Hash) -> Option<Height> {
+        Schema::new(self.view.as_ref()).transactions_locations().get(tx_hash).map(|location| location.block_height())
+    }
+
+    /// Returns the service keys of the actual validators.
+    pub fn validators(&self) -> Vec<PublicKey> {
+        Schema::new(self.view.as_ref()).actual_configuration().validator_keys
+            .iter()
+            .map(|keys| keys.service_key)
+            .collect()
+    }
+
+    pub fn elections(&self) -> ProofMapIndex<&dyn Snapshot, Hash, Election> {
+        ProofMapIndex::new(ELECTION_INDEX, self.view.as_ref())
+    }
+
+    /// Returns the roots of the vote histories of all the candidates by `history_id`.
+    pub fn history_roots(&self) -> ProofMapIndex<&dyn Snapshot, Hash, Hash> {
+        ProofMapIndex::new(HISTORY_ROOT_INDEX, self.view.as_ref())
+    }
+
+    /// Returns the hashes of the ballot transactions of all the elections in the order of execution.
+    pub fn ballot_log(&self) -> ProofListIndex<&dyn Snapshot, Hash> {
+        ProofListIndex::new(BALLOT_LOG, self.view.as_ref())
+    }
+
+    /// Returns the positions of the logged ballot transactions in the ballot log.
+    pub fn ballot_positions(&self) -> MapIndex<&dyn Snapshot, Hash, u64> {
+        MapIndex::new(BALLOT_POSITION_INDEX, self.view.as_ref())
+    }
+
+    pub fn election(&self, election_id: &Hash) -> Option<Election> {
+        self.elections().get(election_id)
+    }
+
+    pub fn candidates(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, PublicKey, Candidate> {
+        ProofMapIndex::new_in_family(CANDIDATE_INDEX, election_id, self.view.as_ref())
+    }
+
+    pub fn candidate(&self, election_id: &Hash, pub_key: &PublicKey) -> Option<Candidate> {
+        self.candidates(election_id).get(pub_key)
+    }
+
+    pub fn electorate(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, PublicKey, Elector> {
+        ProofMapIndex::new_in_family(ELECTORATE_INDEX, election_id, self.view.as_ref())
+    }
+
+    pub fn elector(&self, election_id: &Hash, pub_key: &PublicKey) -> Option<Elector> {
+        self.electorate(election_id).get(pub_key)
+    }
+
+    pub fn vote_history(&self, election_id: &Hash, public_key: &PublicKey) -> ProofListIndex<&T, Hash> {
+        ProofListIndex::new_in_family(VOTE_HISTORY, &history_key(election_id, public_key), &self.view)
+    }
+
+    pub fn ballots(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, PublicKey, Ballot> {
+        ProofMapIndex::new_in_family(BALLOT_INDEX, election_id, self.view.as_ref())
+    }
+
+    pub fn ballot(&self, election_id: &Hash, elector: &PublicKey) -> Option<Ballot> {
+        self.ballots(election_id).get(elector)
+    }
+
+    /// Returns the electors whose ballots name the candidate.
+    pub fn backers(&self, election_id: &Hash, candidate: &PublicKey) -> KeySetIndex<&dyn Snapshot, PublicKey> {
+        KeySetIndex::new_in_family(BACKER_INDEX, &history_key(election_id, candidate), self.view.as_ref())
+    }
+
+    pub fn delegations(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, PublicKey, Delegation> {
+        ProofMapIndex::new_in_family(DELEGATION_INDEX, election_id, self.view.as_ref())
+    }
+
+    pub fn delegation(&self, election_id: &Hash, from: &PublicKey) -> Option<Delegation> {
+        self.delegations(election_id).get(from)
+    }
+
+    pub fn commitments(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, PublicKey, Commitment> {
+        ProofMapIndex::new_in_family(COMMITMENT_INDEX, election_id, self.view.as_ref())
+    }
+
+    pub fn commitment(&self, election_id: &Hash, elector: &PublicKey) -> Option<Commitment> {
+        self.commitments(election_id).get(elector)
+    }
+
+    pub fn key_shares(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, PublicKey, KeyShare> {
+        ProofMapIndex::new_in_family(KEY_SHARE_INDEX, election_id, self.view.as_ref())
+    }
+
+    pub fn key_share(&self, election_id: &Hash, validator: &PublicKey) -> Option<KeyShare> {
+        self.key_shares(election_id).get(validator)
+    }
+
+    /// Returns the dealings of the trustees which aren't disqualified.
+    pub fn qualified_dealings(&self, election_id: &Hash) -> Vec<KeyShare> {
+        self.key_shares(election_id).values()
+            .filter(|dealing| !dealing.disqualified())
+            .collect()
+    }
+
+    /// Returns the election key, the sum of the key shares of the qualified dealings.
+    pub fn encryption_key(&self, election_id: &Hash) -> RistrettoPoint {
+        self.qualified_dealings(election_id).iter()
+            .map(|dealing| elgamal::hash_to_point(&dealing.key()).expect("Invalid key share."))
+            .fold(RistrettoPoint::identity(), |key, share| key + share)
+    }
+
+    /// Returns the public share of the trustee with the given index, see `dkg::public_share`.
+    pub fn public_share(&self, election_id: &Hash, index: u64) -> RistrettoPoint {
+        let dealings: Vec<Vec<RistrettoPoint>> = self.qualified_dealings(election_id).iter()
+            .map(KeyShare::commitment_points)
+            .collect();
+        dkg::public_share(&dealings, index)
+    }
+
+    pub fn decryptions(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, PublicKey, PartialDecryption> {
+        ProofMapIndex::new_in_family(DECRYPTION_INDEX, election_id, self.view.as_ref())
+    }
+
+    pub fn decryption(&self, election_id: &Hash, validator: &PublicKey) -> Option<PartialDecryption> {
+        self.decryptions(election_id).get(validator)
+    }
+
+    /// Returns the key images of the anonymous ballots with the hashes of the ballot transactions.
+    pub fn key_images(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, Hash, Hash> {
+        ProofMapIndex::new_in_family(KEY_IMAGE_INDEX, election_id, self.view.as_ref())
+    }
+
+    /// Returns the electors who were issued a voting token with the hashes of the issuing transactions.
+    pub fn tokens(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, PublicKey, Hash> {
+        ProofMapIndex::new_in_family(TOKEN_INDEX, election_id, self.view.as_ref())
+    }
+
+    /// Returns the spent voting tokens with the hashes of the ballot transactions.
+    pub fn spent_tokens(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, PublicKey, Hash> {
+        ProofMapIndex::new_in_family(SPENT_TOKEN_INDEX, election_id, self.view.as_ref())
     }
 
-    pub fn candidate(&self, pub_key: &PublicKey) -> Option<Candidate> {
-        self.candidates().get(pub_key)
+    /// Returns the nonces committed by the registrars for the tokens of the electors.
+    pub fn token_sessions(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, PublicKey, TokenSession> {
+        ProofMapIndex::new_in_family(TOKEN_SESSION_INDEX, election_id, self.view.as_ref())
     }
 
-    pub fn electorate(&self) -> ProofMapIndex<&dyn Snapshot, PublicKey, Elector> {
-        ProofMapIndex::new(ELECTORATE_INDEX, self.view.as_ref())
+    pub fn token_session(&self, election_id: &Hash, elector: &PublicKey) -> Option<TokenSession> {
+        self.token_sessions(election_id).get(elector)
     }
 
-    pub fn elector(&self, pub_key: &PublicKey) -> Option<Elector> {
-        self.electorate().get(pub_key)
+    /// Returns the issuing keys of the registrars signing the tokens, see `blind::IssuingKey`.
+    pub fn issuing_keys(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, PublicKey, PublicKey> {
+        ProofMapIndex::new_in_family(ISSUING_KEY_INDEX, election_id, self.view.as_ref())
     }
 
-    pub fn vote_history(&self, public_key: &PublicKey) -> ProofListIndex<&T, Hash> {
-        ProofListIndex::new_in_family(VOTE_HISTORY, public_key, &self.view)
+    /// Returns the records of the ballots cast by the electors.
+    pub fn vote_records(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, PublicKey, VoteRecord> {
+        ProofMapIndex::new_in_family(VOTE_RECORD_INDEX, election_id, self.view.as_ref())
+    }
+
+    pub fn vote_record(&self, election_id: &Hash, elector: &PublicKey) -> Option<VoteRecord> {
+        self.vote_records(election_id).get(elector)
+    }
+
+    /// Returns the votes of the candidate by the heights of the blocks which changed them.
+    pub fn voices_checkpoints(&self, election_id: &Hash, pub_key: &PublicKey) -> ProofListIndex<&dyn Snapshot, VoicesCheckpoint> {
+        ProofListIndex::new_in_family(VOICES_CHECKPOINT, &history_key(election_id, pub_key), self.view.as_ref())
+    }
+
+    /// Returns the turnout of the election by the heights of the blocks which changed it.
+    pub fn turnout_checkpoints(&self, election_id: &Hash) -> ProofListIndex<&dyn Snapshot, TurnoutCheckpoint> {
+        ProofListIndex::new_in_family(TURNOUT_CHECKPOINT_INDEX, election_id, self.view.as_ref())
+    }
+
+    /// Returns the numbers of the ballots cast by the buckets of block heights, see `turnout_bucket_id`.
+    pub fn turnout_buckets(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, Hash, TurnoutBucket> {
+        ProofMapIndex::new_in_family(TURNOUT_BUCKET_INDEX, election_id, self.view.as_ref())
+    }
+
+    /// Returns the replaced keys of the electors and the candidates with their replacements.
+    pub fn key_aliases(&self, election_id: &Hash) -> ProofMapIndex<&dyn Snapshot, PublicKey, KeyAlias> {
+        ProofMapIndex::new_in_family(KEY_ALIAS_INDEX, election_id, self.view.as_ref())
+    }
+
+    /// Returns the candidates named by the ballot of the elector.
+    pub fn ballot_choices(&self, election_id: &Hash, elector: &Elector) -> Vec<PublicKey> {
+        match self.ballot(election_id, elector.pub_key()) {
+            Some(ballot) => ballot.choices(),
+            None => Some(*elector.candidate()).into_iter().filter(|key| *key != PublicKey::zero()).collect(),
+        }
+    }
+
+    /// Checks that the ballot of the elector names an active candidate,
+    /// the ballot naming only the withdrawn candidates is void.
+    pub fn counts_ballot(&self, election_id: &Hash, elector: &Elector) -> bool {
+        self.ballot_choices(election_id, elector).iter()
+            .filter_map(|key| self.candidate(election_id, key))
+            .any(|candidate| candidate.is_active())
+    }
+
+    /// Follows the replacements of the key up to the actual one.
+    pub fn resolve_key(&self, election_id: &Hash, pub_key: &PublicKey) -> PublicKey {
+        let aliases = self.key_aliases(election_id);
+        let mut pub_key = *pub_key;
+        while let Some(alias) = aliases.get(&pub_key) {
+            pub_key = *alias.new_key();
+        }
+        pub_key
+    }
+
+    /// Returns the ring of an `Anonymous` election frozen by the first ballot, see `freeze_ring`.
+    pub fn ring(&self, election_id: &Hash) -> ProofListIndex<&dyn Snapshot, PublicKey> {
+        ProofListIndex::new_in_family(RING_INDEX, election_id, self.view.as_ref())
+    }
+
+    /// Returns the number of the electors in the ring, the eligible electorate until it's frozen.
+    pub fn ring_len(&self, election_id: &Hash) -> u64 {
+        let ring = self.ring(election_id);
+        if ring.is_empty() {
+            self.electorate(election_id).values().filter(|elector| !elector.revoked()).count() as u64
+        } else {
+            ring.len()
+        }
+    }
+
+    /// Returns the members of the sub-ring with the given index, see `sub_ring_bounds`.
+    ///
+    /// Until the ring is frozen, the sub-rings are taken from the eligible electorate
+    /// in the order of the keys, which is the order of the frozen ring.
+    pub fn sub_ring(&self, election_id: &Hash, index: u64) -> Option<Vec<PublicKey>> {
+        let election = self.election(election_id)?;
+        let ring = self.ring(election_id);
+        let (start, end) = sub_ring_bounds(self.ring_len(election_id), u64::from(election.ring_size()), index)?;
+        let members = if ring.is_empty() {
+            self.electorate(election_id).values()
+                .filter(|elector| !elector.revoked())
+                .skip(start as usize)
+                .take((end - start) as usize)
+                .map(|elector| *elector.pub_key())
+                .collect()
+        } else {
+            ring.iter_from(start).take((end - start) as usize).collect()
+        };
+        Some(members)
+    }
+
+    /// Returns the revocations of the electors in the order of execution.
+    pub fn revocations(&self, election_id: &Hash) -> ProofListIndex<&dyn Snapshot, Revocation> {
+        ProofListIndex::new_in_family(REVOCATION_INDEX, election_id, self.view.as_ref())
     }
 }
 
 impl<'a> VoteSchema<&'a mut Fork> {
-    pub fn candidate_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, Candidate> {
-        ProofMapIndex::new(CANDIDATE_INDEX, &mut self.view)
+    pub fn elections_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Election> {
+        ProofMapIndex::new(ELECTION_INDEX, &mut self.view)
+    }
+
+    pub fn history_roots_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Hash> {
+        ProofMapIndex::new(HISTORY_ROOT_INDEX, &mut self.view)
+    }
+
+    pub fn ballot_log_mut(&mut self) -> ProofListIndex<&mut Fork, Hash> {
+        ProofListIndex::new(BALLOT_LOG, &mut self.view)
+    }
+
+    pub fn ballot_positions_mut(&mut self) -> MapIndex<&mut Fork, Hash, u64> {
+        MapIndex::new(BALLOT_POSITION_INDEX, &mut self.view)
+    }
+
+    /// Appends the ballot transaction to the ballot log unless it's already logged.
+    pub fn log_ballot(&mut self, tx_hash: Hash) {
+        if self.ballot_positions().contains(&tx_hash) {
+            return;
+        }
+        let position = self.ballot_log().len();
+        self.ballot_log_mut().push(tx_hash);
+        self.ballot_positions_mut().put(&tx_hash, position);
+    }
+
+    /// Appends the transaction to the candidate's vote history and returns the new history root.
+    pub fn push_history(&mut self, election_id: &Hash, pub_key: &PublicKey, tx_hash: Hash) -> Hash {
+        let history_hash = {
+            let mut history = self.vote_history_mut(election_id, pub_key);
+            history.push(tx_hash);
+            history.merkle_root()
+        };
+        self.history_roots_mut().put(&history_id(election_id, pub_key), history_hash);
+        history_hash
+    }
+
+    pub fn candidate_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, PublicKey, Candidate> {
+        ProofMapIndex::new_in_family(CANDIDATE_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn electorate_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, PublicKey, Elector> {
+        ProofMapIndex::new_in_family(ELECTORATE_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn vote_history_mut(&mut self, election_id: &Hash, public_key: &PublicKey) -> ProofListIndex<&mut Fork, Hash> {
+        ProofListIndex::new_in_family(VOTE_HISTORY, &history_key(election_id, public_key), &mut self.view)
+    }
+
+    pub fn ballots_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, PublicKey, Ballot> {
+        ProofMapIndex::new_in_family(BALLOT_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn backers_mut(&mut self, election_id: &Hash, candidate: &PublicKey) -> KeySetIndex<&mut Fork, PublicKey> {
+        KeySetIndex::new_in_family(BACKER_INDEX, &history_key(election_id, candidate), &mut self.view)
+    }
+
+    pub fn delegations_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, PublicKey, Delegation> {
+        ProofMapIndex::new_in_family(DELEGATION_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn commitments_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, PublicKey, Commitment> {
+        ProofMapIndex::new_in_family(COMMITMENT_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn key_shares_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, PublicKey, KeyShare> {
+        ProofMapIndex::new_in_family(KEY_SHARE_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn decryptions_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, PublicKey, PartialDecryption> {
+        ProofMapIndex::new_in_family(DECRYPTION_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn key_images_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, Hash, Hash> {
+        ProofMapIndex::new_in_family(KEY_IMAGE_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn tokens_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, PublicKey, Hash> {
+        ProofMapIndex::new_in_family(TOKEN_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn spent_tokens_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, PublicKey, Hash> {
+        ProofMapIndex::new_in_family(SPENT_TOKEN_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn token_sessions_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, PublicKey, TokenSession> {
+        ProofMapIndex::new_in_family(TOKEN_SESSION_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn issuing_keys_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, PublicKey, PublicKey> {
+        ProofMapIndex::new_in_family(ISSUING_KEY_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn vote_records_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, PublicKey, VoteRecord> {
+        ProofMapIndex::new_in_family(VOTE_RECORD_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn turnout_buckets_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, Hash, TurnoutBucket> {
+        ProofMapIndex::new_in_family(TURNOUT_BUCKET_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn voices_checkpoints_mut(&mut self, election_id: &Hash, pub_key: &PublicKey) -> ProofListIndex<&mut Fork, VoicesCheckpoint> {
+        ProofListIndex::new_in_family(VOICES_CHECKPOINT, &history_key(election_id, pub_key), &mut self.view)
+    }
+
+    pub fn turnout_checkpoints_mut(&mut self, election_id: &Hash) -> ProofListIndex<&mut Fork, TurnoutCheckpoint> {
+        ProofListIndex::new_in_family(TURNOUT_CHECKPOINT_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn revocations_mut(&mut self, election_id: &Hash) -> ProofListIndex<&mut Fork, Revocation> {
+        ProofListIndex::new_in_family(REVOCATION_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn key_aliases_mut(&mut self, election_id: &Hash) -> ProofMapIndex<&mut Fork, PublicKey, KeyAlias> {
+        ProofMapIndex::new_in_family(KEY_ALIAS_INDEX, election_id, &mut self.view)
+    }
+
+    pub fn ring_mut(&mut self, election_id: &Hash) -> ProofListIndex<&mut Fork, PublicKey> {
+        ProofListIndex::new_in_family(RING_INDEX, election_id, &mut self.view)
+    }
+
+    /// Freezes the ring of an `Anonymous` election, the eligible electors in the order of the keys.
+    ///
+    /// The electorate doesn't change during the voting, so the ring frozen by the first ballot
+    /// is the electorate at the start of the voting.
+    pub fn freeze_ring(&mut self, election_id: &Hash) {
+        if !self.ring(election_id).is_empty() {
+            return;
+        }
+        let members: Vec<PublicKey> = self.electorate(election_id).values()
+            .filter(|elector| !elector.revoked())
+            .map(|elector| *elector.pub_key())
+            .collect();
+        self.ring_mut(election_id).extend(members);
+    }
+
+    /// Moves the vote history and the checkpoints of the candidate to the new key.
+    pub fn move_history(&mut self, election_id: &Hash, old_key: &PublicKey, new_key: &PublicKey) {
+        let history: Vec<Hash> = self.vote_history(election_id, old_key).iter().collect();
+        let checkpoints: Vec<VoicesCheckpoint> = self.voices_checkpoints(election_id, old_key).iter().collect();
+        self.vote_history_mut(election_id, old_key).clear();
+        self.voices_checkpoints_mut(election_id, old_key).clear();
+        self.vote_history_mut(election_id, new_key).extend(history);
+        self.voices_checkpoints_mut(election_id, new_key).extend(checkpoints);
+        self.history_roots_mut().remove(&history_id(election_id, old_key));
+    }
+
+    /// Stores the candidate with the checkpoint of its votes at the current height.
+    pub fn put_candidate(&mut self, election_id: &Hash, candidate: Candidate) {
+        let height = self.height().0;
+        let checkpoints_hash = {
+            let mut checkpoints = self.voices_checkpoints_mut(election_id, candidate.pub_key());
+            let checkpoint = VoicesCheckpoint::new(height, candidate.voices());
+            // Only the last state of the block is kept.
+            match checkpoints.last() {
+                Some(ref last) if last.height() == height => {
+                    let index = checkpoints.len() - 1;
+                    checkpoints.set(index, checkpoint);
+                }
+                _ => checkpoints.push(checkpoint),
+            }
+            checkpoints.merkle_root()
+        };
+        let pub_key = *candidate.pub_key();
+        self.candidate_mut(election_id).put(&pub_key, candidate.with_checkpoints(&checkpoints_hash));
     }
 
-    pub fn electorate_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, Elector> {
-        ProofMapIndex::new(ELECTORATE_INDEX, &mut self.view)
+    /// Stores the election with the checkpoint of its turnout at the current height.
+    pub fn put_turnout(&mut self, election_id: &Hash, election: Election) {
+        let height = self.height().0;
+        {
+            let mut checkpoints = self.turnout_checkpoints_mut(election_id);
+            let checkpoint = TurnoutCheckpoint::new(height, election.turnout(), election.total_weight());
+            match checkpoints.last() {
+                Some(ref last) if last.height() == height => {
+                    let index = checkpoints.len() - 1;
+                    checkpoints.set(index, checkpoint);
+                }
+                _ => checkpoints.push(checkpoint),
+            }
+        }
+        self.elections_mut().put(election_id, election);
     }
 
-    pub fn vote_history_mut(&mut self, public_key: &PublicKey) -> ProofListIndex<&mut Fork, Hash> {
-        ProofListIndex::new_in_family(VOTE_HISTORY, public_key, &mut self.view)
+    /// Stores the current roots of the election indices in the election record,
+    /// so that they are covered by the service state hash.
+    pub fn refresh_election(&mut self, election_id: &Hash) {
+        if let Some(election) = self.election(election_id) {
+            let candidates_hash = self.candidates(election_id).merkle_root();
+            let electorate_hash = self.electorate(election_id).merkle_root();
+            let ballots_hash = self.ballots(election_id).merkle_root();
+            let delegations_hash = self.delegations(election_id).merkle_root();
+            let commitments_hash = self.commitments(election_id).merkle_root();
+            let key_shares_hash = self.key_shares(election_id).merkle_root();
+            let decryptions_hash = self.decryptions(election_id).merkle_root();
+            let key_images_hash = self.key_images(election_id).merkle_root();
+            let tokens_hash = self.tokens(election_id).merkle_root();
+            let spent_tokens_hash = self.spent_tokens(election_id).merkle_root();
+            let token_sessions_hash = self.token_sessions(election_id).merkle_root();
+            let issuing_keys_hash = self.issuing_keys(election_id).merkle_root();
+            let vote_records_hash = self.vote_records(election_id).merkle_root();
+            let turnout_buckets_hash = self.turnout_buckets(election_id).merkle_root();
+            let turnout_checkpoints_hash = self.turnout_checkpoints(election_id).merkle_root();
+            let revocations_hash = self.revocations(election_id).merkle_root();
+            let key_aliases_hash = self.key_aliases(election_id).merkle_root();
+            let ring_hash = self.ring(election_id).merkle_root();
+            let election = election.update_hashes(
+                &candidates_hash,
+                &electorate_hash,
+                &ballots_hash,
+                &delegations_hash,
+                &commitments_hash,
+                &key_shares_hash,
+                &decryptions_hash,
+                &key_images_hash,
+                &tokens_hash,
+                &spent_tokens_hash,
+                &token_sessions_hash,
+                &issuing_keys_hash,
+                &vote_records_hash,
+                &turnout_buckets_hash,
+                &turnout_checkpoints_hash,
+                &revocations_hash,
+                &key_aliases_hash,
+                &ring_hash,
+            );
+            self.elections_mut().put(election_id, election);
+        }
     }
 }
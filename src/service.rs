@@ -1,4 +1,5 @@
 use api::PublicApi;
+use config::VoteConfig;
 use schema::VoteSchema;
 use transactions::VoteTransactions;
 
@@ -6,13 +7,23 @@ use exonum::{
     api::ServiceApiBuilder,
     blockchain::{Transaction, TransactionSet, Service},
     crypto::Hash, encoding::Error as StreamStructError,
-    messages::RawTransaction, storage::Snapshot,
+    messages::RawTransaction, storage::{Fork, Snapshot},
 };
+use serde_json::{self, Value};
 
 pub const VOTE_SERVICE: u16 = 13;
 pub const SERVICE_NAME: &str = "vote";
 
-pub struct VoteService;
+#[derive(Debug, Default)]
+pub struct VoteService {
+    config: VoteConfig,
+}
+
+impl VoteService {
+    pub fn new(config: VoteConfig) -> Self {
+        VoteService { config }
+    }
+}
 
 impl Service for VoteService {
     fn service_id(&self) -> u16 {
@@ -28,6 +39,10 @@ impl Service for VoteService {
         schema.state_hash()
     }
 
+    fn initialize(&self, _fork: &mut Fork) -> Value {
+        serde_json::to_value(&self.config).unwrap()
+    }
+
     fn tx_from_raw(&self, raw: RawTransaction) -> Result<Box<Transaction>, StreamStructError> {
         let tx = VoteTransactions::tx_from_raw(raw)?;
         Ok(tx.into())
@@ -0,0 +1,182 @@
+//! Tallying of the ballots.
+
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use exonum::crypto::PublicKey;
+
+use schema::{Election, Elector};
+
+/// Number of votes of a candidate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandidateTally {
+    pub pub_key: PublicKey,
+    pub votes: u64,
+}
+
+/// Candidates which took the seats of an election.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Seats {
+    /// Seated candidates, the candidate with most votes first.
+    pub winners: Vec<PublicKey>,
+    /// Candidates with equal votes competing for the remaining seats, they are not seated.
+    pub tied: Vec<PublicKey>,
+}
+
+/// Seats the candidates with the most votes.
+///
+/// If the candidates with the same number of votes as the last seated candidate
+/// don't fit into the remaining seats, they are reported as tied.
+pub fn top_candidates(tallies: &[CandidateTally], seats: u32) -> Seats {
+    let mut sorted = tallies.to_vec();
+    sorted.sort_by_key(|tally| Reverse(tally.votes));
+    let seats = seats as usize;
+
+    if seats == 0 {
+        return Seats { winners: Vec::new(), tied: Vec::new() };
+    }
+    if sorted.len() <= seats {
+        let winners = sorted.iter().map(|tally| tally.pub_key).collect();
+        return Seats { winners, tied: Vec::new() };
+    }
+
+    let cutoff = sorted[seats - 1].votes;
+    let winners: Vec<PublicKey> = sorted.iter()
+        .filter(|tally| tally.votes > cutoff)
+        .map(|tally| tally.pub_key)
+        .collect();
+    let tied: Vec<PublicKey> = sorted.iter()
+        .filter(|tally| tally.votes == cutoff)
+        .map(|tally| tally.pub_key)
+        .collect();
+
+    if winners.len() + tied.len() <= seats {
+        Seats { winners: winners.into_iter().chain(tied).collect(), tied: Vec::new() }
+    } else {
+        Seats { winners, tied }
+    }
+}
+
+/// Ballot counted with the weight of its elector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedBallot {
+    pub weight: u64,
+    /// Chosen candidates in the order of preference.
+    pub choices: Vec<PublicKey>,
+}
+
+/// Round of the instant-runoff.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IrvRound {
+    /// Votes of the candidates remaining in the round.
+    pub tallies: Vec<CandidateTally>,
+    /// Total weight of the ballots without remaining candidates.
+    pub exhausted: u64,
+    /// Candidates eliminated at the end of the round.
+    pub eliminated: Vec<PublicKey>,
+}
+
+/// Outcome of the instant-runoff.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IrvOutcome {
+    pub rounds: Vec<IrvRound>,
+    /// The winner, `None` if there are no votes or the remaining candidates are tied.
+    pub winner: Option<PublicKey>,
+}
+
+/// Computes the instant-runoff rounds.
+///
+/// Every round the ballots are counted with their weight for the highest ranked remaining candidate.
+/// A candidate with the majority of the counted ballots wins, otherwise all the candidates
+/// with the fewest votes are eliminated. If all the remaining candidates have the same number
+/// of votes, the election is tied.
+pub fn instant_runoff(candidates: &[PublicKey], ballots: &[WeightedBallot]) -> IrvOutcome {
+    let mut remaining = candidates.to_vec();
+    let mut rounds = Vec::new();
+
+    while !remaining.is_empty() {
+        let mut tallies: Vec<CandidateTally> = remaining.iter()
+            .map(|pub_key| CandidateTally { pub_key: *pub_key, votes: 0 })
+            .collect();
+        let mut exhausted = 0;
+
+        for ballot in ballots {
+            let choice = ballot.choices.iter()
+                .filter_map(|choice| tallies.iter().position(|tally| &tally.pub_key == choice))
+                .next();
+            match choice {
+                Some(idx) => tallies[idx].votes += ballot.weight,
+                None => exhausted += ballot.weight,
+            }
+        }
+
+        let counted: u64 = tallies.iter().map(|tally| tally.votes).sum();
+        let leader = tallies.iter().max_by_key(|tally| tally.votes).cloned();
+        if let Some(leader) = leader.filter(|leader| leader.votes * 2 > counted) {
+            rounds.push(IrvRound { tallies, exhausted, eliminated: Vec::new() });
+            return IrvOutcome { rounds, winner: Some(leader.pub_key) };
+        }
+
+        let fewest = tallies.iter().map(|tally| tally.votes).min().unwrap_or(0);
+        let eliminated: Vec<PublicKey> = tallies.iter()
+            .filter(|tally| tally.votes == fewest)
+            .map(|tally| tally.pub_key)
+            .collect();
+        if eliminated.len() == remaining.len() {
+            rounds.push(IrvRound { tallies, exhausted, eliminated: Vec::new() });
+            break;
+        }
+
+        remaining.retain(|pub_key| !eliminated.contains(pub_key));
+        rounds.push(IrvRound { tallies, exhausted, eliminated });
+    }
+
+    IrvOutcome { rounds, winner: None }
+}
+
+/// Finds the elector who votes on behalf of `elector`.
+///
+/// The delegation chain is followed until an elector who has voted directly,
+/// so a direct vote overrides the delegation. Returns `None` if the chain ends
+/// without a vote or contains a cycle.
+pub fn resolve_delegation<D, V>(elector: &PublicKey, delegate: D, has_voted: V) -> Option<PublicKey>
+where
+    D: Fn(&PublicKey) -> Option<PublicKey>,
+    V: Fn(&PublicKey) -> bool,
+{
+    let mut visited = vec![*elector];
+    let mut current = *elector;
+    loop {
+        if has_voted(&current) {
+            return Some(current);
+        }
+        current = delegate(&current)?;
+        if visited.contains(&current) {
+            return None;
+        }
+        visited.push(current);
+    }
+}
+
+/// Sums the weight delegated to the electors whose votes are counted, by the voter.
+///
+/// The delegation of an elector who has voted directly is ignored.
+pub fn delegated_weights<I, D, E>(election: &Election, delegators: I, delegate: D, elector: E) -> BTreeMap<PublicKey, u64>
+where
+    I: IntoIterator<Item = PublicKey>,
+    D: Fn(&PublicKey) -> Option<PublicKey>,
+    E: Fn(&PublicKey) -> Option<Elector>,
+{
+    let mut delegated = BTreeMap::new();
+    for from in delegators {
+        let weight = match elector(&from) {
+            Some(ref delegator) if delegator.has_vote() => delegator.weight(),
+            _ => continue,
+        };
+        let voter = resolve_delegation(&from, &delegate, |key| elector(key).map_or(false, |e| !e.has_vote()));
+        let voter = voter.filter(|voter| elector(voter).map_or(false, |e| election.counts_vote(&e)));
+        if let Some(voter) = voter {
+            *delegated.entry(voter).or_insert(0) += weight;
+        }
+    }
+    delegated
+}
@@ -1,28 +1,243 @@
+use std::collections::HashSet;
+
+use curve25519_dalek::ristretto::RistrettoPoint;
 use exonum::{
-    blockchain::{ExecutionError, Transaction}, crypto::{PublicKey, CryptoHash},
-    messages::Message, storage::Fork,
+    blockchain::{ExecutionError, Transaction}, crypto::{self, Hash, PublicKey, Signature, CryptoHash},
+    helpers::Height, messages::Message, storage::{Fork, Snapshot},
 };
 use service;
-use schema::{VoteSchema, Candidate, Elector};
-use errors::Error;
+use config::{RevokedVotes, VoteConfig, VotingScheme, WithdrawnVotes, CONFIGURED_SCHEME};
+use blind;
+use elgamal::{self, Ciphertext, EqualityProof, RangeProof};
+use dkg;
+use lsag::{self, RingSignature};
+use schema::{
+    anonymous_ballot, hashes_to_keys, keys_to_hashes, key_rotation, turnout_bucket_id, vote_commitment, VoteSchema, VoteRecord, TurnoutBucket, Ballot, Commitment, Delegation, Election, ElectionPhase, Candidate, CandidateStatus, Elector,
+    ElectorEntry, EncryptedChoice, KeyAlias, KeyShare, DecryptionShare, PartialDecryption, Revocation, RevocationReason, TokenSession,
+};
+use errors::{EntriesError, Error};
+
+/// Maximal total weight of the ballots of an `Encrypted` election, the sums are decrypted up to it.
+pub const MAX_ENCRYPTED_WEIGHT: u64 = 1 << 32;
+
+/// Maximal number of the electors registered by a `RegisterElectorsBatch`, so that the batch fits into a message.
+pub const MAX_BATCH_SIZE: usize = 1_000;
 
 transactions! {
     pub VoteTransactions {
         const SERVICE_ID = service::VOTE_SERVICE;
 
         struct CreateCandidate {
+            election: &Hash,
             pub_key: &PublicKey,
             name: &str,
         }
 
         struct CreateElector {
+            election: &Hash,
             pub_key: &PublicKey,
             name: &str,
         }
 
         struct Vote {
+            election: &Hash,
+            elector: &PublicKey,
+            candidate: &PublicKey,
+            seed: u64,
+        }
+
+        struct CreateElection {
+            author: &PublicKey,
+            name: &str,
+            voting_height: u64,
+            tallying_height: u64,
+            closing_height: u64,
+            seats: u32,
+            max_choices: u32,
+            scheme: u8,
+            seed: u64,
+        }
+
+        struct RegisterElector {
+            election: &Hash,
+            registrar: &PublicKey,
+            pub_key: &PublicKey,
+            name: &str,
+            weight: u64,
+        }
+
+        struct ChangeVote {
+            election: &Hash,
+            elector: &PublicKey,
+            candidate: &PublicKey,
+            seed: u64,
+        }
+
+        struct RevokeVote {
+            election: &Hash,
+            elector: &PublicKey,
+            seed: u64,
+        }
+
+        struct RankedVote {
+            election: &Hash,
+            elector: &PublicKey,
+            candidates: &[Hash],
+            seed: u64,
+        }
+
+        struct ApprovalVote {
+            election: &Hash,
+            elector: &PublicKey,
+            candidates: &[Hash],
+            seed: u64,
+        }
+
+        struct Delegate {
+            election: &Hash,
+            from: &PublicKey,
+            to: &PublicKey,
+            seed: u64,
+        }
+
+        struct CommitVote {
+            election: &Hash,
+            elector: &PublicKey,
+            commitment: &Hash,
+            seed: u64,
+        }
+
+        struct RevealVote {
+            election: &Hash,
+            elector: &PublicKey,
+            candidate: &PublicKey,
+            salt: &Hash,
+            seed: u64,
+        }
+
+        struct SubmitKeyShare {
+            election: &Hash,
+            validator: &PublicKey,
+            /// Commitments to the coefficients of the dealt polynomial, see `dkg::Dealing`.
+            commitments: &[Hash],
+            ephemeral: &Hash,
+            /// Encrypted shares of the trustees in the order of the trustees of the election.
+            shares: &[Hash],
+            challenge: &Hash,
+            response: &Hash,
+            seed: u64,
+        }
+
+        struct EncryptedVote {
+            election: &Hash,
+            elector: &PublicKey,
+            choices: Vec<EncryptedChoice>,
+            challenges: &[Hash],
+            responses: &[Hash],
+            seed: u64,
+        }
+
+        struct SubmitDecryption {
+            election: &Hash,
+            validator: &PublicKey,
+            shares: Vec<DecryptionShare>,
+            seed: u64,
+        }
+
+        struct AnonymousVote {
+            election: &Hash,
+            ballot_key: &PublicKey,
+            candidate: &PublicKey,
+            /// Index of the sub-ring of the signer, see `schema::sub_ring_bounds`.
+            ring: u64,
+            key_image: &Hash,
+            challenge: &Hash,
+            responses: &[Hash],
+            seed: u64,
+        }
+
+        struct IssueToken {
+            election: &Hash,
+            registrar: &PublicKey,
             elector: &PublicKey,
+            /// Blinded challenges of the elector for the committed nonces, see `blind::Blinding`.
+            challenges: &[Hash],
+            /// Index of the answered challenge.
+            clause: u8,
+            response: &Hash,
+            seed: u64,
+        }
+
+        struct VoteWithToken {
+            election: &Hash,
+            registrar: &PublicKey,
+            token: &PublicKey,
+            candidate: &PublicKey,
+            nonce: &Hash,
+            response: &Hash,
+            seed: u64,
+        }
+
+        struct WithdrawCandidate {
+            election: &Hash,
+            pub_key: &PublicKey,
+            seed: u64,
+        }
+
+        struct DisqualifyCandidate {
+            election: &Hash,
+            registrar: &PublicKey,
             candidate: &PublicKey,
+            seed: u64,
+        }
+
+        struct RevokeElector {
+            election: &Hash,
+            registrar: &PublicKey,
+            elector: &PublicKey,
+            /// See `RevocationReason`.
+            reason: u8,
+            seed: u64,
+        }
+
+        struct RotateKey {
+            election: &Hash,
+            old_key: &PublicKey,
+            new_key: &PublicKey,
+            /// Zero unless the lost key is replaced by a registrar, who then signs instead of the old key.
+            registrar: &PublicKey,
+            /// Signature of `key_rotation` by the new key.
+            new_key_signature: &Signature,
+            seed: u64,
+        }
+
+        struct RegisterElectorsBatch {
+            election: &Hash,
+            registrar: &PublicKey,
+            electors: Vec<ElectorEntry>,
+            seed: u64,
+        }
+
+        struct ComplainKeyShare {
+            election: &Hash,
+            trustee: &PublicKey,
+            dealer: &PublicKey,
+            /// Diffie-Hellman key of the trustee for the ephemeral key of the dealing.
+            shared_key: &Hash,
+            challenge: &Hash,
+            response: &Hash,
+            seed: u64,
+        }
+
+        struct CommitToken {
+            election: &Hash,
+            registrar: &PublicKey,
+            elector: &PublicKey,
+            /// Issuing key of the registrar for the election, see `blind::IssuingKey`.
+            issuing_key: &PublicKey,
+            /// Commitments to the fresh nonces of the registrar, see `blind::Session`.
+            commitments: &[Hash],
+            seed: u64,
         }
     }
 }
@@ -35,17 +250,34 @@ impl Transaction for CreateCandidate {
     fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
         let mut schema = VoteSchema::new(fork);
 
-        if schema.candidate(self.pub_key()).is_none() {
-            let history_hash = {
-                let mut history = schema.vote_history_mut(self.pub_key());
-                history.push(self.hash());
-                history.merkle_root()
-            };
+        let election = match schema.election(self.election()) {
+            Some(val) => val,
+            None => Err(Error::ElectionNotFound)?,
+        };
+
+        if election.phase(schema.height()) != ElectionPhase::Registration {
+            Err(Error::RegistrationClosed)?
+        }
 
-            let candidate = Candidate::new(self.pub_key(), self.name(), &history_hash, 0);
+        let config = vote_config(&schema)?;
+        let name_len = self.name().chars().count() as u32;
+        if name_len < config.min_candidate_name_len || name_len > config.max_candidate_name_len {
+            Err(Error::InvalidCandidateName)?
+        }
+        // The old keys resolve to the actual ones, so they can't be registered again.
+        if schema.key_aliases(self.election()).contains(self.pub_key()) {
+            Err(Error::KeyReplaced)?
+        }
+
+        if schema.candidate(self.election(), self.pub_key()).is_none() {
+            let history_hash = schema.push_history(self.election(), self.pub_key(), self.hash());
+
+            let candidate = Candidate::registered(self.pub_key(), self.name(), &history_hash);
 
             println!("Create the candidate: {:?}", candidate);
-            schema.candidate_mut().put(self.pub_key(), candidate);
+            schema.elections_mut().put(self.election(), election.register_candidate());
+            schema.put_candidate(self.election(), candidate);
+            schema.refresh_election(self.election());
             Ok(())
         } else {
             Err(Error::CandidateAlreadyExists)?
@@ -61,14 +293,126 @@ impl Transaction for CreateElector {
     fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
         let mut schema = VoteSchema::new(fork);
 
-        if schema.elector(self.pub_key()).is_none() {
-            let elector = Elector::new(self.pub_key(), self.name(), true);
-            println!("Create the elector: {:?}", elector);
-            schema.electorate_mut().put(self.pub_key(), elector);
-            Ok(())
-        } else {
-            Err(Error::ElectorAlreadyExists)?
+        let config = vote_config(&schema)?;
+
+        if !config.registrars.is_empty() {
+            Err(Error::SelfRegistrationDisabled)?
+        }
+
+        add_elector(&mut schema, self.election(), self.pub_key(), self.name(), 1)
+    }
+}
+
+impl Transaction for RegisterElector {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.registrar())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let config = vote_config(&schema)?;
+
+        if !config.is_registrar(self.registrar()) {
+            Err(Error::UnknownRegistrar)?
+        }
+
+        if self.weight() == 0 {
+            Err(Error::InvalidWeight)?
+        }
+
+        add_elector(&mut schema, self.election(), self.pub_key(), self.name(), self.weight())
+    }
+}
+
+impl Transaction for RegisterElectorsBatch {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.registrar())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let config = vote_config(&schema)?;
+
+        if !config.is_registrar(self.registrar()) {
+            Err(Error::UnknownRegistrar)?
+        }
+
+        let election = match schema.election(self.election()) {
+            Some(val) => val,
+            None => Err(Error::ElectionNotFound)?,
+        };
+
+        if election.phase(schema.height()) != ElectionPhase::Registration {
+            Err(Error::RegistrationClosed)?
+        }
+
+        let electors = self.electors();
+        if electors.len() > MAX_BATCH_SIZE {
+            Err(Error::BatchTooLarge)?
         }
+        if electors.iter().any(|entry| entry.weight() == 0) {
+            Err(Error::InvalidWeight)?
+        }
+
+        // The batch is applied as a whole, all the duplicates are reported at once.
+        let mut keys = HashSet::new();
+        let duplicates: Vec<usize> = electors.iter()
+            .enumerate()
+            .filter(|(_, entry)| !keys.insert(*entry.pub_key()) || schema.elector(self.election(), entry.pub_key()).is_some())
+            .map(|(index, _)| index)
+            .collect();
+        if !duplicates.is_empty() {
+            Err(EntriesError { error: Error::DuplicateElectors, entries: duplicates })?
+        }
+        let replaced: Vec<usize> = electors.iter()
+            .enumerate()
+            .filter(|(_, entry)| schema.key_aliases(self.election()).contains(entry.pub_key()))
+            .map(|(index, _)| index)
+            .collect();
+        if !replaced.is_empty() {
+            Err(EntriesError { error: Error::KeyReplaced, entries: replaced })?
+        }
+
+        for entry in &electors {
+            let elector = Elector::registered(entry.pub_key(), entry.name(), entry.weight());
+            schema.electorate_mut(self.election()).put(entry.pub_key(), elector);
+        }
+        schema.elections_mut().put(self.election(), election.register_electors(electors.len() as u64));
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+fn add_elector(
+    schema: &mut VoteSchema<&mut Fork>,
+    election_id: &Hash,
+    pub_key: &PublicKey,
+    name: &str,
+    weight: u64,
+) -> Result<(), ExecutionError> {
+    let election = match schema.election(election_id) {
+        Some(val) => val,
+        None => Err(Error::ElectionNotFound)?,
+    };
+
+    if election.phase(schema.height()) != ElectionPhase::Registration {
+        Err(Error::RegistrationClosed)?
+    }
+    if schema.key_aliases(election_id).contains(pub_key) {
+        Err(Error::KeyReplaced)?
+    }
+
+    if schema.elector(election_id, pub_key).is_none() {
+        let elector = Elector::registered(pub_key, name, weight);
+        println!("Create the elector: {:?}", elector);
+        schema.electorate_mut(election_id).put(pub_key, elector);
+        schema.elections_mut().put(election_id, election.register_elector());
+        schema.refresh_election(election_id);
+        Ok(())
+    } else {
+        Err(Error::ElectorAlreadyExists)?
     }
 }
 
@@ -79,29 +423,239 @@ impl Transaction for Vote {
 
     fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
         let mut schema = VoteSchema::new(fork);
-        let elector = match schema.elector(self.elector()) {
+
+        let election = check_voting_open(&schema, self.election())?;
+        if election.voting_scheme() != VotingScheme::Plurality {
+            Err(Error::WrongVotingScheme)?
+        }
+
+        let elector = match schema.elector(self.election(), self.elector()) {
             Some(val) => val,
             None => Err(Error::ElectorNotFound)?,
         };
+        if elector.revoked() {
+            Err(Error::ElectorRevoked)?
+        }
 
-        let candidate = match schema.candidate(self.candidate()) {
+        // The candidate may have rotated the key since the ballot was signed.
+        let candidate_key = schema.resolve_key(self.election(), self.candidate());
+        let candidate = match schema.candidate(self.election(), &candidate_key) {
             Some(val) => val,
             None => Err(Error::CandidateNotFound)?,
         };
+        check_active(&candidate)?;
 
         if elector.has_vote() {
-            let history_hash = {
-                let mut history = schema.vote_history_mut(candidate.pub_key());
-                history.push(self.hash());
-                history.merkle_root()
-            };
+            let history_hash = schema.push_history(self.election(), candidate.pub_key(), self.hash());
 
-            let elector = elector.vote();
-            let candidate = candidate.add_voice(&history_hash);
+            let elector = elector.vote(&candidate_key, &self.hash());
+            let candidate = candidate.add_voice(&history_hash, elector.weight());
 
             println!("{:?} voted in favor of {:?}", elector, candidate);
-            schema.electorate_mut().put(self.elector(), elector);
-            schema.candidate_mut().put(self.candidate(), candidate);
+            count_ballot(&mut schema, self.election(), elector.weight());
+            schema.electorate_mut(self.election()).put(self.elector(), elector);
+            record_vote(&mut schema, self.election(), self.elector(), self.hash());
+            schema.backers_mut(self.election(), candidate.pub_key()).insert(*self.elector());
+            schema.put_candidate(self.election(), candidate);
+            schema.log_ballot(self.hash());
+            schema.refresh_election(self.election());
+            Ok(())
+        } else {
+            Err(Error::AlreadyVoted)?
+        }
+    }
+}
+
+impl Transaction for ChangeVote {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.elector())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let election = check_voting_open(&schema, self.election())?;
+        if election.voting_scheme() != VotingScheme::Plurality {
+            Err(Error::WrongVotingScheme)?
+        }
+
+        let elector = match schema.elector(self.election(), self.elector()) {
+            Some(val) => val,
+            None => Err(Error::ElectorNotFound)?,
+        };
+        if elector.revoked() {
+            Err(Error::ElectorRevoked)?
+        }
+
+        // The candidate may have rotated the key since the ballot was signed.
+        let candidate_key = schema.resolve_key(self.election(), self.candidate());
+        let candidate = match schema.candidate(self.election(), &candidate_key) {
+            Some(val) => val,
+            None => Err(Error::CandidateNotFound)?,
+        };
+        check_active(&candidate)?;
+
+        if elector.has_vote() {
+            Err(Error::NotVoted)?
+        }
+        if elector.candidate() == &candidate_key {
+            Err(Error::SameCandidate)?
+        }
+
+        let previous_key = *elector.candidate();
+        let previous = schema.candidate(self.election(), &previous_key)
+            .expect("Backed candidate doesn't exist.");
+        let previous_hash = schema.push_history(self.election(), &previous_key, self.hash());
+        let history_hash = schema.push_history(self.election(), candidate.pub_key(), self.hash());
+
+        let elector = elector.vote(&candidate_key, &self.hash());
+        let previous = previous.remove_voice(&previous_hash, elector.weight());
+        let candidate = candidate.add_voice(&history_hash, elector.weight());
+
+        // The void vote for a withdrawn candidate is counted again from the change.
+        if !previous.is_active() {
+            schema.vote_records_mut(self.election()).remove(self.elector());
+            count_ballot(&mut schema, self.election(), elector.weight());
+        }
+        schema.electorate_mut(self.election()).put(self.elector(), elector);
+        record_vote(&mut schema, self.election(), self.elector(), self.hash());
+        schema.backers_mut(self.election(), &previous_key).remove(self.elector());
+        schema.backers_mut(self.election(), candidate.pub_key()).insert(*self.elector());
+        schema.put_candidate(self.election(), previous);
+        schema.put_candidate(self.election(), candidate);
+        schema.log_ballot(self.hash());
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+impl Transaction for RevokeVote {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.elector())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let election = check_voting_open(&schema, self.election())?;
+        // The encrypted vote can't be taken back from the sums.
+        if election.voting_scheme() == VotingScheme::Encrypted {
+            Err(Error::WrongVotingScheme)?
+        }
+
+        let elector = match schema.elector(self.election(), self.elector()) {
+            Some(val) => val,
+            None => Err(Error::ElectorNotFound)?,
+        };
+        if elector.revoked() {
+            Err(Error::ElectorRevoked)?
+        }
+
+        if elector.has_vote() {
+            Err(Error::NotVoted)?
+        }
+
+        revoke_ballot(&mut schema, &election, elector, self.hash());
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+impl Transaction for RankedVote {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.elector())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let election = check_voting_open(&schema, self.election())?;
+        if election.voting_scheme() != VotingScheme::RankedChoice {
+            Err(Error::WrongVotingScheme)?
+        }
+
+        let elector = match schema.elector(self.election(), self.elector()) {
+            Some(val) => val,
+            None => Err(Error::ElectorNotFound)?,
+        };
+        if elector.revoked() {
+            Err(Error::ElectorRevoked)?
+        }
+
+        let ranking = resolve_keys(&schema, self.election(), &hashes_to_keys(self.candidates()));
+        check_choices(&schema, &election, &ranking)?;
+
+        if elector.has_vote() {
+            // The first preference is counted as the voice of the candidate.
+            let first_key = ranking[0];
+            let first = schema.candidate(self.election(), &first_key).unwrap();
+            let history_hash = schema.push_history(self.election(), first.pub_key(), self.hash());
+
+            let elector = elector.vote(first.pub_key(), &self.hash());
+            let first = first.add_voice(&history_hash, elector.weight());
+            let ballot = Ballot::new(self.elector(), &keys_to_hashes(&ranking));
+
+            count_ballot(&mut schema, self.election(), elector.weight());
+            schema.electorate_mut(self.election()).put(self.elector(), elector);
+            record_vote(&mut schema, self.election(), self.elector(), self.hash());
+            schema.put_candidate(self.election(), first);
+            schema.ballots_mut(self.election()).put(self.elector(), ballot);
+            for candidate_key in &ranking {
+                schema.backers_mut(self.election(), candidate_key).insert(*self.elector());
+            }
+            schema.log_ballot(self.hash());
+            schema.refresh_election(self.election());
+            Ok(())
+        } else {
+            Err(Error::AlreadyVoted)?
+        }
+    }
+}
+
+impl Transaction for ApprovalVote {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.elector())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let election = check_voting_open(&schema, self.election())?;
+        if election.voting_scheme() != VotingScheme::Approval {
+            Err(Error::WrongVotingScheme)?
+        }
+
+        let elector = match schema.elector(self.election(), self.elector()) {
+            Some(val) => val,
+            None => Err(Error::ElectorNotFound)?,
+        };
+        if elector.revoked() {
+            Err(Error::ElectorRevoked)?
+        }
+
+        let approved = resolve_keys(&schema, self.election(), &hashes_to_keys(self.candidates()));
+        check_choices(&schema, &election, &approved)?;
+
+        if elector.has_vote() {
+            for candidate_key in &approved {
+                let candidate = schema.candidate(self.election(), candidate_key).unwrap();
+                let history_hash = schema.push_history(self.election(), candidate_key, self.hash());
+                let candidate = candidate.add_voice(&history_hash, elector.weight());
+                schema.put_candidate(self.election(), candidate);
+            }
+
+            let elector = elector.vote(&PublicKey::zero(), &self.hash());
+            let ballot = Ballot::new(self.elector(), &keys_to_hashes(&approved));
+
+            count_ballot(&mut schema, self.election(), elector.weight());
+            schema.electorate_mut(self.election()).put(self.elector(), elector);
+            record_vote(&mut schema, self.election(), self.elector(), self.hash());
+            schema.ballots_mut(self.election()).put(self.elector(), ballot);
+            for candidate_key in &approved {
+                schema.backers_mut(self.election(), candidate_key).insert(*self.elector());
+            }
+            schema.log_ballot(self.hash());
+            schema.refresh_election(self.election());
             Ok(())
         } else {
             Err(Error::AlreadyVoted)?
@@ -109,3 +663,1252 @@ impl Transaction for Vote {
     }
 }
 
+impl Transaction for Delegate {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.from())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let election = match schema.election(self.election()) {
+            Some(val) => val,
+            None => Err(Error::ElectionNotFound)?,
+        };
+
+        match election.phase(schema.height()) {
+            ElectionPhase::Registration | ElectionPhase::Voting => (),
+            ElectionPhase::Tallying | ElectionPhase::Closed => Err(Error::VotingClosed)?,
+        }
+        // The weight of an encrypted ballot is applied when the ballot is cast,
+        // the voter of an anonymous ballot is unknown.
+        match election.voting_scheme() {
+            VotingScheme::Encrypted | VotingScheme::Anonymous | VotingScheme::Token => Err(Error::WrongVotingScheme)?,
+            _ => (),
+        }
+
+        let (from, to) = match (schema.elector(self.election(), self.from()), schema.elector(self.election(), self.to())) {
+            (Some(from), Some(to)) => (from, to),
+            _ => Err(Error::ElectorNotFound)?,
+        };
+        if from.revoked() || to.revoked() {
+            Err(Error::ElectorRevoked)?
+        }
+        if self.from() == self.to() {
+            Err(Error::SelfDelegation)?
+        }
+
+        // The chain starting from the new delegate must not come back to the delegator.
+        let mut next = Some(*self.to());
+        while let Some(delegate) = next {
+            if &delegate == self.from() {
+                Err(Error::DelegationCycle)?
+            }
+            next = schema.delegation(self.election(), &delegate).map(|d| *d.to());
+        }
+
+        let delegation = Delegation::new(self.from(), self.to());
+        if schema.delegation(self.election(), self.from()).is_none() {
+            schema.elections_mut().put(self.election(), election.add_delegation());
+        }
+        schema.delegations_mut(self.election()).put(self.from(), delegation);
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+impl Transaction for CommitVote {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.elector())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let election = check_voting_open(&schema, self.election())?;
+        if election.voting_scheme() != VotingScheme::CommitReveal {
+            Err(Error::WrongVotingScheme)?
+        }
+
+        let elector = match schema.elector(self.election(), self.elector()) {
+            Some(val) => val,
+            None => Err(Error::ElectorNotFound)?,
+        };
+        if elector.revoked() {
+            Err(Error::ElectorRevoked)?
+        }
+
+        if elector.has_vote() {
+            let elector = elector.vote(&PublicKey::zero(), &self.hash());
+            let commitment = Commitment::new(self.elector(), self.elector(), self.commitment(), false);
+
+            schema.elections_mut().put(self.election(), election.commit_vote());
+            schema.electorate_mut(self.election()).put(self.elector(), elector);
+            record_vote(&mut schema, self.election(), self.elector(), self.hash());
+            schema.commitments_mut(self.election()).put(self.elector(), commitment);
+            schema.log_ballot(self.hash());
+            schema.refresh_election(self.election());
+            Ok(())
+        } else {
+            Err(Error::AlreadyVoted)?
+        }
+    }
+}
+
+impl Transaction for RevealVote {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.elector())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let election = match schema.election(self.election()) {
+            Some(val) => val,
+            None => Err(Error::ElectionNotFound)?,
+        };
+
+        if election.voting_scheme() != VotingScheme::CommitReveal {
+            Err(Error::WrongVotingScheme)?
+        }
+
+        match election.phase(schema.height()) {
+            ElectionPhase::Registration | ElectionPhase::Voting => Err(Error::RevealNotStarted)?,
+            ElectionPhase::Tallying => (),
+            ElectionPhase::Closed => Err(Error::RevealClosed)?,
+        }
+
+        let elector = match schema.elector(self.election(), self.elector()) {
+            Some(val) => val,
+            None => Err(Error::ElectorNotFound)?,
+        };
+
+        let commitment = match schema.commitment(self.election(), self.elector()) {
+            Some(val) => val,
+            None => Err(Error::NotVoted)?,
+        };
+
+        if commitment.revealed() {
+            Err(Error::AlreadyRevealed)?
+        }
+        let expected = vote_commitment(self.election(), commitment.committer(), self.candidate(), self.salt());
+        if &expected != commitment.commitment() {
+            Err(Error::InvalidReveal)?
+        }
+
+        // The candidate may have rotated the key since the commitment.
+        let candidate_key = schema.resolve_key(self.election(), self.candidate());
+        let candidate = match schema.candidate(self.election(), &candidate_key) {
+            Some(val) => val,
+            None => Err(Error::CandidateNotFound)?,
+        };
+        // The hidden vote for a candidate deactivated during the voting is only known at the reveal,
+        // so it's settled here: returned votes are taken back, void votes stay with the candidate uncounted.
+        if !candidate.is_active() && election.withdrawn_votes() == WithdrawnVotes::Return {
+            revoke_ballot(&mut schema, &election, elector, self.hash());
+            schema.refresh_election(self.election());
+            return Ok(());
+        }
+
+        let history_hash = schema.push_history(self.election(), candidate.pub_key(), self.hash());
+
+        let ballot = *elector.ballot();
+        let elector = elector.vote(&candidate_key, &ballot);
+        let counted = candidate.is_active();
+        let candidate = candidate.add_voice(&history_hash, elector.weight());
+
+        if counted {
+            count_ballot(&mut schema, self.election(), elector.weight());
+            // The hidden vote is counted in the turnout by the reveal.
+            if let Some(record) = schema.vote_record(self.election(), self.elector()) {
+                let record = VoteRecord::new(record.tx_hash(), record.height(), schema.height().0);
+                schema.vote_records_mut(self.election()).put(self.elector(), record);
+            }
+        }
+        let election = schema.election(self.election()).expect("Election doesn't exist.");
+        schema.elections_mut().put(self.election(), election.settle_commitment());
+        schema.electorate_mut(self.election()).put(self.elector(), elector);
+        schema.backers_mut(self.election(), &candidate_key).insert(*self.elector());
+        schema.put_candidate(self.election(), candidate);
+        schema.commitments_mut(self.election()).put(self.elector(), commitment.reveal());
+        schema.log_ballot(self.hash());
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+impl Transaction for SubmitKeyShare {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.validator())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let election = match schema.election(self.election()) {
+            Some(val) => val,
+            None => Err(Error::ElectionNotFound)?,
+        };
+
+        if election.voting_scheme() != VotingScheme::Encrypted {
+            Err(Error::WrongVotingScheme)?
+        }
+        if schema.height().0 >= election.dealing_height() {
+            Err(Error::RegistrationClosed)?
+        }
+        if election.trustee_index(self.validator()).is_none() {
+            Err(Error::NotTrustee)?
+        }
+        if schema.key_share(self.election(), self.validator()).is_some() {
+            Err(Error::KeyShareExists)?
+        }
+
+        // The polynomial has the degree of the threshold and a share for every trustee,
+        // the dealer proves the knowledge of the secret of the key share.
+        let commitments = self.commitments();
+        let shares = self.shares();
+        if commitments.len() != election.threshold() as usize || shares.len() != election.trustees().len() {
+            Err(Error::InvalidProof)?
+        }
+        match dkg::Dealing::from_hashes(commitments, self.ephemeral(), shares, self.challenge(), self.response()) {
+            Some(ref dealing) if dealing.verify(self.election(), self.validator()) => (),
+            _ => Err(Error::InvalidProof)?,
+        }
+
+        let key_share = KeyShare::new(self.validator(), commitments, self.ephemeral(), shares, false);
+        schema.key_shares_mut(self.election()).put(self.validator(), key_share);
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+impl Transaction for ComplainKeyShare {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.trustee())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let election = match schema.election(self.election()) {
+            Some(val) => val,
+            None => Err(Error::ElectionNotFound)?,
+        };
+
+        if election.voting_scheme() != VotingScheme::Encrypted {
+            Err(Error::WrongVotingScheme)?
+        }
+        if election.phase(schema.height()) != ElectionPhase::Registration {
+            Err(Error::RegistrationClosed)?
+        }
+        let index = match election.trustee_index(self.trustee()) {
+            Some(val) => val,
+            None => Err(Error::NotTrustee)?,
+        };
+        let dealing = match schema.key_share(self.election(), self.dealer()) {
+            Some(val) => val,
+            None => Err(Error::KeyShareNotFound)?,
+        };
+        if dealing.disqualified() {
+            Err(Error::DealerDisqualified)?
+        }
+
+        // The trustee reveals the Diffie-Hellman key of its share, so anyone can decrypt the share.
+        let ephemeral = lsag::hash_to_edwards(dealing.ephemeral()).expect("Invalid ephemeral key.");
+        let shared_key = lsag::hash_to_edwards(self.shared_key());
+        let proof = dkg::SharedKeyProof::from_hashes(self.challenge(), self.response());
+        let context = elgamal::proof_context(self.election(), self.trustee(), Some(self.dealer()));
+        let shared_key = match (shared_key, proof) {
+            (Some(shared_key), Some(proof)) if proof.verify(self.trustee(), &ephemeral, &shared_key, &context) => shared_key,
+            _ => Err(Error::InvalidProof)?,
+        };
+
+        let encrypted = lsag::scalar_from_hash(&dealing.shares()[index as usize - 1]).expect("Invalid share.");
+        let share = dkg::decrypt_share(&shared_key, &encrypted, self.election(), self.dealer(), self.trustee());
+        if dkg::verify_share(&dealing.commitment_points(), index, &share) {
+            Err(Error::KeyShareValid)?
+        }
+
+        let dealing = dealing.disqualify();
+        schema.key_shares_mut(self.election()).put(self.dealer(), dealing);
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+impl Transaction for EncryptedVote {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.elector())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let election = check_voting_open(&schema, self.election())?;
+        if election.voting_scheme() != VotingScheme::Encrypted {
+            Err(Error::WrongVotingScheme)?
+        }
+
+        let elector = match schema.elector(self.election(), self.elector()) {
+            Some(val) => val,
+            None => Err(Error::ElectorNotFound)?,
+        };
+        if elector.revoked() {
+            Err(Error::ElectorRevoked)?
+        }
+
+        if !elector.has_vote() {
+            Err(Error::AlreadyVoted)?
+        }
+
+        if (schema.qualified_dealings(self.election()).len() as u32) < election.threshold() {
+            Err(Error::EncryptionKeyNotReady)?
+        }
+        // The decryption searches for the sums up to the total weight.
+        if election.total_weight() + elector.weight() > MAX_ENCRYPTED_WEIGHT {
+            Err(Error::EncryptedWeightExceeded)?
+        }
+        let key = schema.encryption_key(self.election());
+
+        // The ballot has a choice for every candidate, so it doesn't reveal the chosen ones.
+        let choices = self.choices();
+        let candidates: Vec<PublicKey> = choices.iter().map(|choice| *choice.candidate()).collect();
+        let candidates = resolve_keys(&schema, self.election(), &candidates);
+        let has_duplicates = candidates.iter().enumerate()
+            .any(|(i, candidate)| candidates[..i].contains(candidate));
+        let all_candidates = candidates.len() as u64 == schema.candidates(self.election()).keys().count() as u64
+            && candidates.iter().all(|candidate| schema.candidate(self.election(), candidate).is_some());
+        if has_duplicates || !all_candidates {
+            Err(Error::InvalidBallot)?
+        }
+
+        let mut votes = Vec::new();
+        let mut total = Ciphertext::zero();
+        for choice in &choices {
+            let vote = match Ciphertext::from_hashes(choice.a(), choice.b()) {
+                Some(val) => val,
+                None => Err(Error::InvalidBallot)?,
+            };
+            let context = elgamal::proof_context(self.election(), self.elector(), Some(choice.candidate()));
+            match RangeProof::from_hashes(choice.challenges(), choice.responses()) {
+                Some(ref proof) if proof.verify(&key, &vote, 1, &context) => (),
+                _ => Err(Error::InvalidProof)?,
+            }
+            total = total.add(&vote);
+            votes.push(vote);
+        }
+
+        // The sum of the choices doesn't exceed the allowed number of choices.
+        let context = elgamal::proof_context(self.election(), self.elector(), None);
+        match RangeProof::from_hashes(self.challenges(), self.responses()) {
+            Some(ref proof) if proof.verify(&key, &total, u64::from(election.max_choices()), &context) => (),
+            _ => Err(Error::InvalidProof)?,
+        }
+
+        for (candidate_key, vote) in candidates.iter().zip(&votes) {
+            let candidate = schema.candidate(self.election(), candidate_key).unwrap();
+            let history_hash = schema.push_history(self.election(), candidate_key, self.hash());
+            let candidate = candidate.add_encrypted_vote(&history_hash, &vote.scale(elector.weight()));
+            schema.put_candidate(self.election(), candidate);
+        }
+
+        let elector = elector.vote(&PublicKey::zero(), &self.hash());
+
+        count_ballot(&mut schema, self.election(), elector.weight());
+        schema.electorate_mut(self.election()).put(self.elector(), elector);
+        record_vote(&mut schema, self.election(), self.elector(), self.hash());
+        schema.log_ballot(self.hash());
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+impl Transaction for SubmitDecryption {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.validator())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let election = match schema.election(self.election()) {
+            Some(val) => val,
+            None => Err(Error::ElectionNotFound)?,
+        };
+
+        if election.voting_scheme() != VotingScheme::Encrypted {
+            Err(Error::WrongVotingScheme)?
+        }
+        if election.phase(schema.height()) != ElectionPhase::Closed {
+            Err(Error::DecryptionNotStarted)?
+        }
+        if election.decrypted() {
+            Err(Error::DecryptionComplete)?
+        }
+
+        // The trustees who left the validators still decrypt the votes.
+        let index = match election.trustee_index(self.validator()) {
+            Some(val) => val,
+            None => Err(Error::NotTrustee)?,
+        };
+        if schema.decryption(self.election(), self.validator()).is_some() {
+            Err(Error::DecryptionExists)?
+        }
+
+        let shares = self.shares();
+        let candidates: Vec<PublicKey> = schema.candidates(self.election()).keys().collect();
+        let shared: Vec<PublicKey> = shares.iter().map(|share| *share.candidate()).collect();
+        if shared != candidates {
+            Err(Error::InvalidDecryption)?
+        }
+
+        let public_share = schema.public_share(self.election(), index);
+        for share in &shares {
+            let candidate = schema.candidate(self.election(), share.candidate()).unwrap();
+            if !check_decryption_share(self.election(), self.validator(), &public_share, &candidate, share) {
+                Err(Error::InvalidProof)?
+            }
+        }
+
+        let decryption = PartialDecryption::new(self.validator(), shares);
+        schema.decryptions_mut(self.election()).put(self.validator(), decryption);
+
+        if schema.decryptions(self.election()).keys().count() as u32 >= election.threshold() {
+            decrypt_votes(&mut schema, &election, self.hash())?;
+        }
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+impl Transaction for AnonymousVote {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.ballot_key())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let election = check_voting_open(&schema, self.election())?;
+        if election.voting_scheme() != VotingScheme::Anonymous {
+            Err(Error::WrongVotingScheme)?
+        }
+
+        // The candidate may have rotated the key since the ballot was signed.
+        let candidate_key = schema.resolve_key(self.election(), self.candidate());
+        let candidate = match schema.candidate(self.election(), &candidate_key) {
+            Some(val) => val,
+            None => Err(Error::CandidateNotFound)?,
+        };
+        check_active(&candidate)?;
+
+        // The key image is the same for all the ballots of an elector.
+        if schema.key_images(self.election()).contains(self.key_image()) {
+            Err(Error::AlreadyVoted)?
+        }
+
+        // Any elector of the sub-ring could have signed the ballot.
+        schema.freeze_ring(self.election());
+        let ring = match schema.sub_ring(self.election(), self.ring()) {
+            Some(ring) => ring,
+            None => Err(Error::InvalidRingSignature)?,
+        };
+        let message = anonymous_ballot(self.election(), self.candidate(), self.ballot_key());
+        match RingSignature::from_hashes(self.key_image(), self.challenge(), self.responses()) {
+            Some(ref signature) if signature.verify(&message, self.election(), &ring) => (),
+            _ => Err(Error::InvalidRingSignature)?,
+        }
+
+        let history_hash = schema.push_history(self.election(), candidate.pub_key(), self.hash());
+        let candidate = candidate.add_voice(&history_hash, 1);
+
+        schema.put_candidate(self.election(), candidate);
+        schema.key_images_mut(self.election()).put(self.key_image(), self.hash());
+        count_ballot(&mut schema, self.election(), 1);
+        schema.log_ballot(self.hash());
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+impl Transaction for IssueToken {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.registrar())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let config = vote_config(&schema)?;
+
+        if !config.is_registrar(self.registrar()) {
+            Err(Error::UnknownRegistrar)?
+        }
+
+        let election = match schema.election(self.election()) {
+            Some(val) => val,
+            None => Err(Error::ElectionNotFound)?,
+        };
+        if election.voting_scheme() != VotingScheme::Token {
+            Err(Error::WrongVotingScheme)?
+        }
+        match election.phase(schema.height()) {
+            ElectionPhase::Registration | ElectionPhase::Voting => (),
+            ElectionPhase::Tallying | ElectionPhase::Closed => Err(Error::VotingClosed)?,
+        }
+
+        match schema.elector(self.election(), self.elector()) {
+            Some(ref elector) if elector.revoked() => Err(Error::ElectorRevoked)?,
+            Some(_) => (),
+            None => Err(Error::ElectorNotFound)?,
+        }
+        if schema.tokens(self.election()).contains(self.elector()) {
+            Err(Error::TokenIssued)?
+        }
+        let session = match schema.token_session(self.election(), self.elector()) {
+            Some(ref session) if session.registrar() == self.registrar() => session.clone(),
+            _ => Err(Error::TokenNotCommitted)?,
+        };
+
+        // The response is checked against the blinded challenge, the token itself stays unknown.
+        let (commitments, challenges) = (session.commitments(), self.challenges());
+        if !blind::verify_blind_response(session.issuing_key(), commitments, challenges, self.clause(), self.response()) {
+            Err(Error::InvalidToken)?
+        }
+
+        schema.tokens_mut(self.election()).put(self.elector(), self.hash());
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+impl Transaction for CommitToken {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.registrar())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let config = vote_config(&schema)?;
+
+        if !config.is_registrar(self.registrar()) {
+            Err(Error::UnknownRegistrar)?
+        }
+
+        let election = match schema.election(self.election()) {
+            Some(val) => val,
+            None => Err(Error::ElectionNotFound)?,
+        };
+        if election.voting_scheme() != VotingScheme::Token {
+            Err(Error::WrongVotingScheme)?
+        }
+        match election.phase(schema.height()) {
+            ElectionPhase::Registration | ElectionPhase::Voting => (),
+            ElectionPhase::Tallying | ElectionPhase::Closed => Err(Error::VotingClosed)?,
+        }
+
+        match schema.elector(self.election(), self.elector()) {
+            Some(ref elector) if elector.revoked() => Err(Error::ElectorRevoked)?,
+            Some(_) => (),
+            None => Err(Error::ElectorNotFound)?,
+        }
+        // Every elector gets a single session, the nonces answer a single challenge.
+        if schema.token_sessions(self.election()).contains(self.elector()) {
+            Err(Error::TokenSessionExists)?
+        }
+
+        // The registrar signs all the tokens of the election by the same issuing key.
+        if !blind::verify_issuing_key(self.issuing_key(), self.registrar()) {
+            Err(Error::InvalidIssuingKey)?
+        }
+        match schema.issuing_keys(self.election()).get(self.registrar()) {
+            Some(ref key) if key != self.issuing_key() => Err(Error::InvalidIssuingKey)?,
+            _ => (),
+        }
+        if !blind::verify_commitments(self.commitments()) {
+            Err(Error::InvalidToken)?
+        }
+
+        let session = TokenSession::new(self.registrar(), self.issuing_key(), self.commitments(), &self.hash());
+        schema.token_sessions_mut(self.election()).put(self.elector(), session);
+        schema.issuing_keys_mut(self.election()).put(self.registrar(), *self.issuing_key());
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+impl Transaction for VoteWithToken {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.token())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let config = vote_config(&schema)?;
+
+        let election = check_voting_open(&schema, self.election())?;
+        if election.voting_scheme() != VotingScheme::Token {
+            Err(Error::WrongVotingScheme)?
+        }
+
+        // The candidate may have rotated the key since the ballot was signed.
+        let candidate_key = schema.resolve_key(self.election(), self.candidate());
+        let candidate = match schema.candidate(self.election(), &candidate_key) {
+            Some(val) => val,
+            None => Err(Error::CandidateNotFound)?,
+        };
+        check_active(&candidate)?;
+
+        if schema.spent_tokens(self.election()).contains(self.token()) {
+            Err(Error::TokenSpent)?
+        }
+
+        let issuing_key = match schema.issuing_keys(self.election()).get(self.registrar()) {
+            Some(key) => key,
+            None => Err(Error::InvalidToken)?,
+        };
+        if !config.is_registrar(self.registrar())
+            || !blind::verify_token(&issuing_key, self.election(), self.token(), self.nonce(), self.response()) {
+            Err(Error::InvalidToken)?
+        }
+
+        let history_hash = schema.push_history(self.election(), candidate.pub_key(), self.hash());
+        let candidate = candidate.add_voice(&history_hash, 1);
+
+        schema.put_candidate(self.election(), candidate);
+        schema.spent_tokens_mut(self.election()).put(self.token(), self.hash());
+        count_ballot(&mut schema, self.election(), 1);
+        schema.log_ballot(self.hash());
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+impl Transaction for WithdrawCandidate {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.pub_key())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        deactivate_candidate(&mut schema, self.election(), self.pub_key(), CandidateStatus::Withdrawn, self.hash())
+    }
+}
+
+impl Transaction for DisqualifyCandidate {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.registrar())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let config = vote_config(&schema)?;
+
+        if !config.is_registrar(self.registrar()) {
+            Err(Error::UnknownRegistrar)?
+        }
+
+        deactivate_candidate(&mut schema, self.election(), self.candidate(), CandidateStatus::Disqualified, self.hash())
+    }
+}
+
+impl Transaction for RevokeElector {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.registrar())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let config = vote_config(&schema)?;
+
+        if !config.is_registrar(self.registrar()) {
+            Err(Error::UnknownRegistrar)?
+        }
+        if RevocationReason::from_u8(self.reason()).is_none() {
+            Err(Error::UnknownRevocationReason)?
+        }
+
+        let election = match schema.election(self.election()) {
+            Some(val) => val,
+            None => Err(Error::ElectionNotFound)?,
+        };
+
+        match election.phase(schema.height()) {
+            ElectionPhase::Registration => (),
+            // The anonymous ballots are signed on behalf of the electorate at the start of the voting.
+            ElectionPhase::Voting if election.voting_scheme() == VotingScheme::Anonymous => Err(Error::RingFrozen)?,
+            ElectionPhase::Voting => (),
+            ElectionPhase::Tallying | ElectionPhase::Closed => Err(Error::VotingClosed)?,
+        }
+
+        let elector = match schema.elector(self.election(), self.elector()) {
+            Some(val) => val,
+            None => Err(Error::ElectorNotFound)?,
+        };
+        if elector.revoked() {
+            Err(Error::ElectorRevoked)?
+        }
+        // The blindly signed token can't be traced to the elector, so it can't be taken back.
+        if schema.token_sessions(self.election()).contains(self.elector()) {
+            Err(Error::TokenSessionExists)?
+        }
+
+        let mut voided = false;
+        if election.revoked_votes() == RevokedVotes::Void {
+            // The encrypted vote can't be taken back from the sums.
+            if !elector.has_vote() && election.voting_scheme() != VotingScheme::Encrypted {
+                revoke_ballot(&mut schema, &election, elector, self.hash());
+                voided = true;
+            }
+            if schema.delegations(self.election()).contains(self.elector()) {
+                schema.delegations_mut(self.election()).remove(self.elector());
+                let election = schema.election(self.election()).expect("Election doesn't exist.");
+                schema.elections_mut().put(self.election(), election.remove_delegation());
+                voided = true;
+            }
+        }
+
+        let elector = schema.elector(self.election(), self.elector()).expect("Elector doesn't exist.");
+        // The counted ballot stays in the turnout, so the elector stays in the electorate size until it's uncounted.
+        let counted = !elector.has_vote()
+            && (election.voting_scheme() == VotingScheme::Encrypted || schema.counts_ballot(self.election(), &elector));
+        schema.electorate_mut(self.election()).put(self.elector(), elector.deregister());
+        if !counted {
+            let election = schema.election(self.election()).expect("Election doesn't exist.");
+            schema.elections_mut().put(self.election(), election.deregister_elector());
+        }
+
+        let height = schema.height().0;
+        let revocation = Revocation::new(self.elector(), self.registrar(), self.reason(), height, &self.hash(), voided);
+        schema.revocations_mut(self.election()).push(revocation);
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+impl Transaction for RotateKey {
+    fn verify(&self) -> bool {
+        let message = key_rotation(self.election(), self.old_key(), self.new_key());
+        let signer = if *self.registrar() == PublicKey::zero() { self.old_key() } else { self.registrar() };
+        self.old_key() != self.new_key()
+            && crypto::verify(self.new_key_signature(), message.as_ref(), self.new_key())
+            && self.verify_signature(signer)
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let config = vote_config(&schema)?;
+
+        if *self.registrar() != PublicKey::zero() && !config.is_registrar(self.registrar()) {
+            Err(Error::UnknownRegistrar)?
+        }
+
+        let election = match schema.election(self.election()) {
+            Some(val) => val,
+            None => Err(Error::ElectionNotFound)?,
+        };
+
+        let anonymous = election.voting_scheme() == VotingScheme::Anonymous
+            || election.voting_scheme() == VotingScheme::Token;
+        match election.phase(schema.height()) {
+            ElectionPhase::Registration => (),
+            // The new key would sign another anonymous ballot with a new key image or get another token.
+            ElectionPhase::Voting if anonymous => Err(Error::KeyRotationClosed)?,
+            ElectionPhase::Voting => (),
+            ElectionPhase::Tallying | ElectionPhase::Closed => Err(Error::VotingClosed)?,
+        }
+
+        let elector = schema.elector(self.election(), self.old_key());
+        let candidate = schema.candidate(self.election(), self.old_key());
+        if elector.is_none() && candidate.is_none() {
+            Err(Error::UnknownKey)?
+        }
+        // A replaced key can't be taken again, so the aliases don't form cycles.
+        if schema.elector(self.election(), self.new_key()).is_some()
+            || schema.candidate(self.election(), self.new_key()).is_some()
+            || schema.key_aliases(self.election()).contains(self.new_key()) {
+            Err(Error::KeyInUse)?
+        }
+
+        if let Some(elector) = elector {
+            rekey_elector(&mut schema, self.election(), elector, self.new_key());
+        }
+        if let Some(candidate) = candidate {
+            rekey_candidate(&mut schema, self.election(), candidate, self.new_key(), self.hash());
+        }
+
+        let height = schema.height().0;
+        let alias = KeyAlias::new(self.old_key(), self.new_key(), self.registrar(), height, &self.hash());
+        schema.key_aliases_mut(self.election()).put(self.old_key(), alias);
+        schema.refresh_election(self.election());
+        Ok(())
+    }
+}
+
+/// Moves the elector with the ballot, the commitment, the vote record, the token and the delegations to the new key.
+fn rekey_elector(schema: &mut VoteSchema<&mut Fork>, election_id: &Hash, elector: Elector, new_key: &PublicKey) {
+    let old_key = *elector.pub_key();
+    for candidate_key in schema.ballot_choices(election_id, &elector) {
+        schema.backers_mut(election_id, &candidate_key).remove(&old_key);
+        schema.backers_mut(election_id, &candidate_key).insert(*new_key);
+    }
+    schema.electorate_mut(election_id).remove(&old_key);
+    schema.electorate_mut(election_id).put(new_key, elector.rekey(new_key));
+
+    if let Some(ballot) = schema.ballot(election_id, &old_key) {
+        schema.ballots_mut(election_id).remove(&old_key);
+        schema.ballots_mut(election_id).put(new_key, Ballot::new(new_key, ballot.candidates()));
+    }
+    if let Some(commitment) = schema.commitment(election_id, &old_key) {
+        schema.commitments_mut(election_id).remove(&old_key);
+        let commitment = Commitment::new(new_key, commitment.committer(), commitment.commitment(), commitment.revealed());
+        schema.commitments_mut(election_id).put(new_key, commitment);
+    }
+    if let Some(record) = schema.vote_record(election_id, &old_key) {
+        schema.vote_records_mut(election_id).remove(&old_key);
+        schema.vote_records_mut(election_id).put(new_key, record);
+    }
+    if let Some(token) = schema.tokens(election_id).get(&old_key) {
+        schema.tokens_mut(election_id).remove(&old_key);
+        schema.tokens_mut(election_id).put(new_key, token);
+    }
+    if let Some(session) = schema.token_session(election_id, &old_key) {
+        schema.token_sessions_mut(election_id).remove(&old_key);
+        schema.token_sessions_mut(election_id).put(new_key, session);
+    }
+
+    let delegations: Vec<Delegation> = schema.delegations(election_id).values()
+        .filter(|delegation| *delegation.from() == old_key || *delegation.to() == old_key)
+        .collect();
+    let rekeyed = |key: &PublicKey| if *key == old_key { *new_key } else { *key };
+    for delegation in delegations {
+        let (from, to) = (rekeyed(delegation.from()), rekeyed(delegation.to()));
+        schema.delegations_mut(election_id).remove(delegation.from());
+        schema.delegations_mut(election_id).put(&from, Delegation::new(&from, &to));
+    }
+}
+
+/// Moves the candidate with the vote history to the new key and updates the ballots backing the candidate.
+fn rekey_candidate(schema: &mut VoteSchema<&mut Fork>, election_id: &Hash, candidate: Candidate, new_key: &PublicKey, tx_hash: Hash) {
+    let old_key = *candidate.pub_key();
+    schema.move_history(election_id, &old_key, new_key);
+    // The rotation is recorded in the history, it isn't a ballot.
+    let history_hash = schema.push_history(election_id, new_key, tx_hash);
+    schema.candidate_mut(election_id).remove(&old_key);
+    schema.put_candidate(election_id, candidate.rekey(new_key, &history_hash));
+
+    let backers: Vec<PublicKey> = schema.backers(election_id, &old_key).iter().collect();
+    schema.backers_mut(election_id, &old_key).clear();
+    for elector_key in backers {
+        schema.backers_mut(election_id, new_key).insert(elector_key);
+        let elector = schema.elector(election_id, &elector_key).expect("Backer doesn't exist.");
+        if *elector.candidate() == old_key {
+            let ballot = *elector.ballot();
+            schema.electorate_mut(election_id).put(&elector_key, elector.vote(new_key, &ballot));
+        }
+        if let Some(ballot) = schema.ballot(election_id, &elector_key) {
+            let choices: Vec<PublicKey> = ballot.choices().into_iter()
+                .map(|choice| if choice == old_key { *new_key } else { choice })
+                .collect();
+            schema.ballots_mut(election_id).put(&elector_key, Ballot::new(&elector_key, &keys_to_hashes(&choices)));
+        }
+    }
+}
+
+/// Deactivates the candidate and voids or returns the votes for it, see `WithdrawnVotes`.
+/// The committed votes don't name the candidate until they are revealed, they are settled by `RevealVote`.
+fn deactivate_candidate(
+    schema: &mut VoteSchema<&mut Fork>,
+    election_id: &Hash,
+    candidate_key: &PublicKey,
+    status: CandidateStatus,
+    tx_hash: Hash,
+) -> Result<(), ExecutionError> {
+    let election = match schema.election(election_id) {
+        Some(val) => val,
+        None => Err(Error::ElectionNotFound)?,
+    };
+
+    match election.phase(schema.height()) {
+        ElectionPhase::Registration | ElectionPhase::Voting => (),
+        ElectionPhase::Tallying | ElectionPhase::Closed => Err(Error::WithdrawalClosed)?,
+    }
+
+    let candidate = match schema.candidate(election_id, candidate_key) {
+        Some(val) => val,
+        None => Err(Error::CandidateNotFound)?,
+    };
+    check_active(&candidate)?;
+
+    let returned = election.withdrawn_votes() == WithdrawnVotes::Return;
+    let backers: Vec<PublicKey> = schema.backers(election_id, candidate_key).iter().collect();
+    if returned {
+        for backer in &backers {
+            let elector = schema.elector(election_id, backer).expect("Backer doesn't exist.");
+            revoke_ballot(schema, &election, elector, tx_hash);
+        }
+    }
+
+    let candidate = schema.candidate(election_id, candidate_key).expect("Candidate doesn't exist.");
+    let history_hash = schema.push_history(election_id, candidate_key, tx_hash);
+    let candidate = candidate.deactivate(&history_hash, status);
+
+    schema.put_candidate(election_id, candidate);
+    void_ballots(schema, &election, candidate_key, if returned { &[] } else { &backers });
+    schema.refresh_election(election_id);
+    Ok(())
+}
+
+/// Takes the ballots left naming only the withdrawn candidates out of the turnout, the votes stay cast.
+fn void_ballots(
+    schema: &mut VoteSchema<&mut Fork>,
+    election: &Election,
+    candidate_key: &PublicKey,
+    backers: &[PublicKey],
+) {
+    let election_id = election.id();
+    match election.voting_scheme() {
+        // The anonymous and the token ballots can't be traced to the electors, so they are void
+        // in either mode. Each of them gives a single voice and is logged in the candidate history.
+        VotingScheme::Anonymous | VotingScheme::Token => {
+            let ballots: Vec<Hash> = schema.vote_history(election_id, candidate_key).iter()
+                .filter(|tx_hash| schema.ballot_positions().contains(tx_hash))
+                .collect();
+            for tx_hash in ballots {
+                let height = schema.tx_height(&tx_hash).unwrap_or_else(|| schema.height());
+                uncount_ballot(schema, election_id, 1, height.0);
+            }
+        }
+        // The encrypted ballots stay counted, the votes for the candidate aren't known until the decryption.
+        VotingScheme::Encrypted => (),
+        _ => for backer in backers {
+            let elector = schema.elector(election_id, backer).expect("Backer doesn't exist.");
+            if !schema.counts_ballot(election_id, &elector) {
+                let record = schema.vote_record(election_id, backer).expect("Vote record doesn't exist.");
+                uncount_ballot(schema, election_id, elector.weight(), record.counted_height());
+                deregister_revoked(schema, election_id, &elector);
+            }
+        },
+    }
+}
+
+/// Decrypts the sums of the encrypted votes into the voices of the candidates.
+///
+/// The decryption shares of any `threshold` trustees give the same sums, the first ones are combined.
+fn decrypt_votes(schema: &mut VoteSchema<&mut Fork>, election: &Election, tx_hash: Hash) -> Result<(), ExecutionError> {
+    let election_id = election.id();
+    let decryptions: Vec<(u64, PartialDecryption)> = schema.decryptions(election_id).values()
+        .take(election.threshold() as usize)
+        .map(|decryption| (election.trustee_index(decryption.validator()).unwrap(), decryption))
+        .collect();
+
+    let baby_steps = elgamal::BabySteps::new(election.total_weight());
+    for candidate in schema.candidates(election_id).values().collect::<Vec<_>>() {
+        let shares: Vec<(u64, RistrettoPoint)> = decryptions.iter()
+            .map(|(index, decryption)| {
+                let share = decryption.shares().into_iter().find(|share| share.candidate() == candidate.pub_key()).unwrap();
+                (*index, elgamal::hash_to_point(share.share()).unwrap())
+            })
+            .collect();
+        let votes = match elgamal::decrypt(&candidate.encrypted_votes(), &dkg::combine(&shares), &baby_steps) {
+            Some(val) => val,
+            None => Err(Error::InvalidDecryption)?,
+        };
+        let history_hash = schema.push_history(election_id, candidate.pub_key(), tx_hash);
+        let candidate = candidate.add_voice(&history_hash, votes);
+        schema.put_candidate(election_id, candidate);
+    }
+
+    let election = schema.election(election_id).unwrap().with_decryption();
+    schema.elections_mut().put(election_id, election);
+    Ok(())
+}
+
+/// Checks the proof that the decryption share of the trustee matches its public share.
+pub fn check_decryption_share(
+    election_id: &Hash,
+    validator: &PublicKey,
+    public_share: &RistrettoPoint,
+    candidate: &Candidate,
+    share: &DecryptionShare,
+) -> bool {
+    let context = elgamal::proof_context(election_id, validator, Some(candidate.pub_key()));
+    let base = candidate.encrypted_votes().a;
+    let decryption_share = elgamal::hash_to_point(share.share());
+    let proof = EqualityProof::from_hashes(share.challenge(), share.response());
+    match (decryption_share, proof) {
+        (Some(decryption_share), Some(proof)) => proof.verify(public_share, &base, &decryption_share, &context),
+        _ => false,
+    }
+}
+
+/// Checks that the ballot chooses existing active candidates, each at most once,
+/// and doesn't exceed `max_choices` of the election.
+fn check_choices<T: AsRef<dyn Snapshot>>(
+    schema: &VoteSchema<T>,
+    election: &Election,
+    choices: &[PublicKey],
+) -> Result<(), ExecutionError> {
+    let has_duplicates = choices.iter().enumerate()
+        .any(|(i, candidate)| choices[..i].contains(candidate));
+    if choices.is_empty() || has_duplicates {
+        Err(Error::InvalidBallot)?
+    }
+    if choices.len() > election.max_choices() as usize {
+        Err(Error::TooManyChoices)?
+    }
+    let candidates: Vec<Option<Candidate>> = choices.iter()
+        .map(|candidate| schema.candidate(election.id(), candidate))
+        .collect();
+    if candidates.iter().any(Option::is_none) {
+        Err(Error::CandidateNotFound)?
+    }
+    for candidate in candidates.iter().flatten() {
+        check_active(candidate)?;
+    }
+    Ok(())
+}
+
+/// Follows the replacements of the candidate keys of the ballot up to the actual ones, see `RotateKey`.
+fn resolve_keys<T: AsRef<dyn Snapshot>>(schema: &VoteSchema<T>, election_id: &Hash, keys: &[PublicKey]) -> Vec<PublicKey> {
+    keys.iter().map(|key| schema.resolve_key(election_id, key)).collect()
+}
+
+/// Checks that the candidate is neither withdrawn nor disqualified.
+fn check_active(candidate: &Candidate) -> Result<(), ExecutionError> {
+    match candidate.candidate_status() {
+        CandidateStatus::Active => Ok(()),
+        CandidateStatus::Withdrawn => Err(Error::CandidateWithdrawn)?,
+        CandidateStatus::Disqualified => Err(Error::CandidateDisqualified)?,
+    }
+}
+
+/// Checks that the election exists and accepts votes in the block being created.
+fn check_voting_open<T: AsRef<dyn Snapshot>>(schema: &VoteSchema<T>, election_id: &Hash) -> Result<Election, ExecutionError> {
+    let election = match schema.election(election_id) {
+        Some(val) => val,
+        None => Err(Error::ElectionNotFound)?,
+    };
+
+    match election.phase(schema.height()) {
+        ElectionPhase::Registration => Err(Error::VotingNotStarted)?,
+        ElectionPhase::Voting => Ok(election),
+        ElectionPhase::Tallying | ElectionPhase::Closed => Err(Error::VotingClosed)?,
+    }
+}
+
+/// Reads the configuration of the service, rejecting the transaction if it's invalid.
+fn vote_config<T: AsRef<Snapshot>>(schema: &VoteSchema<T>) -> Result<VoteConfig, ExecutionError> {
+    match schema.config() {
+        Some(config) => Ok(config),
+        None => Err(Error::InvalidConfig)?,
+    }
+}
+
+/// Adds the ballot of the given weight to the turnout of the election.
+fn count_ballot(schema: &mut VoteSchema<&mut Fork>, election_id: &Hash, weight: u64) {
+    let election = schema.election(election_id).expect("Election doesn't exist.");
+    let height = election.turnout_bucket(schema.height());
+    let bucket_id = turnout_bucket_id(height);
+    let ballots = schema.turnout_buckets(election_id).get(&bucket_id).map_or(0, |bucket| bucket.ballots());
+    schema.turnout_buckets_mut(election_id).put(&bucket_id, TurnoutBucket::new(height, ballots + 1));
+    schema.put_turnout(election_id, election.count_ballot(weight));
+}
+
+/// Takes the revoked elector out of the electorate size once the ballot counted before the revocation is uncounted.
+fn deregister_revoked(schema: &mut VoteSchema<&mut Fork>, election_id: &Hash, elector: &Elector) {
+    if elector.revoked() {
+        let election = schema.election(election_id).expect("Election doesn't exist.");
+        schema.elections_mut().put(election_id, election.deregister_elector());
+    }
+}
+
+/// Takes back the ballot of the elector, so that the elector may vote again.
+fn revoke_ballot(schema: &mut VoteSchema<&mut Fork>, election: &Election, elector: Elector, tx_hash: Hash) {
+    let elector_key = *elector.pub_key();
+    // An approval ballot gives a voice to every approved candidate.
+    let backed = match election.voting_scheme() {
+        VotingScheme::Approval => schema.ballot(election.id(), &elector_key)
+            .expect("Approval ballot doesn't exist.")
+            .choices(),
+        // The hidden vote gives no voices until it is revealed.
+        VotingScheme::CommitReveal => Vec::new(),
+        _ => vec![*elector.candidate()],
+    };
+
+    for previous_key in &backed {
+        let previous = schema.candidate(election.id(), previous_key)
+            .expect("Backed candidate doesn't exist.");
+        let previous_hash = schema.push_history(election.id(), previous_key, tx_hash);
+        let previous = previous.remove_voice(&previous_hash, elector.weight());
+        schema.put_candidate(election.id(), previous);
+    }
+
+    // The hidden vote isn't counted until it is revealed, the void vote is taken out of the turnout
+    // when the candidates are withdrawn.
+    if election.voting_scheme() != VotingScheme::CommitReveal && schema.counts_ballot(election.id(), &elector) {
+        let record = schema.vote_record(election.id(), &elector_key).expect("Vote record doesn't exist.");
+        uncount_ballot(schema, election.id(), elector.weight(), record.counted_height());
+        deregister_revoked(schema, election.id(), &elector);
+    }
+    for candidate_key in schema.ballot_choices(election.id(), &elector) {
+        schema.backers_mut(election.id(), &candidate_key).remove(&elector_key);
+    }
+    let elector = elector.revoke();
+    schema.electorate_mut(election.id()).put(&elector_key, elector);
+    schema.ballots_mut(election.id()).remove(&elector_key);
+    schema.vote_records_mut(election.id()).remove(&elector_key);
+    if let Some(commitment) = schema.commitment(election.id(), &elector_key) {
+        schema.commitments_mut(election.id()).remove(&elector_key);
+        if !commitment.revealed() {
+            let settled = schema.election(election.id()).expect("Election doesn't exist.").settle_commitment();
+            schema.elections_mut().put(election.id(), settled);
+        }
+    }
+}
+
+/// Takes back the ballot of the given weight counted at the height from the turnout of the election.
+fn uncount_ballot(schema: &mut VoteSchema<&mut Fork>, election_id: &Hash, weight: u64, counted_height: u64) {
+    let election = schema.election(election_id).expect("Election doesn't exist.");
+    let height = election.turnout_bucket(Height(counted_height));
+    let bucket_id = turnout_bucket_id(height);
+    let bucket = schema.turnout_buckets(election_id).get(&bucket_id).expect("Turnout bucket doesn't exist.");
+    schema.turnout_buckets_mut(election_id).put(&bucket_id, TurnoutBucket::new(height, bucket.ballots() - 1));
+    schema.put_turnout(election_id, election.uncount_ballot(weight));
+}
+
+/// Records the transaction which cast the ballot of the elector with the height of its block.
+fn record_vote(schema: &mut VoteSchema<&mut Fork>, election_id: &Hash, elector: &PublicKey, tx_hash: Hash) {
+    let height = schema.height().0;
+    let counted_height = schema.vote_record(election_id, elector).map_or(height, |record| record.counted_height());
+    let record = VoteRecord::new(&tx_hash, height, counted_height);
+    schema.vote_records_mut(election_id).put(elector, record);
+}
+
+impl Transaction for CreateElection {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.author())
+    }
+
+    fn execute(&self, fork: &mut Fork) -> Result<(), ExecutionError> {
+        let mut schema = VoteSchema::new(fork);
+
+        let config = vote_config(&schema)?;
+
+        let height = schema.height().0;
+        if self.voting_height() < height + config.min_registration_period
+            || self.voting_height() <= height
+            || self.tallying_height() < self.voting_height() + config.min_voting_period
+            || self.tallying_height() <= self.voting_height()
+            || self.closing_height() < self.tallying_height() {
+            Err(Error::InvalidPhaseHeights)?
+        }
+
+        let scheme = if self.scheme() == CONFIGURED_SCHEME {
+            config.voting_scheme
+        } else {
+            match VotingScheme::from_u8(self.scheme()) {
+                Some(scheme) => scheme,
+                None => Err(Error::UnknownVotingScheme)?,
+            }
+        };
+
+        let single_choice = scheme == VotingScheme::Plurality
+            || scheme == VotingScheme::Anonymous
+            || scheme == VotingScheme::Token;
+        let multiple_choices = single_choice && self.max_choices() != 1;
+        let multiple_seats = scheme == VotingScheme::RankedChoice && self.seats() != 1;
+        if self.seats() == 0 || self.max_choices() == 0 || multiple_choices || multiple_seats {
+            Err(Error::InvalidElectionParams)?
+        }
+
+        // The key of an encrypted election is shared by the actual validators,
+        // any two thirds of them are able to decrypt the votes.
+        let (trustees, threshold, dealing_height) = if scheme == VotingScheme::Encrypted {
+            let mut trustees = schema.validators();
+            trustees.sort();
+            let threshold = trustees.len() - (trustees.len() - 1) / 3;
+            let dealing_height = self.voting_height().saturating_sub(config.key_complaint_period);
+            if dealing_height <= height {
+                Err(Error::InvalidPhaseHeights)?
+            }
+            (keys_to_hashes(&trustees), threshold as u32, dealing_height)
+        } else {
+            (Vec::new(), 0, self.voting_height())
+        };
+
+        let id = self.hash();
+        let candidates_hash = schema.candidates(&id).merkle_root();
+        let electorate_hash = schema.electorate(&id).merkle_root();
+        let ballots_hash = schema.ballots(&id).merkle_root();
+        let delegations_hash = schema.delegations(&id).merkle_root();
+        let commitments_hash = schema.commitments(&id).merkle_root();
+        let key_shares_hash = schema.key_shares(&id).merkle_root();
+        let decryptions_hash = schema.decryptions(&id).merkle_root();
+        let key_images_hash = schema.key_images(&id).merkle_root();
+        let tokens_hash = schema.tokens(&id).merkle_root();
+        let spent_tokens_hash = schema.spent_tokens(&id).merkle_root();
+        let token_sessions_hash = schema.token_sessions(&id).merkle_root();
+        let issuing_keys_hash = schema.issuing_keys(&id).merkle_root();
+        let vote_records_hash = schema.vote_records(&id).merkle_root();
+        let turnout_buckets_hash = schema.turnout_buckets(&id).merkle_root();
+        let turnout_checkpoints_hash = schema.turnout_checkpoints(&id).merkle_root();
+        let revocations_hash = schema.revocations(&id).merkle_root();
+        let key_aliases_hash = schema.key_aliases(&id).merkle_root();
+        let ring_hash = schema.ring(&id).merkle_root();
+        let ring_size = if scheme == VotingScheme::Anonymous { config.ring_size.max(1) } else { 0 };
+        let election = Election::new(
+            &id,
+            self.author(),
+            self.name(),
+            scheme as u8,
+            self.voting_height(),
+            self.tallying_height(),
+            self.closing_height(),
+            self.seats(),
+            self.max_choices(),
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            &trustees,
+            threshold,
+            dealing_height,
+            false,
+            ring_size,
+            config.withdrawn_votes as u8,
+            config.revoked_votes as u8,
+            config.turnout_bucket_size,
+            &candidates_hash,
+            &electorate_hash,
+            &ballots_hash,
+            &delegations_hash,
+            &commitments_hash,
+            &key_shares_hash,
+            &decryptions_hash,
+            &key_images_hash,
+            &tokens_hash,
+            &spent_tokens_hash,
+            &token_sessions_hash,
+            &issuing_keys_hash,
+            &vote_records_hash,
+            &turnout_buckets_hash,
+            &turnout_checkpoints_hash,
+            &revocations_hash,
+            &key_aliases_hash,
+            &ring_hash,
+        );
+
+        schema.put_turnout(&id, election);
+        schema.refresh_election(&id);
+        Ok(())
+    }
+}
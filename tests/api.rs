@@ -1,6 +1,7 @@
 #[macro_use]
 extern crate serde_json;
 
+#[macro_use]
 extern crate exonum_testkit;
 extern crate exonum;
 extern crate vote_service as voting;
@@ -8,37 +9,74 @@ extern crate vote_service as voting;
 use exonum::{
     api::node::public::explorer::TransactionQuery,
     crypto::{self, CryptoHash, Hash, PublicKey, SecretKey},
+    helpers::Height,
 };
 
 use exonum_testkit::{ApiKind, TestKit, TestKitApi, TestKitBuilder};
 use voting::{
-    api::{CandidateQuery, ElectorQuery, VotingResults},
+    api::{CandidateQuery, CandidatesQuery, DelegationPage, ElectorsQuery, HistoryQuery, Page, ProofPage, ProofPageQuery, ElectionQuery, ElectionStats, ReceiptQuery, ResultsQuery, RevocationLog, RevocationsQuery, RingQuery, SubRing, ElectorQuery, BallotLog, BallotLogQuery, IrvResults, VoteLocation, VotingHistory, VotingResults},
+    client,
+    config::{VoteConfig, VotingScheme, CONFIGURED_SCHEME},
+    dkg::Dealing,
+    elgamal::{self, EqualityProof},
+    lsag::RingSignature,
     service::{VoteService, SERVICE_NAME},
-    transactions::{CreateCandidate, CreateElector, Vote},
-    schema::{Candidate, Elector},
+    transactions::{
+        AnonymousVote, ApprovalVote, ChangeVote, CommitVote, CreateCandidate, CreateElection, CreateElector, Delegate, EncryptedVote,
+        RankedVote, RegisterElector, RevealVote, RevokeVote, SubmitDecryption, SubmitKeyShare, Vote, WithdrawCandidate, RevokeElector, RotateKey,
+    },
+    schema::{
+        anonymous_ballot, history_id, key_rotation, keys_to_hashes, vote_commitment, Candidate, Commitment, DecryptionShare, Election, ElectionPhase,
+        Elector, PartialDecryption, RevocationReason, VoteSchema,
+    },
 };
 
+const VOTING_HEIGHT: u64 = 10;
+const TALLYING_HEIGHT: u64 = 20;
+const CLOSING_HEIGHT: u64 = 30;
+
+#[test]
+fn create_election() {
+    let (mut testkit, api) = create_testkit();
+    let board_tx = api.create_election("Board");
+    let lunch_tx = api.create_election("Lunch");
+    testkit.create_block();
+
+    api.assert_tx_success(board_tx.hash());
+    api.assert_tx_success(lunch_tx.hash());
+
+    let board = api.get_election(board_tx.hash()).unwrap();
+    assert_eq!(board.id(), &board_tx.hash());
+    assert_eq!(board.author(), board_tx.author());
+    assert_eq!(board.name(), "Board");
+
+    let elections = api.get_elections();
+    assert_eq!(2, elections.len());
+    assert!(elections.iter().any(|e| e.name() == "Lunch" && e.id() == &lunch_tx.hash()));
+}
+
 #[test]
 fn create_candidate() {
     let (mut testkit, api) = create_testkit();
-    let (john_tx, _) = api.create_candidate("John");
-    let (john_1_tx, _) = api.create_candidate("John_1");
+    let election = api.init_election(&mut testkit);
+    let (john_tx, _) = api.create_candidate(&election, "John");
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
     testkit.create_block();
 
     api.assert_tx_success(john_tx.hash());
     api.assert_tx_success(john_1_tx.hash());
 
-    let john = api.get_candidate(*john_tx.pub_key()).unwrap();
+    let john = api.get_candidate(election, *john_tx.pub_key()).unwrap();
     assert_eq!(john.pub_key(), john_tx.pub_key());
     assert_eq!(john.name(), john_tx.name());
     assert_eq!(john.voices(), 0);
 
-    let john_1 = api.get_candidate(*john_1_tx.pub_key()).unwrap();
+    let john_1 = api.get_candidate(election, *john_1_tx.pub_key()).unwrap();
     assert_eq!(john_1.pub_key(), john_1_tx.pub_key());
     assert_eq!(john_1.name(), john_1_tx.name());
     assert_eq!(john_1.voices(), 0);
 
-    let candidates = api.get_candidates();
+    let candidates = api.get_candidates(election);
     assert_eq!(2, candidates.len());
     assert_eq!(true, candidates.iter()
         .any(|c| c.pub_key() == john.pub_key() && c.name() == john.name() && john.voices() == 0));
@@ -49,88 +87,1140 @@ fn create_candidate() {
 #[test]
 fn create_elector() {
     let (mut testkit, api) = create_testkit();
-    let (den_tx, _) = api.create_elector("Den");
+    let election = api.init_election(&mut testkit);
+    let (den_tx, _) = api.create_elector(&election, "Den");
     testkit.create_block();
 
     api.assert_tx_success(den_tx.hash());
-    let den = api.get_elector(*den_tx.pub_key()).unwrap();
+    let den = api.get_elector(election, *den_tx.pub_key()).unwrap();
 
     assert_eq!(den.pub_key(), den_tx.pub_key());
     assert_eq!(den.name(), den_tx.name());
     assert_eq!(den.has_vote(), true);
 }
 
+#[test]
+fn register_elector() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let config = VoteConfig { registrars: vec![registrar], ..VoteConfig::default() };
+    let (mut testkit, api) = create_testkit_with_config(config);
+    assert_eq!(api.get_registrars(), vec![registrar]);
+
+    let election = api.init_election(&mut testkit);
+    let (den, _) = crypto::gen_keypair();
+    let tx = RegisterElector::new(&election, &registrar, &den, "Den", 1, &registrar_sec);
+    let tx_info: serde_json::Value = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&tx)
+        .post("v1/elector/register")
+        .unwrap();
+    assert_eq!(tx_info, json!({ "tx_hash": tx.hash() }));
+
+    let (self_tx, _) = api.create_elector(&election, "Mallory");
+    testkit.create_block();
+
+    api.assert_tx_success(tx.hash());
+    api.assert_tx_status(self_tx.hash(), &json!({ "type": "error", "code" : 11,  "description": "Electors must be registered by a registrar"}));
+    assert_eq!(api.get_elector(election, den).unwrap().name(), "Den");
+    assert!(api.get_elector(election, *self_tx.pub_key()).is_none());
+}
+
+#[test]
+fn weighted_results() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let config = VoteConfig { registrars: vec![registrar], ..VoteConfig::default() };
+    let (mut testkit, api) = create_testkit_with_config(config);
+    let election = api.init_election(&mut testkit);
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
+
+    let electors: Vec<_> = [3, 1, 4].iter()
+        .map(|weight| {
+            let (pub_key, sec) = crypto::gen_keypair();
+            let tx = RegisterElector::new(&election, &registrar, &pub_key, "Den", *weight, &registrar_sec);
+            let _: serde_json::Value = api.inner
+                .public(ApiKind::Service(SERVICE_NAME))
+                .query(&tx)
+                .post("v1/elector/register")
+                .unwrap();
+            (pub_key, sec)
+        })
+        .collect();
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+
+    // The elector with the weight 4 doesn't vote.
+    api.vote(&election, john_1_tx.pub_key(), &electors[0].0, &electors[0].1);
+    api.vote(&election, john_2_tx.pub_key(), &electors[1].0, &electors[1].1);
+    testkit.create_block();
+
+    let res = api.get_results(election);
+    assert_eq!(res.total_weight, 4);
+    let john_1 = res.candidates.iter().find(|c| c.candidate.pub_key() == john_1_tx.pub_key()).unwrap();
+    assert_eq!(john_1.candidate.voices(), 3);
+    assert_eq!(john_1.vote_percent, 75.0);
+    assert_eq!(res.seats.as_ref().unwrap().winners, vec![*john_1_tx.pub_key()]);
+}
+
+#[test]
+fn delegated_results() {
+    let (mut testkit, api) = create_testkit();
+    let election = api.init_election(&mut testkit);
+    let (ann_tx, ann_sec) = api.create_elector(&election, "Ann");
+    let (bob_tx, bob_sec) = api.create_elector(&election, "Bob");
+    let (den_tx, den_sec) = api.create_elector(&election, "Den");
+    let (eve_tx, eve_sec) = api.create_elector(&election, "Eve");
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
+    testkit.create_block();
+
+    // Ann and Eve delegate to Bob, Den delegates to Ann.
+    let delegations = vec![
+        Delegate::new(&election, ann_tx.pub_key(), bob_tx.pub_key(), 0, &ann_sec),
+        Delegate::new(&election, eve_tx.pub_key(), bob_tx.pub_key(), 0, &eve_sec),
+        Delegate::new(&election, den_tx.pub_key(), ann_tx.pub_key(), 0, &den_sec),
+    ];
+    for tx in &delegations {
+        let tx_info: serde_json::Value = api.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(tx)
+            .post("v1/delegate")
+            .unwrap();
+        assert_eq!(tx_info, json!({ "tx_hash": tx.hash() }));
+    }
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+
+    // Eve overrides the delegation by voting directly.
+    api.vote(&election, john_1_tx.pub_key(), bob_tx.pub_key(), &bob_sec);
+    api.vote(&election, john_2_tx.pub_key(), eve_tx.pub_key(), &eve_sec);
+    testkit.create_block();
+
+    let res = api.get_results(election);
+    assert_eq!(res.total_weight, 4);
+    let john_1 = res.candidates.iter().find(|c| c.candidate.pub_key() == john_1_tx.pub_key()).unwrap();
+    assert_eq!(john_1.candidate.voices(), 1);
+    assert_eq!(john_1.delegated_voices, 2);
+    assert_eq!(john_1.vote_percent, 75.0);
+    let john_2 = res.candidates.iter().find(|c| c.candidate.pub_key() == john_2_tx.pub_key()).unwrap();
+    assert_eq!(john_2.delegated_voices, 0);
+    assert_eq!(res.seats.as_ref().unwrap().winners, vec![*john_1_tx.pub_key()]);
+    assert_eq!(res.election.delegations_count(), 3);
+
+    // The delegations are proven by pages, apart from the summary.
+    let pages = api.get_delegation_pages(election, 2);
+    assert_eq!(pages.len(), 2);
+    let delegations = pages[0].delegations.proof.clone().check().unwrap();
+    assert_eq!(delegations.entries().len(), 2);
+    assert_eq!(&delegations.merkle_root(), res.election.delegations_hash());
+
+    // The delegated votes are recomputed from the proven delegations and elector records.
+    let validators = api.consensus_keys(&testkit);
+    let tally = client::verify_results(api.get_results(election), pages, &validators).unwrap();
+    let mut voices = tally.voices.clone();
+    voices.sort_by_key(|&(_, voices)| voices);
+    assert_eq!(voices, vec![(*john_2_tx.pub_key(), 1), (*john_1_tx.pub_key(), 3)]);
+
+    let mut pages = api.get_delegation_pages(election, 2);
+    pages.pop();
+    let err = client::verify_results(api.get_results(election), pages, &validators).unwrap_err();
+    assert_eq!(err, client::Error::InvalidDelegationProof);
+
+    let mut tampered = api.get_results(election);
+    let index = tampered.candidates.iter().position(|c| c.candidate.pub_key() == john_2_tx.pub_key()).unwrap();
+    tampered.candidates[index].delegated_voices = 2;
+    let err = client::verify_results(tampered, api.get_delegation_pages(election, 2), &validators).unwrap_err();
+    assert_eq!(err, client::Error::InvalidDelegatedVoices(*john_2_tx.pub_key()));
+}
+
+#[test]
+fn commit_reveal_results() {
+    let (mut testkit, api) = create_testkit_with_config(VoteConfig { voting_scheme: VotingScheme::CommitReveal, ..VoteConfig::default() });
+    let election = api.init_election(&mut testkit);
+    let (den_1_tx, den_1_sec) = api.create_elector(&election, "Den_1");
+    let (den_2_tx, den_2_sec) = api.create_elector(&election, "Den_2");
+    let (john_tx, _) = api.create_candidate(&election, "John");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+
+    let salt = crypto::hash(b"salt");
+    for (elector_tx, sec) in &[(&den_1_tx, &den_1_sec), (&den_2_tx, &den_2_sec)] {
+        let commitment = vote_commitment(&election, elector_tx.pub_key(), john_tx.pub_key(), &salt);
+        let tx = CommitVote::new(&election, elector_tx.pub_key(), &commitment, 0, sec);
+        let _: serde_json::Value = api.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&tx)
+            .post("v1/vote/commit")
+            .unwrap();
+    }
+    testkit.create_block();
+    assert_eq!(api.get_results(election).unrevealed, 2);
+
+    testkit.create_blocks_until(Height(TALLYING_HEIGHT - 1));
+    let tx = RevealVote::new(&election, den_1_tx.pub_key(), john_tx.pub_key(), &salt, 0, &den_1_sec);
+    let tx_info: serde_json::Value = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&tx)
+        .post("v1/vote/reveal")
+        .unwrap();
+    assert_eq!(tx_info, json!({ "tx_hash": tx.hash() }));
+    testkit.create_block();
+    api.assert_tx_success(tx.hash());
+
+    let res = api.get_results(election);
+    assert_eq!(res.phase, ElectionPhase::Tallying);
+    assert_eq!(res.unrevealed, 1);
+    let query = ProofPageQuery { election_id: election, from: None, limit: Some(1) };
+    let page: ProofPage<PublicKey, Commitment> = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&query)
+        .get("v1/election/commitments")
+        .unwrap();
+    let proof = page.proof.check().unwrap();
+    assert_eq!(proof.merkle_root(), *res.election.commitments_hash());
+    assert_eq!(proof.entries().len(), 1);
+    let query = ProofPageQuery { from: page.next, ..query };
+    let next_page: ProofPage<PublicKey, Commitment> = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&query)
+        .get("v1/election/commitments")
+        .unwrap();
+    assert!(next_page.next.is_none());
+    let unrevealed: Vec<PublicKey> = proof.entries().into_iter()
+        .chain(next_page.proof.check().unwrap().entries())
+        .filter(|(_, commitment)| !commitment.revealed())
+        .map(|(key, _)| *key)
+        .collect();
+    assert_eq!(unrevealed, vec![*den_2_tx.pub_key()]);
+    assert_eq!(res.total_weight, 1);
+    assert_eq!(res.candidates[0].candidate.voices(), 1);
+
+    // The receipt proves the revealed ballot.
+    let receipt = api.get_receipt(election, *den_1_tx.pub_key(), None).unwrap();
+    let validators = api.consensus_keys(&testkit);
+    let verified = client::verify_receipt_json(&receipt.to_string(), &validators).unwrap();
+    assert_eq!(verified.tx_hash, tx.hash());
+    assert!(api.get_receipt(election, *den_2_tx.pub_key(), Some(*john_tx.pub_key())).is_none());
+}
+
+#[test]
+fn encrypted_results() {
+    let (mut testkit, api) = create_testkit_with_config(VoteConfig { voting_scheme: VotingScheme::Encrypted, ..VoteConfig::default() });
+    let election = api.init_election(&mut testkit);
+    let electors = [
+        api.create_elector(&election, "Den_1"),
+        api.create_elector(&election, "Den_2"),
+        api.create_elector(&election, "Den_3"),
+    ];
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
+
+    let (validator, validator_sec) = testkit.network().validators()[0].service_keypair();
+    let (validator, validator_sec) = (*validator, validator_sec.clone());
+    // The only trustee holds the whole key, its secret share is the secret of the dealing.
+    let secret = elgamal::election_secret(&validator_sec, &election);
+    let dealing = Dealing::new(&validator_sec, &election, &validator, &[validator], 1).unwrap();
+    let key = dealing.commitments[0];
+    let (commitments, ephemeral, shares, challenge, response) = dealing.to_hashes();
+    let tx = SubmitKeyShare::new(&election, &validator, &commitments, &ephemeral, &shares, &challenge, &response, 0, &validator_sec);
+    let _: serde_json::Value = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&tx)
+        .post("v1/election/key")
+        .unwrap();
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    api.assert_tx_success(tx.hash());
+
+    let mut candidates = vec![*john_1_tx.pub_key(), *john_2_tx.pub_key()];
+    candidates.sort();
+    for ((elector_tx, sec), chosen) in electors.iter().zip(&[john_1_tx.pub_key(), john_2_tx.pub_key(), john_1_tx.pub_key()]) {
+        let (choices, proof) = elgamal::encrypt_ballot(&key, &election, elector_tx.pub_key(), &candidates, &[**chosen], 1);
+        let (challenges, responses) = proof.to_hashes();
+        let tx = EncryptedVote::new(&election, elector_tx.pub_key(), choices, &challenges, &responses, 0, sec);
+        let _: serde_json::Value = api.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&tx)
+            .post("v1/vote/encrypted")
+            .unwrap();
+    }
+    testkit.create_block();
+
+    // The votes are counted, but not decrypted yet.
+    let res = api.get_results(election);
+    assert_eq!(res.total_weight, 3);
+    assert!(res.candidates.iter().all(|c| c.decrypted_voices.is_none() && c.candidate.voices() == 0));
+
+    testkit.create_blocks_until(Height(CLOSING_HEIGHT));
+    let closed = api.get_results(election);
+    assert_eq!(closed.phase, ElectionPhase::Closed);
+    assert!(!closed.is_final);
+
+    let shares = res.candidates.iter()
+        .map(|c| {
+            let votes = api.get_candidate(election, *c.candidate.pub_key()).unwrap().encrypted_votes();
+            let share = elgamal::point_to_hash(&elgamal::decryption_share(&secret, &votes));
+            let context = elgamal::proof_context(&election, &validator, Some(c.candidate.pub_key()));
+            let (challenge, response) = EqualityProof::new(&secret, &votes.a, &context).to_hashes();
+            DecryptionShare::new(c.candidate.pub_key(), &share, &challenge, &response)
+        })
+        .collect();
+    let tx = SubmitDecryption::new(&election, &validator, shares, 0, &validator_sec);
+    let _: serde_json::Value = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&tx)
+        .post("v1/election/decryption")
+        .unwrap();
+    testkit.create_block();
+    api.assert_tx_success(tx.hash());
+
+    let res = api.get_results(election);
+    assert!(res.is_final);
+    let john_1 = res.candidates.iter().find(|c| c.candidate.pub_key() == john_1_tx.pub_key()).unwrap();
+    assert_eq!(john_1.decrypted_voices, Some(2));
+    assert!((john_1.vote_percent - 66.67).abs() < 0.01);
+    let john_2 = res.candidates.iter().find(|c| c.candidate.pub_key() == john_2_tx.pub_key()).unwrap();
+    assert_eq!(john_2.decrypted_voices, Some(1));
+    assert_eq!(res.seats.as_ref().unwrap().winners, vec![*john_1_tx.pub_key()]);
+    let decryptions: ProofPage<PublicKey, PartialDecryption> = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&ProofPageQuery::<PublicKey> { election_id: election, from: None, limit: None })
+        .get("v1/election/decryptions")
+        .unwrap();
+    assert_eq!(decryptions.proof.check().unwrap().merkle_root(), *res.election.decryptions_hash());
+    assert!(decryptions.next.is_none());
+
+    // The decrypted votes are the voices of the proven candidate records.
+    let validators = api.consensus_keys(&testkit);
+    let mut tampered = api.get_results(election);
+    let pub_key = *tampered.candidates[0].candidate.pub_key();
+    tampered.candidates[0].decrypted_voices = Some(3);
+    let err = client::verify_results(tampered, Vec::new(), &validators).unwrap_err();
+    assert_eq!(err, client::Error::InvalidDecryptedVoices(pub_key));
+    let tally = client::verify_results(api.get_results(election), Vec::new(), &validators).unwrap();
+    assert!(tally.voices.contains(&(*john_1_tx.pub_key(), 2)));
+}
+
+#[test]
+fn anonymous_results() {
+    let (mut testkit, api) = create_testkit_with_config(VoteConfig { voting_scheme: VotingScheme::Anonymous, ..VoteConfig::default() });
+    let election = api.init_election(&mut testkit);
+    let (_, den_1_sec) = api.create_elector(&election, "Den_1");
+    api.create_elector(&election, "Den_2");
+    let (john_tx, _) = api.create_candidate(&election, "John");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+
+    // The ring is the electorate in the order of the keys.
+    let ring: Vec<PublicKey> = VoteSchema::new(&testkit.snapshot()).electorate(&election).keys().collect();
+    let sub_ring = api.get_ring(election, 0);
+    assert_eq!((sub_ring.rings, &sub_ring.members), (1, &ring));
+    assert!(sub_ring.proof.is_none());
+    let (ballot_key, ballot_sec) = crypto::gen_keypair();
+    let message = anonymous_ballot(&election, john_tx.pub_key(), &ballot_key);
+    let (key_image, challenge, responses) = RingSignature::sign(&message, &election, &ring, &den_1_sec).unwrap().to_hashes();
+    let tx = AnonymousVote::new(&election, &ballot_key, john_tx.pub_key(), 0, &key_image, &challenge, &responses, 0, &ballot_sec);
+    let tx_info: serde_json::Value = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&tx)
+        .post("v1/vote/anonymous")
+        .unwrap();
+    assert_eq!(tx_info, json!({ "tx_hash": tx.hash() }));
+    testkit.create_block();
+    api.assert_tx_success(tx.hash());
+
+    // The first ballot freezes the ring.
+    let sub_ring = api.get_ring(election, 0);
+    assert_eq!(sub_ring.members, ring);
+    let proof = sub_ring.proof.unwrap();
+    let ring_hash = *VoteSchema::new(&testkit.snapshot()).election(&election).unwrap().ring_hash();
+    assert_eq!(proof.validate(ring_hash, 2).unwrap().len(), 2);
+
+    let res = api.get_results(election);
+    assert_eq!(res.total_weight, 1);
+    assert_eq!(res.candidates[0].candidate.voices(), 1);
+    assert_eq!(res.seats.as_ref().unwrap().winners, vec![*john_tx.pub_key()]);
+    let key_images: ProofPage<Hash, Hash> = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&ProofPageQuery::<Hash> { election_id: election, from: None, limit: None })
+        .get("v1/election/key_images")
+        .unwrap();
+    let key_images = key_images.proof.check().unwrap();
+    assert_eq!(key_images.merkle_root(), *res.election.key_images_hash());
+    assert_eq!(key_images.entries(), vec![(&key_image, &tx.hash())]);
+}
+
 #[test]
 fn vote() {
     let (mut testkit, api) = create_testkit();
-    let (den_1_tx, den_1_seq) = api.create_elector("Den lee");
-    let (den_2_tx, den_2_seq) = api.create_elector("Den_2");
-    let (john_1_tx, _) = api.create_candidate("John Forbes Nash");
-    let (john_2_tx, _) = api.create_candidate("John_2");
+    let election = api.init_election(&mut testkit);
+    let (den_1_tx, den_1_seq) = api.create_elector(&election, "Den lee");
+    let (den_2_tx, den_2_seq) = api.create_elector(&election, "Den_2");
+    let (john_1_tx, _) = api.create_candidate(&election, "John Forbes Nash");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
     testkit.create_block();
 
     api.assert_tx_success(den_1_tx.hash());
     api.assert_tx_success(john_2_tx.hash());
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
 
-    let vote_1 = api.vote(john_1_tx.pub_key(), den_1_tx.pub_key(), &den_1_seq);
-    let vote_2 = api.vote(john_2_tx.pub_key(), den_2_tx.pub_key(), &den_2_seq);
+    let vote_1 = api.vote(&election, john_1_tx.pub_key(), den_1_tx.pub_key(), &den_1_seq);
+    let vote_2 = api.vote(&election, john_2_tx.pub_key(), den_2_tx.pub_key(), &den_2_seq);
     testkit.create_block();
     api.assert_tx_success(vote_1.hash());
     api.assert_tx_success(vote_2.hash());
 
-    let vote_3 = api.vote(john_1_tx.pub_key(), den_2_tx.pub_key(), &den_2_seq);
-    let vote_4 = api.vote(john_2_tx.pub_key(), den_1_tx.pub_key(), &den_1_seq);
+    let vote_3 = api.vote(&election, john_1_tx.pub_key(), den_2_tx.pub_key(), &den_2_seq);
+    let vote_4 = api.vote(&election, john_2_tx.pub_key(), den_1_tx.pub_key(), &den_1_seq);
     testkit.create_block();
 
     api.assert_tx_status(vote_3.hash(), &json!({ "type": "error", "code" : 4,  "description": "The voter has already voted."}));
     api.assert_tx_status(vote_4.hash(), &json!({ "type": "error", "code" : 4,  "description": "The voter has already voted."}));
 
-    let john_1 = api.get_candidate(*john_1_tx.pub_key()).unwrap();
-    let john_2 = api.get_candidate(*john_2_tx.pub_key()).unwrap();
+    let john_1 = api.get_candidate(election, *john_1_tx.pub_key()).unwrap();
+    let john_2 = api.get_candidate(election, *john_2_tx.pub_key()).unwrap();
     assert_eq!(john_1.voices(), 1);
     assert_eq!(john_2.voices(), 1);
 
-    let den_1 = api.get_elector(*den_1_tx.pub_key()).unwrap();
-    let den_2 = api.get_elector(*den_2_tx.pub_key()).unwrap();
+    let den_1 = api.get_elector(election, *den_1_tx.pub_key()).unwrap();
+    let den_2 = api.get_elector(election, *den_2_tx.pub_key()).unwrap();
     assert_eq!(den_1.has_vote(), false);
     assert_eq!(den_2.has_vote(), false);
 }
 
+#[test]
+fn change_vote() {
+    let (mut testkit, api) = create_testkit();
+    let election = api.init_election(&mut testkit);
+    let (den_tx, den_seq) = api.create_elector(&election, "Den");
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    api.vote(&election, john_1_tx.pub_key(), den_tx.pub_key(), &den_seq);
+    testkit.create_block();
+
+    let tx = ChangeVote::new(&election, den_tx.pub_key(), john_2_tx.pub_key(), 0, &den_seq);
+    let tx_info: serde_json::Value = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&tx)
+        .post("v1/vote/change")
+        .unwrap();
+    assert_eq!(tx_info, json!({ "tx_hash": tx.hash() }));
+    testkit.create_block();
+    api.assert_tx_success(tx.hash());
+
+    let den = api.get_elector(election, *den_tx.pub_key()).unwrap();
+    assert_eq!(den.candidate(), john_2_tx.pub_key());
+    assert_eq!(api.get_candidate(election, *john_1_tx.pub_key()).unwrap().voices(), 0);
+    assert_eq!(api.get_candidate(election, *john_2_tx.pub_key()).unwrap().voices(), 1);
+}
+
+#[test]
+fn vote_in_unknown_election() {
+    let (mut testkit, api) = create_testkit();
+    let election = api.init_election(&mut testkit);
+    let (den_tx, den_seq) = api.create_elector(&election, "Den");
+    let (john_tx, _) = api.create_candidate(&election, "John");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+
+    let unknown = crypto::hash(b"unknown");
+    let vote = api.vote(&unknown, john_tx.pub_key(), den_tx.pub_key(), &den_seq);
+    testkit.create_block();
+
+    api.assert_tx_status(vote.hash(), &json!({ "type": "error", "code" : 5,  "description": "Election doesn't exist"}));
+    assert!(api.get_elector(election, *den_tx.pub_key()).unwrap().has_vote());
+}
+
 #[test]
 fn get_block_number() {
     let (mut testkit, api) = create_testkit();
-    let (den_1_tx, den_1_seq) = api.create_elector("Den_1");
+    let election = api.init_election(&mut testkit);
+    let (den_1_tx, den_1_seq) = api.create_elector(&election, "Den_1");
+    let (den_2_tx, _) = api.create_elector(&election, "Den_2");
     testkit.create_block();
-    let (john_1_tx, _) = api.create_candidate("John_1");
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
     testkit.create_block();
-    api.vote(john_1_tx.pub_key(), den_1_tx.pub_key(), &den_1_seq);
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    let vote_tx = api.vote(&election, john_1_tx.pub_key(), den_1_tx.pub_key(), &den_1_seq);
     testkit.create_block();
 
-    assert_eq!(Some(3), api.get_block_number(*den_1_tx.pub_key()));
+    let location = api.get_block_number(election, *den_1_tx.pub_key()).unwrap();
+    assert_eq!(location.height, Height(VOTING_HEIGHT));
+    assert_eq!(location.tx_hash, vote_tx.hash());
+    let election_proof = location.election_proof.check().unwrap();
+    let (_, election_record) = election_proof.entries()[0];
+    let records = location.proof.check().unwrap();
+    assert_eq!(records.merkle_root(), *election_record.vote_records_hash());
+    assert_eq!(records.entries()[0].1.tx_hash(), &vote_tx.hash());
+    assert!(api.get_block_number(election, *den_2_tx.pub_key()).is_none());
+}
+
+#[test]
+fn rotated_key_lookups() {
+    let (mut testkit, api) = create_testkit();
+    let election = api.init_election(&mut testkit);
+    let (den_tx, den_sec) = api.create_elector(&election, "Den");
+    let (john_tx, john_sec) = api.create_candidate(&election, "John");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    let vote_tx = api.vote(&election, john_tx.pub_key(), den_tx.pub_key(), &den_sec);
+    testkit.create_block();
+
+    let (new_den, new_den_sec) = crypto::gen_keypair();
+    let (new_john, new_john_sec) = crypto::gen_keypair();
+    let rotations = vec![
+        (den_tx.pub_key(), &den_sec, new_den, &new_den_sec),
+        (john_tx.pub_key(), &john_sec, new_john, &new_john_sec),
+    ];
+    for (old_key, old_sec, new_key, new_sec) in rotations {
+        let signature = crypto::sign(key_rotation(&election, old_key, &new_key).as_ref(), new_sec);
+        let tx = RotateKey::new(&election, old_key, &new_key, &PublicKey::zero(), &signature, 0, old_sec);
+        let tx_info: serde_json::Value = api.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&tx)
+            .post("v1/key/rotate")
+            .unwrap();
+        assert_eq!(tx_info, json!({ "tx_hash": tx.hash() }));
+    }
+    testkit.create_block();
+
+    let location = api.get_block_number(election, *den_tx.pub_key()).unwrap();
+    assert_eq!(location.pub_key, new_den);
+    assert_eq!(location.tx_hash, vote_tx.hash());
+
+    let candidate = api.get_candidate(election, new_john).unwrap();
+    let query = HistoryQuery { election_id: election, pub_key: *john_tx.pub_key(), from: None, limit: None };
+    let history: VotingHistory = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&query)
+        .get("v1/candidate/history")
+        .unwrap();
+    // The history keeps the registration and the ballot signed for the old key.
+    let transactions = client::verify_history(&candidate, history).unwrap();
+    assert_eq!(transactions.len(), 3);
+    assert_eq!(transactions[1].hash(), vote_tx.hash());
+
+    let receipt = api.get_receipt(election, *den_tx.pub_key(), None).unwrap();
+    let verified = client::verify_receipt_json(&receipt.to_string(), &api.consensus_keys(&testkit)).unwrap();
+    assert_eq!((verified.elector, verified.candidate), (new_den, new_john));
+    assert_eq!(verified.tx_hash, vote_tx.hash());
+}
+
+#[test]
+fn elector_revocations() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let config = VoteConfig { registrars: vec![registrar], ..VoteConfig::default() };
+    let (mut testkit, api) = create_testkit_with_config(config);
+    let election = api.init_election(&mut testkit);
+    let (den, _) = crypto::gen_keypair();
+    testkit.create_block_with_transaction(RegisterElector::new(&election, &registrar, &den, "Den", 1, &registrar_sec));
+
+    let query = RevocationsQuery { election_id: election, from: None, limit: None };
+    let empty: Option<RevocationLog> = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&query)
+        .get("v1/elector/revocations")
+        .ok();
+    assert!(empty.is_none());
+
+    let tx = RevokeElector::new(&election, &registrar, &den, RevocationReason::Left as u8, 0, &registrar_sec);
+    let tx_info: serde_json::Value = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&tx)
+        .post("v1/elector/revoke")
+        .unwrap();
+    assert_eq!(tx_info, json!({ "tx_hash": tx.hash() }));
+    testkit.create_block();
+    api.assert_tx_success(tx.hash());
+    assert!(api.get_elector(election, den).unwrap().revoked());
+
+    let log: RevocationLog = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&query)
+        .get("v1/elector/revocations")
+        .unwrap();
+    assert_eq!((log.from, log.length), (0, 1));
+    assert_eq!(log.revocations[0].elector(), &den);
+    assert_eq!(log.revocations[0].tx_hash(), &tx.hash());
+    let election = api.get_election(election).unwrap();
+    let proven = log.proof.validate(*election.revocations_hash(), log.length).unwrap();
+    assert_eq!(proven.len(), 1);
+    assert_eq!(proven[0].1, &log.revocations[0]);
+}
+
+#[test]
+fn revoked_elector_stats() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let config = VoteConfig { registrars: vec![registrar], ..VoteConfig::default() };
+    let (mut testkit, api) = create_testkit_with_config(config);
+    let election = api.init_election(&mut testkit);
+    let (den, den_sec) = crypto::gen_keypair();
+    let (ann, _) = crypto::gen_keypair();
+    let (john_tx, _) = api.create_candidate(&election, "John");
+    testkit.create_block_with_transactions(txvec![
+        RegisterElector::new(&election, &registrar, &den, "Den", 1, &registrar_sec),
+        RegisterElector::new(&election, &registrar, &ann, "Ann", 1, &registrar_sec),
+    ]);
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    api.vote(&election, john_tx.pub_key(), &den, &den_sec);
+    testkit.create_block();
+
+    // The vote of the revoked elector is still counted, so the elector stays in the electorate size.
+    testkit.create_block_with_transactions(txvec![
+        RevokeElector::new(&election, &registrar, &den, RevocationReason::Left as u8, 0, &registrar_sec),
+        RevokeElector::new(&election, &registrar, &ann, RevocationReason::Left as u8, 0, &registrar_sec),
+    ]);
+    let stats = api.get_stats(election);
+    assert_eq!(stats.electorate_size, 1);
+    assert_eq!(stats.ballots_cast, 1);
+    assert_eq!(stats.turnout_percent, 100.0);
+    assert_eq!(stats.abstentions, 0);
 }
 
 #[test]
 fn results() {
     let (mut testkit, api) = create_testkit();
-    let (den_1_tx, den_1_seq) = api.create_elector("Den_1");
-    let (den_2_tx, den_2_seq) = api.create_elector("Den_2");
-    let (den_3_tx, den_3_seq) = api.create_elector("Den_3");
+    let election = api.init_election(&mut testkit);
+    let (den_1_tx, den_1_seq) = api.create_elector(&election, "Den_1");
+    let (den_2_tx, den_2_seq) = api.create_elector(&election, "Den_2");
+    let (den_3_tx, den_3_seq) = api.create_elector(&election, "Den_3");
 
-    let (john_1_tx, _) = api.create_candidate("John_1");
-    let (john_2_tx, _) = api.create_candidate("John_2");
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
     testkit.create_block();
-    api.vote(john_1_tx.pub_key(), den_1_tx.pub_key(), &den_1_seq);
-    api.vote(john_2_tx.pub_key(), den_2_tx.pub_key(), &den_2_seq);
-    api.vote(john_1_tx.pub_key(), den_3_tx.pub_key(), &den_3_seq);
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    api.vote(&election, john_1_tx.pub_key(), den_1_tx.pub_key(), &den_1_seq);
+    api.vote(&election, john_2_tx.pub_key(), den_2_tx.pub_key(), &den_2_seq);
+    api.vote(&election, john_1_tx.pub_key(), den_3_tx.pub_key(), &den_3_seq);
     testkit.create_block();
 
-    let res = api.get_results();
+    let res = api.get_results(election);
+    assert_eq!(res.election.id(), &election);
+    assert_eq!(res.phase, ElectionPhase::Voting);
+    assert!(!res.is_final);
     assert_eq!(2, res.candidates.len());
+    assert_eq!(res.election.turnout(), 3);
+    assert_eq!(res.election.total_weight(), 3);
 
     assert_eq!(true, res.candidates.iter()
         .any(|c| c.candidate.name() == "John_1" && c.candidate.voices() == 2));
     assert_eq!(true, res.candidates.iter()
         .any(|c| c.candidate.name() == "John_2" && c.candidate.voices() == 1));
+
+    testkit.create_blocks_until(Height(TALLYING_HEIGHT - 1));
+    let res = api.get_results(election);
+    assert_eq!(res.phase, ElectionPhase::Tallying);
+    assert!(!res.is_final);
+
+    testkit.create_blocks_until(Height(CLOSING_HEIGHT - 1));
+    let res = api.get_results(election);
+    assert_eq!(res.phase, ElectionPhase::Closed);
+    assert!(res.is_final);
+}
+
+#[test]
+fn withdrawn_results() {
+    let (mut testkit, api) = create_testkit();
+    let election = api.init_election(&mut testkit);
+    let (den_1_tx, den_1_seq) = api.create_elector(&election, "Den_1");
+    let (den_2_tx, den_2_seq) = api.create_elector(&election, "Den_2");
+    let (den_3_tx, den_3_seq) = api.create_elector(&election, "Den_3");
+    let (john_1_tx, john_1_sec) = api.create_candidate(&election, "John_1");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    api.vote(&election, john_1_tx.pub_key(), den_1_tx.pub_key(), &den_1_seq);
+    api.vote(&election, john_1_tx.pub_key(), den_2_tx.pub_key(), &den_2_seq);
+    api.vote(&election, john_2_tx.pub_key(), den_3_tx.pub_key(), &den_3_seq);
+    testkit.create_block();
+
+    let tx = WithdrawCandidate::new(&election, john_1_tx.pub_key(), 0, &john_1_sec);
+    let tx_info: serde_json::Value = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&tx)
+        .post("v1/candidate/withdraw")
+        .unwrap();
+    assert_eq!(tx_info, json!({ "tx_hash": tx.hash() }));
+    testkit.create_block();
+    api.assert_tx_success(tx.hash());
+
+    let res = api.get_results(election);
+    assert_eq!(res.candidates.len(), 1);
+    assert_eq!(res.candidates[0].candidate.pub_key(), john_2_tx.pub_key());
+    assert_eq!(res.withdrawn.len(), 1);
+    assert_eq!(res.withdrawn[0].candidate.pub_key(), john_1_tx.pub_key());
+    assert_eq!(res.withdrawn[0].candidate.voices(), 2);
+    assert_eq!(res.seats.as_ref().unwrap().winners, vec![*john_2_tx.pub_key()]);
+    // The void votes aren't included in the total weight.
+    assert_eq!(res.total_weight, 1);
+    assert_eq!(res.candidates[0].vote_percent, 100.0);
+
+    let tally = client::verify_results(res, Vec::new(), &api.consensus_keys(&testkit)).unwrap();
+    assert_eq!(tally.voices, vec![(*john_2_tx.pub_key(), 1)]);
+}
+
+#[test]
+fn vote_receipt() {
+    let (mut testkit, api) = create_testkit();
+    let election = api.init_election(&mut testkit);
+    let (den_1_tx, den_1_sec) = api.create_elector(&election, "Den_1");
+    let (den_2_tx, _) = api.create_elector(&election, "Den_2");
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    api.vote(&election, john_1_tx.pub_key(), den_1_tx.pub_key(), &den_1_sec);
+    testkit.create_block();
+    let validators = api.consensus_keys(&testkit);
+
+    let receipt = api.get_receipt(election, *den_1_tx.pub_key(), None).unwrap();
+    let verified = client::verify_receipt_json(&receipt.to_string(), &validators).unwrap();
+    assert_eq!(verified.election, election);
+    assert_eq!(verified.elector, *den_1_tx.pub_key());
+    assert_eq!(verified.candidate, *john_1_tx.pub_key());
+    assert_eq!(verified.height, Height(VOTING_HEIGHT));
+    // The registration of the candidate comes first in the history.
+    assert_eq!(receipt["position"], json!(1));
+
+    let change = ChangeVote::new(&election, den_1_tx.pub_key(), john_2_tx.pub_key(), 1, &den_1_sec);
+    testkit.create_block_with_transaction(change.clone());
+    let receipt = api.get_receipt(election, *den_1_tx.pub_key(), None).unwrap();
+    let verified = client::verify_receipt_json(&receipt.to_string(), &validators).unwrap();
+    assert_eq!(verified.candidate, *john_2_tx.pub_key());
+    assert_eq!(verified.tx_hash, change.hash());
+    // The ballot is not in the history of the former candidate any more.
+    assert!(api.get_receipt(election, *den_1_tx.pub_key(), Some(*john_1_tx.pub_key())).is_none());
+
+    let mut tampered = receipt.clone();
+    tampered["position"] = json!(0);
+    let err = client::verify_receipt_json(&tampered.to_string(), &validators).unwrap_err();
+    assert_eq!(err, client::Error::InvalidHistoryProof(*john_2_tx.pub_key()));
+
+    let mut tampered = receipt.clone();
+    tampered["elector"]["name"] = json!("Ann");
+    let err = client::verify_receipt_json(&tampered.to_string(), &validators).unwrap_err();
+    assert_eq!(err, client::Error::InvalidElectorProof(*den_1_tx.pub_key()));
+
+    assert!(api.get_receipt(election, *den_2_tx.pub_key(), None).is_none());
+}
+
+#[test]
+fn historical_results() {
+    let (mut testkit, api) = create_testkit();
+    let election = api.init_election(&mut testkit);
+    let electors: Vec<_> = ["Den_1", "Den_2", "Den_3"].iter()
+        .map(|name| api.create_elector(&election, name))
+        .collect();
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    api.vote(&election, john_1_tx.pub_key(), electors[0].0.pub_key(), &electors[0].1);
+    testkit.create_blocks_until(Height(VOTING_HEIGHT + 1));
+    api.vote(&election, john_2_tx.pub_key(), electors[1].0.pub_key(), &electors[1].1);
+    api.vote(&election, john_1_tx.pub_key(), electors[2].0.pub_key(), &electors[2].1);
+    testkit.create_blocks_until(Height(VOTING_HEIGHT + 4));
+
+    let validators: Vec<PublicKey> = testkit.network().validators().iter()
+        .map(|validator| validator.public_keys().consensus_key)
+        .collect();
+    let expected = [
+        // The election is created in the first block, the candidates are registered later.
+        (1, 0, 0, 0),
+        (VOTING_HEIGHT - 1, 0, 0, 0),
+        (VOTING_HEIGHT, 1, 1, 0),
+        (VOTING_HEIGHT + 1, 1, 1, 0),
+        (VOTING_HEIGHT + 2, 3, 2, 1),
+        (VOTING_HEIGHT + 4, 3, 2, 1),
+    ];
+    for &(height, turnout, john_1, john_2) in &expected {
+        let results: serde_json::Value = api.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&ResultsQuery { election_id: election, height: Some(height) })
+            .get("v1/results")
+            .unwrap();
+        let tally = client::verify_results_json(&results.to_string(), &[], &validators).unwrap();
+        assert_eq!(tally.height, Height(VOTING_HEIGHT + 4));
+
+        let historical = tally.historical.unwrap();
+        assert_eq!(historical.height, Height(height));
+        assert_eq!(historical.turnout, turnout);
+        let mut voices = historical.voices.clone();
+        voices.sort_by_key(|&(pub_key, _)| if pub_key == *john_1_tx.pub_key() { 0 } else { 1 });
+        assert_eq!(voices, vec![(*john_1_tx.pub_key(), john_1), (*john_2_tx.pub_key(), john_2)]);
+    }
+
+    let mut tampered: serde_json::Value = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&ResultsQuery { election_id: election, height: Some(VOTING_HEIGHT) })
+        .get("v1/results")
+        .unwrap();
+    tampered["historical"]["candidates"][0]["voices"] = json!(2);
+    let err = client::verify_results_json(&tampered.to_string(), &[], &validators).unwrap_err();
+    assert_eq!(err, client::Error::InvalidCheckpointProof);
+
+    // The tally can't be moved past the block which proves the checkpoints.
+    let mut tampered: serde_json::Value = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&ResultsQuery { election_id: election, height: Some(VOTING_HEIGHT + 4) })
+        .get("v1/results")
+        .unwrap();
+    tampered["historical"]["height"] = json!(VOTING_HEIGHT + 5);
+    let err = client::verify_results_json(&tampered.to_string(), &[], &validators).unwrap_err();
+    assert_eq!(err, client::Error::InvalidCheckpointProof);
+
+    let future: Result<VotingResults, _> = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&ResultsQuery { election_id: election, height: Some(VOTING_HEIGHT + 5) })
+        .get("v1/results");
+    assert!(future.is_err());
+}
+
+#[test]
+fn turnout_stats() {
+    let (mut testkit, api) = create_testkit_with_config(VoteConfig { turnout_bucket_size: 5, ..VoteConfig::default() });
+    let election = api.init_election(&mut testkit);
+    let electors: Vec<_> = ["Den_1", "Den_2", "Den_3", "Den_4"].iter()
+        .map(|name| api.create_elector(&election, name))
+        .collect();
+    let (john_tx, _) = api.create_candidate(&election, "John");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    api.vote(&election, john_tx.pub_key(), electors[0].0.pub_key(), &electors[0].1);
+    api.vote(&election, john_tx.pub_key(), electors[1].0.pub_key(), &electors[1].1);
+    testkit.create_blocks_until(Height(VOTING_HEIGHT + 5 - 1));
+    api.vote(&election, john_tx.pub_key(), electors[2].0.pub_key(), &electors[2].1);
+    testkit.create_block();
+
+    let stats = api.get_stats(election);
+    assert_eq!(stats.electorate_size, 4);
+    assert_eq!(stats.ballots_cast, 3);
+    assert_eq!(stats.turnout_percent, 75.0);
+    assert_eq!(stats.abstentions, 1);
+    let buckets: Vec<(u64, u64)> = stats.buckets.iter().map(|bucket| (bucket.height(), bucket.ballots())).collect();
+    assert_eq!(buckets, vec![(VOTING_HEIGHT, 2), (VOTING_HEIGHT + 5, 1)]);
+
+    let election_proof = stats.election_proof.check().unwrap();
+    assert_eq!(election_proof.entries(), vec![(&election, &stats.election)]);
+    let buckets_proof = stats.buckets_proof.check().unwrap();
+    assert_eq!(buckets_proof.merkle_root(), *stats.election.turnout_buckets_hash());
+    assert_eq!(buckets_proof.entries().len(), 2);
+
+    // The bucket size is fixed at the creation of the election.
+    let mut proposal = testkit.configuration_change_proposal();
+    proposal.set_actual_from(Height(VOTING_HEIGHT + 6));
+    proposal.set_service_config(SERVICE_NAME, VoteConfig { turnout_bucket_size: 3, ..VoteConfig::default() });
+    testkit.commit_configuration_change(proposal);
+    testkit.create_blocks_until(Height(VOTING_HEIGHT + 6));
+
+    // The changed and then revoked ballot is taken out of the bucket which counted it.
+    let (elector, sec) = (electors[0].0.pub_key(), &electors[0].1);
+    testkit.create_block_with_transaction(ChangeVote::new(&election, elector, john_2_tx.pub_key(), 1, sec));
+    testkit.create_block_with_transaction(RevokeVote::new(&election, elector, 2, sec));
+    api.vote(&election, john_tx.pub_key(), electors[3].0.pub_key(), &electors[3].1);
+    testkit.create_block();
+    let stats = api.get_stats(election);
+    assert_eq!(stats.election.turnout_bucket_size(), 5);
+    assert_eq!(stats.ballots_cast, 3);
+    let buckets: Vec<(u64, u64)> = stats.buckets.iter().map(|bucket| (bucket.height(), bucket.ballots())).collect();
+    assert_eq!(buckets, vec![(VOTING_HEIGHT, 1), (VOTING_HEIGHT + 5, 2)]);
+}
+
+#[test]
+fn histories_in_state_hash() {
+    let (mut testkit, api) = create_testkit();
+    let election = api.init_election(&mut testkit);
+    let (den_1_tx, den_1_seq) = api.create_elector(&election, "Den_1");
+    let (den_2_tx, den_2_seq) = api.create_elector(&election, "Den_2");
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    let vote_1 = api.vote(&election, john_1_tx.pub_key(), den_1_tx.pub_key(), &den_1_seq);
+    let vote_2 = api.vote(&election, john_2_tx.pub_key(), den_2_tx.pub_key(), &den_2_seq);
+    testkit.create_block();
+
+    let res = api.get_results(election);
+    let state_hash = *res.block_proof.block.state_hash();
+    let to_table = res.history_roots_to_table.check().unwrap();
+    assert_eq!(to_table.merkle_root(), state_hash);
+    let history_roots = *to_table.entries()[0].1;
+    for info in res.candidates {
+        let key = history_id(&election, info.candidate.pub_key());
+        let root_proof = info.history_root_proof.check().unwrap();
+        assert_eq!(root_proof.merkle_root(), history_roots);
+        assert_eq!(root_proof.entries(), vec![(&key, info.candidate.history_hash())]);
+    }
+
+    let log: BallotLog = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&BallotLogQuery { from: 0, to: 2 })
+        .get("v1/ballots")
+        .unwrap();
+    assert_eq!(log.length, 2);
+    let mut ballots = log.transactions.clone();
+    ballots.sort();
+    let mut expected = vec![vote_1.hash(), vote_2.hash()];
+    expected.sort();
+    assert_eq!(ballots, expected);
+    let to_table = log.to_table.check().unwrap();
+    assert_eq!(to_table.merkle_root(), *log.block_proof.block.state_hash());
+    let entries = log.proof.validate(*to_table.entries()[0].1, log.length).unwrap();
+    assert_eq!(entries.len(), 2);
+
+    let invalid: Result<BallotLog, _> = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&BallotLogQuery { from: 1, to: 3 })
+        .get("v1/ballots");
+    assert!(invalid.is_err());
+}
+
+#[test]
+fn verify_results_offline() {
+    let (mut testkit, api) = create_testkit();
+    let election = api.init_election(&mut testkit);
+    let (den_1_tx, den_1_seq) = api.create_elector(&election, "Den_1");
+    let (den_2_tx, den_2_seq) = api.create_elector(&election, "Den_2");
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    api.vote(&election, john_1_tx.pub_key(), den_1_tx.pub_key(), &den_1_seq);
+    api.vote(&election, john_1_tx.pub_key(), den_2_tx.pub_key(), &den_2_seq);
+    testkit.create_block();
+
+    let validators: Vec<PublicKey> = testkit.network().validators().iter()
+        .map(|validator| validator.public_keys().consensus_key)
+        .collect();
+    let results: serde_json::Value = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&ElectionQuery { election_id: election })
+        .get("v1/results")
+        .unwrap();
+
+    let tally = client::verify_results_json(&results.to_string(), &[], &validators).unwrap();
+    assert_eq!(tally.election, election);
+    assert_eq!(tally.height, Height(VOTING_HEIGHT));
+    assert_eq!(tally.phase, ElectionPhase::Voting);
+    let mut voices = tally.voices.clone();
+    voices.sort_by_key(|&(_, voices)| voices);
+    assert_eq!(voices, vec![(*john_2_tx.pub_key(), 0), (*john_1_tx.pub_key(), 2)]);
+
+    let strangers = vec![crypto::gen_keypair().0];
+    let err = client::verify_results_json(&results.to_string(), &[], &strangers).unwrap_err();
+    assert_eq!(err, client::Error::InvalidPrecommit);
+
+    // The phase in the response isn't trusted, it's derived from the proven election and block.
+    let mut tampered = results.clone();
+    tampered["phase"] = json!("Closed");
+    let tally = client::verify_results_json(&tampered.to_string(), &[], &validators).unwrap();
+    assert_eq!(tally.phase, ElectionPhase::Voting);
+
+    let mut tampered = results.clone();
+    let candidate = tampered["candidates"][0]["candidate"].clone();
+    let pub_key: PublicKey = serde_json::from_value(candidate["pub_key"].clone()).unwrap();
+    tampered["candidates"][0]["candidate"]["voices"] = json!("5");
+    let err = client::verify_results_json(&tampered.to_string(), &[], &validators).unwrap_err();
+    assert_eq!(err, client::Error::InvalidCandidateProof(pub_key));
+
+    let mut tampered = results.clone();
+    tampered["candidates"][0]["history_root_proof"] = results["candidates"][1]["history_root_proof"].clone();
+    let err = client::verify_results_json(&tampered.to_string(), &[], &validators).unwrap_err();
+    assert_eq!(err, client::Error::InvalidHistoryProof(pub_key));
+
+    // A candidate left out of the results is detected by the number of the candidates in the election record.
+    let mut tampered = results.clone();
+    tampered["candidates"].as_array_mut().unwrap().remove(1);
+    let err = client::verify_results_json(&tampered.to_string(), &[], &validators).unwrap_err();
+    assert_eq!(err, client::Error::IncompleteCandidates);
+
+    let mut tampered = results.clone();
+    tampered["election"]["name"] = json!("Lunch");
+    let err = client::verify_results_json(&tampered.to_string(), &[], &validators).unwrap_err();
+    assert_eq!(err, client::Error::InvalidElectionProof);
+}
+
+#[test]
+fn candidate_history_pages() {
+    let (mut testkit, api) = create_testkit();
+    let election = api.init_election(&mut testkit);
+    let electors: Vec<_> = ["Den_1", "Den_2", "Den_3"].iter()
+        .map(|name| api.create_elector(&election, name))
+        .collect();
+    let (john_tx, _) = api.create_candidate(&election, "John");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    let votes: Vec<Hash> = electors.iter()
+        .map(|(tx, sec)| api.vote(&election, john_tx.pub_key(), tx.pub_key(), sec).hash())
+        .collect();
+    testkit.create_block();
+
+    let candidate = api.get_results(election).candidates.remove(0).candidate;
+    let query = HistoryQuery { election_id: election, pub_key: *john_tx.pub_key(), from: Some(1), limit: Some(1) };
+    let page: serde_json::Value = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&query)
+        .get("v1/candidate/history")
+        .unwrap();
+    // The history starts with the registration of the candidate.
+    assert_eq!(page["length"], json!(4));
+
+    let transactions = client::verify_history_json(&candidate, &page.to_string()).unwrap();
+    let hashes: Vec<Hash> = transactions.iter().map(|tx| tx.hash()).collect();
+    let second = VoteSchema::new(testkit.snapshot()).vote_history(&election, john_tx.pub_key()).get(1).unwrap();
+    assert!(votes.contains(&second));
+    assert_eq!(hashes, vec![second]);
+
+    let mut tampered = page.clone();
+    tampered["from"] = json!(0);
+    let err = client::verify_history_json(&candidate, &tampered.to_string()).unwrap_err();
+    assert_eq!(err, client::Error::InvalidHistoryProof(*john_tx.pub_key()));
+
+    let query = HistoryQuery { election_id: election, pub_key: *john_tx.pub_key(), from: Some(4), limit: None };
+    let past_end: Result<VotingHistory, _> = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&query)
+        .get("v1/candidate/history");
+    assert!(past_end.is_err());
+}
+
+#[test]
+fn paginated_listings() {
+    let (mut testkit, api) = create_testkit();
+    let election = api.init_election(&mut testkit);
+    let names = ["Den_1", "Den_2", "Den_3", "Ann_1", "Ann_2", "Eve_1", "Eve_2", "Eve_3", "Eve_4", "Eve_5", "Eve_6"];
+    let mut electors: Vec<(PublicKey, SecretKey)> = names.iter()
+        .map(|name| {
+            let (tx, sec) = api.create_elector(&election, name);
+            (*tx.pub_key(), sec)
+        })
+        .collect();
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
+    api.create_candidate(&election, "Mary");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+    api.vote(&election, john_1_tx.pub_key(), &electors[0].0, &electors[0].1);
+    testkit.create_block();
+    electors.sort_by_key(|&(pub_key, _)| pub_key);
+
+    // The pages follow the key order.
+    let mut listed = Vec::new();
+    let mut from = None;
+    loop {
+        let query = ElectorsQuery { election_id: election, from, limit: Some(2), name_prefix: None, has_vote: None };
+        let page = api.get_electors_page(&query).unwrap();
+        assert!(page.items.len() <= 2);
+        listed.extend(page.items.iter().map(|elector| *elector.pub_key()));
+        from = page.next;
+        if from.is_none() {
+            break;
+        }
+    }
+    assert_eq!(listed, electors.iter().map(|&(pub_key, _)| pub_key).collect::<Vec<_>>());
+
+    let query = ElectorsQuery { election_id: election, from: None, limit: None, name_prefix: Some("Ann".to_owned()), has_vote: None };
+    let page = api.get_electors_page(&query).unwrap();
+    assert_eq!(page.items.len(), 2);
+    assert!(page.items.iter().all(|elector| elector.name().starts_with("Ann")));
+    assert!(page.next.is_none());
+
+    let query = ElectorsQuery { election_id: election, from: None, limit: None, name_prefix: None, has_vote: Some(false) };
+    let page = api.get_electors_page(&query).unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].name(), "Den_1");
+
+    let query = CandidatesQuery { election_id: election, from: None, limit: Some(1), name_prefix: Some("John".to_owned()) };
+    let page = api.get_candidates_page(&query).unwrap();
+    assert_eq!(page.items.len(), 1);
+    let query = CandidatesQuery { election_id: election, from: page.next, limit: Some(1), name_prefix: Some("John".to_owned()) };
+    let next_page = api.get_candidates_page(&query).unwrap();
+    assert_eq!(next_page.items.len(), 1);
+    assert!(next_page.next.is_none());
+    let mut johns = vec![*page.items[0].pub_key(), *next_page.items[0].pub_key()];
+    johns.sort();
+    let mut expected = vec![*john_1_tx.pub_key(), *john_2_tx.pub_key()];
+    expected.sort();
+    assert_eq!(johns, expected);
+
+    let query = CandidatesQuery { election_id: election, from: None, limit: Some(0), name_prefix: None };
+    assert!(api.get_candidates_page(&query).is_none());
+
+    // The scan of a page is capped, a short page still points to the rest of the electorate.
+    let query = ElectorsQuery { election_id: election, from: None, limit: Some(1), name_prefix: Some("Bob".to_owned()), has_vote: None };
+    let page = api.get_electors_page(&query).unwrap();
+    assert!(page.items.is_empty());
+    assert!(page.next.is_some());
+    let query = ElectorsQuery { from: page.next, ..query };
+    let page = api.get_electors_page(&query).unwrap();
+    assert!(page.items.is_empty());
+    assert!(page.next.is_none());
+}
+
+#[test]
+fn approval_results() {
+    let (mut testkit, api) = create_testkit_with_config(VoteConfig { voting_scheme: VotingScheme::Approval, ..VoteConfig::default() });
+    let election_tx = api.create_election_with_seats("Board", 2, 2);
+    testkit.create_block();
+    let election = election_tx.hash();
+    let electors: Vec<_> = (0..3).map(|i| api.create_elector(&election, &format!("Den_{}", i))).collect();
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
+    let (john_3_tx, _) = api.create_candidate(&election, "John_3");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+
+    let approvals = vec![
+        vec![*john_1_tx.pub_key(), *john_2_tx.pub_key()],
+        vec![*john_1_tx.pub_key(), *john_3_tx.pub_key()],
+        vec![*john_1_tx.pub_key()],
+    ];
+    for ((elector_tx, sec), approved) in electors.iter().zip(approvals) {
+        let tx = ApprovalVote::new(&election, elector_tx.pub_key(), &keys_to_hashes(&approved), 0, sec);
+        let tx_info: serde_json::Value = api.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&tx)
+            .post("v1/vote/approval")
+            .unwrap();
+        assert_eq!(tx_info, json!({ "tx_hash": tx.hash() }));
+    }
+    testkit.create_block();
+
+    let res = api.get_results(election);
+    let seats = res.seats.unwrap();
+    assert_eq!(seats.winners, vec![*john_1_tx.pub_key()]);
+    assert_eq!(seats.tied.len(), 2);
+    assert!(seats.tied.contains(john_2_tx.pub_key()));
+    assert!(seats.tied.contains(john_3_tx.pub_key()));
+}
+
+#[test]
+fn irv_results() {
+    let (mut testkit, api) = create_testkit_with_config(VoteConfig { voting_scheme: VotingScheme::RankedChoice, ..VoteConfig::default() });
+    let election_tx = api.create_election_with_seats("Board", 1, 3);
+    testkit.create_block();
+    let election = election_tx.hash();
+    let electors: Vec<_> = (0..4).map(|i| api.create_elector(&election, &format!("Den_{}", i))).collect();
+    let (john_1_tx, _) = api.create_candidate(&election, "John_1");
+    let (john_2_tx, _) = api.create_candidate(&election, "John_2");
+    let (john_3_tx, _) = api.create_candidate(&election, "John_3");
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 1));
+
+    let rankings = vec![
+        vec![*john_1_tx.pub_key()],
+        vec![*john_1_tx.pub_key(), *john_2_tx.pub_key()],
+        vec![*john_2_tx.pub_key(), *john_1_tx.pub_key()],
+        vec![*john_3_tx.pub_key(), *john_1_tx.pub_key()],
+    ];
+    for ((elector_tx, sec), ranking) in electors.iter().zip(rankings) {
+        let tx = RankedVote::new(&election, elector_tx.pub_key(), &keys_to_hashes(&ranking), 0, sec);
+        let tx_info: serde_json::Value = api.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&tx)
+            .post("v1/vote/ranked")
+            .unwrap();
+        assert_eq!(tx_info, json!({ "tx_hash": tx.hash() }));
+    }
+    testkit.create_block();
+
+    let res = api.get_irv_results(election);
+    assert_eq!(res.election.id(), &election);
+    assert_eq!(res.phase, ElectionPhase::Voting);
+    assert_eq!(res.rounds.len(), 2);
+    assert_eq!(res.rounds[0].eliminated.len(), 2);
+    assert_eq!(res.winner, Some(*john_1_tx.pub_key()));
+
+    let err = api.inner
+        .public(ApiKind::Service(SERVICE_NAME))
+        .query(&ElectionQuery { election_id: crypto::hash(b"unknown") })
+        .get::<IrvResults>("v1/results/irv");
+    assert!(err.is_err());
 }
 
 struct Api {
@@ -138,9 +1228,32 @@ struct Api {
 }
 
 impl Api {
-    fn create_candidate(&self, name: &str) -> (CreateCandidate, SecretKey) {
+    fn create_election(&self, name: &str) -> CreateElection {
+        self.create_election_with_seats(name, 1, 1)
+    }
+
+    fn create_election_with_seats(&self, name: &str, seats: u32, max_choices: u32) -> CreateElection {
         let (pubkey, key) = crypto::gen_keypair();
-        let tx = CreateCandidate::new(&pubkey, name, &key);
+        let tx = CreateElection::new(&pubkey, name, VOTING_HEIGHT, TALLYING_HEIGHT, CLOSING_HEIGHT, seats, max_choices, CONFIGURED_SCHEME, 0, &key);
+        let tx_info: serde_json::Value = self.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&tx)
+            .post("v1/election")
+            .unwrap();
+        assert_eq!(tx_info, json!({ "tx_hash": tx.hash() }));
+        tx
+    }
+
+    fn init_election(&self, testkit: &mut TestKit) -> Hash {
+        let tx = self.create_election("Election");
+        testkit.create_block();
+        self.assert_tx_success(tx.hash());
+        tx.hash()
+    }
+
+    fn create_candidate(&self, election: &Hash, name: &str) -> (CreateCandidate, SecretKey) {
+        let (pubkey, key) = crypto::gen_keypair();
+        let tx = CreateCandidate::new(election, &pubkey, name, &key);
         println!("create candidate: {}", serde_json::to_string_pretty(&tx).unwrap());
         let tx_info: serde_json::Value = self.inner
             .public(ApiKind::Service(SERVICE_NAME))
@@ -151,9 +1264,9 @@ impl Api {
         (tx, key)
     }
 
-    fn create_elector(&self, name: &str) -> (CreateElector, SecretKey) {
+    fn create_elector(&self, election: &Hash, name: &str) -> (CreateElector, SecretKey) {
         let (pubkey, key) = crypto::gen_keypair();
-        let tx = CreateElector::new(&pubkey, name, &key);
+        let tx = CreateElector::new(election, &pubkey, name, &key);
         println!("create elector: {}", serde_json::to_string_pretty(&tx).unwrap());
         let tx_info: serde_json::Value = self.inner
             .public(ApiKind::Service(SERVICE_NAME))
@@ -164,8 +1277,8 @@ impl Api {
         (tx, key)
     }
 
-    fn vote(&self, candidate_pub_key: &PublicKey, elector_pub_key: &PublicKey, elector_sec_key: &SecretKey) -> Vote {
-        let tx = Vote::new(&elector_pub_key, candidate_pub_key, elector_sec_key);
+    fn vote(&self, election: &Hash, candidate_pub_key: &PublicKey, elector_pub_key: &PublicKey, elector_sec_key: &SecretKey) -> Vote {
+        let tx = Vote::new(election, &elector_pub_key, candidate_pub_key, 0, elector_sec_key);
         println!("vote elector: {}", serde_json::to_string_pretty(&tx).unwrap());
 
         let tx_info: serde_json::Value = self.inner
@@ -196,48 +1309,144 @@ impl Api {
         self.assert_tx_status(tx_hash, &json!({ "type": "success" }));
     }
 
-    fn get_elector(&self, pub_key: PublicKey) -> Option<Elector> {
+    fn get_election(&self, election_id: Hash) -> Option<Election> {
+        self.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&ElectionQuery { election_id })
+            .get::<Election>("v1/election")
+            .ok()
+    }
+
+    fn get_elections(&self) -> Vec<Election> {
         self.inner
             .public(ApiKind::Service(SERVICE_NAME))
-            .query(&ElectorQuery { pub_key })
+            .get::<Vec<Election>>("v1/elections")
+            .unwrap()
+    }
+
+    fn get_registrars(&self) -> Vec<PublicKey> {
+        self.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .get::<Vec<PublicKey>>("v1/registrars")
+            .unwrap()
+    }
+
+    fn get_elector(&self, election_id: Hash, pub_key: PublicKey) -> Option<Elector> {
+        self.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&ElectorQuery { election_id, pub_key })
             .get::<Elector>("v1/elector")
             .ok()
     }
 
-    fn get_block_number(&self, pub_key: PublicKey) -> Option<i32> {
+    fn get_receipt(&self, election_id: Hash, pub_key: PublicKey, candidate: Option<PublicKey>) -> Option<serde_json::Value> {
+        self.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&ReceiptQuery { election_id, pub_key, candidate })
+            .get("v1/elector/receipt")
+            .ok()
+    }
+
+    fn consensus_keys(&self, testkit: &TestKit) -> Vec<PublicKey> {
+        testkit.network().validators().iter()
+            .map(|validator| validator.public_keys().consensus_key)
+            .collect()
+    }
+
+    fn get_block_number(&self, election_id: Hash, pub_key: PublicKey) -> Option<VoteLocation> {
         self.inner
             .public(ApiKind::Service(SERVICE_NAME))
-            .query(&ElectorQuery { pub_key })
-            .get::<i32>("v1/vote/block")
+            .query(&ElectorQuery { election_id, pub_key })
+            .get::<VoteLocation>("v1/vote/block")
             .ok()
     }
 
-    fn get_candidate(&self, pub_key: PublicKey) -> Option<Candidate> {
+    fn get_candidate(&self, election_id: Hash, pub_key: PublicKey) -> Option<Candidate> {
         self.inner
             .public(ApiKind::Service(SERVICE_NAME))
-            .query(&CandidateQuery { pub_key })
+            .query(&CandidateQuery { election_id, pub_key })
             .get::<Candidate>("v1/candidate")
             .ok()
     }
 
-    fn get_candidates(&self) -> Vec<Candidate> {
+    fn get_candidates(&self, election_id: Hash) -> Vec<Candidate> {
+        let query = CandidatesQuery { election_id, from: None, limit: None, name_prefix: None };
+        self.get_candidates_page(&query).unwrap().items
+    }
+
+    fn get_candidates_page(&self, query: &CandidatesQuery) -> Option<Page<Candidate>> {
+        self.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(query)
+            .get::<Page<Candidate>>("v1/candidates")
+            .ok()
+    }
+
+    fn get_electors_page(&self, query: &ElectorsQuery) -> Option<Page<Elector>> {
+        self.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(query)
+            .get::<Page<Elector>>("v1/electors")
+            .ok()
+    }
+
+    fn get_stats(&self, election_id: Hash) -> ElectionStats {
         self.inner
             .public(ApiKind::Service(SERVICE_NAME))
-            .get::<Vec<Candidate>>("v1/candidates")
+            .query(&ElectionQuery { election_id })
+            .get("v1/stats")
             .unwrap()
     }
 
-    fn get_results(&self) -> VotingResults {
+    fn get_results(&self, election_id: Hash) -> VotingResults {
         self.inner
             .public(ApiKind::Service(SERVICE_NAME))
+            .query(&ElectionQuery { election_id })
             .get::<VotingResults>("v1/results")
             .unwrap()
     }
+
+    fn get_delegation_pages(&self, election_id: Hash, limit: u32) -> Vec<DelegationPage> {
+        let mut pages = Vec::new();
+        let mut from = None;
+        loop {
+            let page: DelegationPage = self.inner
+                .public(ApiKind::Service(SERVICE_NAME))
+                .query(&ProofPageQuery { election_id, from, limit: Some(limit) })
+                .get("v1/results/delegations")
+                .unwrap();
+            from = page.delegations.next;
+            pages.push(page);
+            if from.is_none() {
+                return pages;
+            }
+        }
+    }
+
+    fn get_ring(&self, election_id: Hash, index: u64) -> SubRing {
+        self.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&RingQuery { election_id, index })
+            .get::<SubRing>("v1/election/ring")
+            .unwrap()
+    }
+
+    fn get_irv_results(&self, election_id: Hash) -> IrvResults {
+        self.inner
+            .public(ApiKind::Service(SERVICE_NAME))
+            .query(&ElectionQuery { election_id })
+            .get::<IrvResults>("v1/results/irv")
+            .unwrap()
+    }
 }
 
 fn create_testkit() -> (TestKit, Api) {
+    create_testkit_with_config(VoteConfig::default())
+}
+
+fn create_testkit_with_config(config: VoteConfig) -> (TestKit, Api) {
     let testkit = TestKitBuilder::validator()
-        .with_service(VoteService)
+        .with_service(VoteService::new(config))
         .create();
     let api = Api {
         inner: testkit.api(),
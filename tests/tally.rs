@@ -0,0 +1,149 @@
+extern crate exonum;
+extern crate vote_service as vote;
+
+use std::collections::BTreeMap;
+
+use exonum::crypto::{self, PublicKey};
+
+use vote::tally::{instant_runoff, resolve_delegation, top_candidates, CandidateTally, WeightedBallot};
+
+fn unweighted(ballots: Vec<Vec<PublicKey>>) -> Vec<WeightedBallot> {
+    ballots.into_iter()
+        .map(|choices| WeightedBallot { weight: 1, choices })
+        .collect()
+}
+
+fn keys(count: usize) -> Vec<PublicKey> {
+    let mut keys: Vec<PublicKey> = (0..count).map(|_| crypto::gen_keypair().0).collect();
+    keys.sort();
+    keys
+}
+
+#[test]
+fn test_irv_majority_in_first_round() {
+    let k = keys(3);
+    let ballots = vec![
+        vec![k[0], k[1]],
+        vec![k[0]],
+        vec![k[1], k[0]],
+    ];
+
+    let outcome = instant_runoff(&k, &unweighted(ballots));
+    assert_eq!(outcome.winner, Some(k[0]));
+    assert_eq!(outcome.rounds.len(), 1);
+    assert_eq!(outcome.rounds[0].tallies[0].votes, 2);
+    assert!(outcome.rounds[0].eliminated.is_empty());
+}
+
+#[test]
+fn test_irv_transfers_eliminated_votes() {
+    let k = keys(3);
+    let ballots = vec![
+        vec![k[0]],
+        vec![k[0]],
+        vec![k[1], k[2]],
+        vec![k[1], k[2]],
+        vec![k[2], k[1]],
+    ];
+
+    let outcome = instant_runoff(&k, &unweighted(ballots));
+    assert_eq!(outcome.rounds.len(), 2);
+    assert_eq!(outcome.rounds[0].eliminated, vec![k[2]]);
+    assert_eq!(outcome.rounds[1].tallies.len(), 2);
+    assert_eq!(outcome.rounds[1].tallies[1].votes, 3);
+    assert_eq!(outcome.winner, Some(k[1]));
+}
+
+#[test]
+fn test_irv_exhausted_ballots() {
+    let k = keys(3);
+    let ballots = vec![
+        vec![k[0]],
+        vec![k[0]],
+        vec![k[1]],
+        vec![k[1]],
+        vec![k[2]],
+    ];
+
+    let outcome = instant_runoff(&k, &unweighted(ballots));
+    assert_eq!(outcome.rounds[0].eliminated, vec![k[2]]);
+    assert_eq!(outcome.rounds[1].exhausted, 1);
+    // The remaining candidates are tied.
+    assert_eq!(outcome.rounds.len(), 2);
+    assert_eq!(outcome.winner, None);
+}
+
+#[test]
+fn test_irv_weighted_ballots() {
+    let k = keys(3);
+    let ballots = vec![
+        WeightedBallot { weight: 10, choices: vec![k[0]] },
+        WeightedBallot { weight: 4, choices: vec![k[1], k[2]] },
+        WeightedBallot { weight: 7, choices: vec![k[2], k[1]] },
+    ];
+
+    let outcome = instant_runoff(&k, &ballots);
+    assert_eq!(outcome.rounds[0].eliminated, vec![k[1]]);
+    assert_eq!(outcome.rounds[1].tallies[1].votes, 11);
+    assert_eq!(outcome.winner, Some(k[2]));
+}
+
+#[test]
+fn test_irv_without_ballots() {
+    let k = keys(2);
+    let outcome = instant_runoff(&k, &[]);
+    assert_eq!(outcome.rounds.len(), 1);
+    assert_eq!(outcome.winner, None);
+}
+
+fn tallies(keys: &[PublicKey], votes: &[u64]) -> Vec<CandidateTally> {
+    keys.iter().zip(votes)
+        .map(|(pub_key, votes)| CandidateTally { pub_key: *pub_key, votes: *votes })
+        .collect()
+}
+
+#[test]
+fn test_top_candidates() {
+    let k = keys(4);
+    let seats = top_candidates(&tallies(&k, &[1, 5, 3, 2]), 2);
+    assert_eq!(seats.winners, vec![k[1], k[2]]);
+    assert!(seats.tied.is_empty());
+}
+
+#[test]
+fn test_top_candidates_with_tie() {
+    let k = keys(4);
+    let seats = top_candidates(&tallies(&k, &[3, 5, 3, 2]), 2);
+    assert_eq!(seats.winners, vec![k[1]]);
+    assert_eq!(seats.tied, vec![k[0], k[2]]);
+
+    // The tied candidates are seated if there are enough seats.
+    let seats = top_candidates(&tallies(&k, &[3, 5, 3, 2]), 3);
+    assert_eq!(seats.winners, vec![k[1], k[0], k[2]]);
+    assert!(seats.tied.is_empty());
+}
+
+#[test]
+fn test_resolve_delegation() {
+    let k = keys(4);
+    let delegations: BTreeMap<PublicKey, PublicKey> = vec![(k[0], k[1]), (k[1], k[2]), (k[3], k[0])]
+        .into_iter()
+        .collect();
+    let delegate = |elector: &PublicKey| delegations.get(elector).cloned();
+
+    // The chain is followed up to the first elector who has voted.
+    assert_eq!(resolve_delegation(&k[3], delegate, |e: &PublicKey| e == &k[2]), Some(k[2]));
+    assert_eq!(resolve_delegation(&k[3], delegate, |e: &PublicKey| e == &k[1] || e == &k[2]), Some(k[1]));
+    // The direct vote overrides the delegation.
+    assert_eq!(resolve_delegation(&k[0], delegate, |e: &PublicKey| e == &k[0]), Some(k[0]));
+    // Nobody in the chain has voted.
+    assert_eq!(resolve_delegation(&k[3], delegate, |_: &PublicKey| false), None);
+}
+
+#[test]
+fn test_resolve_delegation_cycle() {
+    let k = keys(2);
+    let delegations: BTreeMap<PublicKey, PublicKey> = vec![(k[0], k[1]), (k[1], k[0])].into_iter().collect();
+    let delegate = |elector: &PublicKey| delegations.get(elector).cloned();
+    assert_eq!(resolve_delegation(&k[0], delegate, |_: &PublicKey| false), None);
+}
@@ -1,37 +1,171 @@
+extern crate curve25519_dalek;
 extern crate exonum;
 extern crate vote_service as vote;
+#[macro_use]
 extern crate exonum_testkit;
 
-use exonum::crypto::{self, PublicKey, SecretKey};
+use std::collections::HashMap;
+
+use exonum::{
+    blockchain::Transaction,
+    crypto::{self, CryptoHash, Hash, PublicKey, SecretKey},
+    helpers::Height,
+};
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
 use exonum_testkit::{TestKit, TestKitBuilder};
 
 use vote::{
-    transactions::{CreateCandidate, CreateElector, Vote},
-    schema::{VoteSchema, Candidate, Elector},
+    config::{RevokedVotes, VoteConfig, VotingScheme, WithdrawnVotes, CONFIGURED_SCHEME},
+    blind::{self, Blinding, IssuingKey, Session},
+    dkg::{self, Dealing, SharedKeyProof},
+    elgamal::{self, EqualityProof},
+    lsag::{self, RingSignature},
+    service::{VoteService, SERVICE_NAME},
+    transactions::{
+        AnonymousVote, ApprovalVote, ChangeVote, CommitVote, CreateCandidate, CreateElection, CreateElector, Delegate, EncryptedVote,
+        IssueToken, RankedVote, RegisterElector, RevealVote, RevokeVote, SubmitDecryption, SubmitKeyShare, Vote, VoteWithToken,
+        WithdrawCandidate, DisqualifyCandidate, RevokeElector, RotateKey, RegisterElectorsBatch, ComplainKeyShare, CommitToken,
+        MAX_BATCH_SIZE,
+    },
+    schema::{
+        anonymous_ballot, key_rotation, keys_to_hashes, vote_commitment, VoteSchema, Candidate, CandidateStatus, DecryptionShare, Elector, ElectorEntry, ElectionPhase,
+        RevocationReason,
+    },
 };
 
+const VOTING_HEIGHT: u64 = 10;
+const TALLYING_HEIGHT: u64 = 20;
+const CLOSING_HEIGHT: u64 = 30;
+
+#[test]
+fn test_create_election() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    let election = schema.election(&election).expect("No election persisted.");
+    assert_eq!(election.name(), "Board");
+    assert_eq!(election.candidates_hash(), &schema.candidates(election.id()).merkle_root());
+    assert_eq!(election.phase(schema.height()), ElectionPhase::Registration);
+    assert_eq!(election.phase(Height(VOTING_HEIGHT)), ElectionPhase::Voting);
+    assert_eq!(election.phase(Height(TALLYING_HEIGHT)), ElectionPhase::Tallying);
+    assert_eq!(election.phase(Height(CLOSING_HEIGHT)), ElectionPhase::Closed);
+}
+
+#[test]
+fn test_create_election_with_invalid_heights() {
+    let mut testkit = init_testkit();
+    let (pubkey, key) = crypto::gen_keypair();
+
+    let in_past = CreateElection::new(&pubkey, "Board", 1, TALLYING_HEIGHT, CLOSING_HEIGHT, 1, 1, CONFIGURED_SCHEME, 0, &key);
+    let no_voting = CreateElection::new(&pubkey, "Board", VOTING_HEIGHT, VOTING_HEIGHT, CLOSING_HEIGHT, 1, 1, CONFIGURED_SCHEME, 1, &key);
+    let closed_early = CreateElection::new(&pubkey, "Board", VOTING_HEIGHT, TALLYING_HEIGHT, VOTING_HEIGHT, 1, 1, CONFIGURED_SCHEME, 2, &key);
+    let block = testkit.create_block_with_transactions(txvec![in_past, no_voting, closed_early]);
+
+    for tx in &block.transactions {
+        let tx_status = tx.status().err().expect("Expect error.");
+        assert_eq!(tx_status.description(), Some("Invalid election phase heights"));
+    }
+    assert_eq!(VoteSchema::new(&testkit.snapshot()).elections().iter().count(), 0);
+}
+
+#[test]
+fn test_create_election_with_invalid_seats() {
+    let mut testkit = init_testkit();
+    let (pubkey, key) = crypto::gen_keypair();
+
+    let no_seats = CreateElection::new(&pubkey, "Board", VOTING_HEIGHT, TALLYING_HEIGHT, CLOSING_HEIGHT, 0, 1, CONFIGURED_SCHEME, 0, &key);
+    let no_choices = CreateElection::new(&pubkey, "Board", VOTING_HEIGHT, TALLYING_HEIGHT, CLOSING_HEIGHT, 1, 0, CONFIGURED_SCHEME, 1, &key);
+    // A plurality ballot has a single choice.
+    let many_choices = CreateElection::new(&pubkey, "Board", VOTING_HEIGHT, TALLYING_HEIGHT, CLOSING_HEIGHT, 2, 2, CONFIGURED_SCHEME, 2, &key);
+    let block = testkit.create_block_with_transactions(txvec![no_seats, no_choices, many_choices]);
+
+    for tx in &block.transactions {
+        let tx_status = tx.status().err().expect("Expect error.");
+        assert_eq!(tx_status.description(), Some("Invalid number of seats or choices"));
+    }
+    assert_eq!(VoteSchema::new(&testkit.snapshot()).elections().iter().count(), 0);
+}
+
+#[test]
+fn test_create_election_with_scheme() {
+    let mut testkit = init_testkit();
+    let (pubkey, key) = crypto::gen_keypair();
+
+    let approval = CreateElection::new(
+        &pubkey, "Board", VOTING_HEIGHT, TALLYING_HEIGHT, CLOSING_HEIGHT, 2, 2, VotingScheme::Approval as u8, 0, &key,
+    );
+    let configured = CreateElection::new(&pubkey, "Board", VOTING_HEIGHT, TALLYING_HEIGHT, CLOSING_HEIGHT, 1, 1, CONFIGURED_SCHEME, 1, &key);
+    let unknown = CreateElection::new(&pubkey, "Board", VOTING_HEIGHT, TALLYING_HEIGHT, CLOSING_HEIGHT, 1, 1, 7, 2, &key);
+    let block = testkit.create_block_with_transactions(txvec![approval.clone(), configured.clone(), unknown]);
+
+    let tx_status = block.transactions[2].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Unknown voting scheme"));
+
+    let schema = VoteSchema::new(testkit.snapshot());
+    assert_eq!(schema.election(&approval.hash()).unwrap().voting_scheme(), VotingScheme::Approval);
+    assert_eq!(schema.election(&configured.hash()).unwrap().voting_scheme(), VotingScheme::Plurality);
+}
+
 #[test]
 fn test_create_candidate() {
     let mut testkit = init_testkit();
-    let (john, _) = create_candidate(&mut testkit, "John");
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
 
-    let candidate = get_candidate(&testkit, john.pub_key());
+    let candidate = get_candidate(&testkit, &election, john.pub_key());
     assert_eq!(candidate.pub_key(), john.pub_key());
     assert_eq!(candidate.name(), "John");
     assert_eq!(candidate.voices(), 0);
 }
 
+#[test]
+fn test_create_candidate_without_election() {
+    let mut testkit = init_testkit();
+    let election = crypto::hash(b"unknown");
+    let (pubkey, key) = crypto::gen_keypair();
+    let tx = CreateCandidate::new(&election, &pubkey, "John", &key);
+
+    let block = testkit.create_block_with_transaction(tx);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Election doesn't exist"));
+    assert!(try_get_candidate(&testkit, &election, &pubkey).is_none());
+}
+
+#[test]
+fn test_candidate_name_limits() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+
+    let (empty, empty_sec) = crypto::gen_keypair();
+    let (long, long_sec) = crypto::gen_keypair();
+    let long_name = "J".repeat(VoteConfig::default().max_candidate_name_len as usize + 1);
+    let block = testkit.create_block_with_transactions(txvec![
+        CreateCandidate::new(&election, &empty, "", &empty_sec),
+        CreateCandidate::new(&election, &long, &long_name, &long_sec),
+    ]);
+
+    for tx in &block.transactions {
+        let tx_status = tx.status().err().expect("Expect error.");
+        assert_eq!(tx_status.description(), Some("Invalid candidate name length"));
+    }
+    assert!(try_get_candidate(&testkit, &election, &empty).is_none());
+    assert!(try_get_candidate(&testkit, &election, &long).is_none());
+}
+
 #[test]
 fn test_candidate_identity() {
     let mut testkit = init_testkit();
-    let (john, sec) = create_candidate(&mut testkit, "John");
+    let election = create_election(&mut testkit, "Board");
+    let (john, sec) = create_candidate(&mut testkit, &election, "John");
 
-    let john_1 = CreateCandidate::new(john.pub_key(), "John_1", &sec);
+    let john_1 = CreateCandidate::new(&election, john.pub_key(), "John_1", &sec);
     let block = testkit.create_block_with_transaction(john_1.clone());
     let tx_status = block.transactions[0].status().err().expect("Expect error.");
     assert_eq!(tx_status.description(), Some("Candidate already exists"));
 
-    let candidate = get_candidate(&testkit, john.pub_key());
+    let candidate = get_candidate(&testkit, &election, john.pub_key());
     assert_eq!(candidate.pub_key(), john.pub_key());
     assert_eq!(candidate.name(), "John");
     assert_eq!(candidate.voices(), 0);
@@ -40,9 +174,10 @@ fn test_candidate_identity() {
 #[test]
 fn test_create_elector() {
     let mut testkit = init_testkit();
-    let (den, _) = create_elector(&mut testkit, "Den");
+    let election = create_election(&mut testkit, "Board");
+    let (den, _) = create_elector(&mut testkit, &election, "Den");
 
-    let candidate = get_elector(&testkit, den.pub_key());
+    let candidate = get_elector(&testkit, &election, den.pub_key());
     assert_eq!(candidate.pub_key(), den.pub_key());
     assert_eq!(candidate.name(), "Den");
     assert_eq!(candidate.has_vote(), true);
@@ -51,94 +186,1572 @@ fn test_create_elector() {
 #[test]
 fn test_elector_identity() {
     let mut testkit = init_testkit();
-    let (den, sec) = create_elector(&mut testkit, "Den");
+    let election = create_election(&mut testkit, "Board");
+    let (den, sec) = create_elector(&mut testkit, &election, "Den");
 
-    let den_1 = CreateElector::new(den.pub_key(), "Den_1", &sec);
+    let den_1 = CreateElector::new(&election, den.pub_key(), "Den_1", &sec);
 
     let block = testkit.create_block_with_transaction(den_1.clone());
     let tx_status = block.transactions[0].status().err().expect("Expect error.");
     assert_eq!(tx_status.description(), Some("Elector already exists"));
 
-    let candidate = get_elector(&testkit, den.pub_key());
+    let candidate = get_elector(&testkit, &election, den.pub_key());
     assert_eq!(candidate.pub_key(), den.pub_key());
     assert_eq!(candidate.name(), "Den");
     assert_eq!(candidate.has_vote(), true);
 }
 
+#[test]
+fn test_register_elector() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let mut testkit = init_testkit_with_registrar(&registrar);
+    let election = create_election(&mut testkit, "Board");
+
+    let (den, _) = crypto::gen_keypair();
+    let tx = RegisterElector::new(&election, &registrar, &den, "Den", 1, &registrar_sec);
+    testkit.create_block_with_transaction(tx);
+
+    let elector = get_elector(&testkit, &election, &den);
+    assert_eq!(elector.name(), "Den");
+    assert!(elector.has_vote());
+}
+
+#[test]
+fn test_register_elector_by_stranger() {
+    let (registrar, _) = crypto::gen_keypair();
+    let mut testkit = init_testkit_with_registrar(&registrar);
+    let election = create_election(&mut testkit, "Board");
+
+    let (den, den_sec) = crypto::gen_keypair();
+    let tx = RegisterElector::new(&election, &den, &den, "Den", 1, &den_sec);
+    let block = testkit.create_block_with_transaction(tx);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The signer is not a registrar"));
+    assert!(try_get_elector(&testkit, &election, &den).is_none());
+}
+
+#[test]
+fn test_self_registration_with_registrars() {
+    let (registrar, _) = crypto::gen_keypair();
+    let mut testkit = init_testkit_with_registrar(&registrar);
+    let election = create_election(&mut testkit, "Board");
+
+    let (den, den_sec) = crypto::gen_keypair();
+    let tx = CreateElector::new(&election, &den, "Den", &den_sec);
+    let block = testkit.create_block_with_transaction(tx);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Electors must be registered by a registrar"));
+    assert!(try_get_elector(&testkit, &election, &den).is_none());
+}
+
+#[test]
+fn test_registrar_added_by_configuration_change() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let mut proposal = testkit.configuration_change_proposal();
+    proposal.set_actual_from(Height(5));
+    proposal.set_service_config(SERVICE_NAME, VoteConfig { registrars: vec![registrar], ..VoteConfig::default() });
+    testkit.commit_configuration_change(proposal);
+    testkit.create_blocks_until(Height(4));
+
+    let (den, _) = crypto::gen_keypair();
+    let tx = RegisterElector::new(&election, &registrar, &den, "Den", 1, &registrar_sec);
+    testkit.create_block_with_transaction(tx);
+    assert!(try_get_elector(&testkit, &election, &den).is_some());
+
+    let (mallory, mallory_sec) = crypto::gen_keypair();
+    let tx = CreateElector::new(&election, &mallory, "Mallory", &mallory_sec);
+    let block = testkit.create_block_with_transaction(tx);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Electors must be registered by a registrar"));
+}
+
+#[test]
+fn test_invalid_configuration() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+
+    let mut malformed = HashMap::new();
+    malformed.insert("registrars", "nobody");
+    let mut proposal = testkit.configuration_change_proposal();
+    proposal.set_actual_from(Height(5));
+    proposal.set_service_config(SERVICE_NAME, malformed);
+    testkit.commit_configuration_change(proposal);
+    testkit.create_blocks_until(Height(4));
+
+    let (den, den_sec) = crypto::gen_keypair();
+    let tx = CreateElector::new(&election, &den, "Den", &den_sec);
+    let block = testkit.create_block_with_transaction(tx);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The vote service configuration is invalid"));
+    assert!(try_get_elector(&testkit, &election, &den).is_none());
+}
+
+#[test]
+fn test_registration_closed() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+    start_voting(&mut testkit, &election);
+
+    let (candidate_key, candidate_sec) = crypto::gen_keypair();
+    let (elector_key, elector_sec) = crypto::gen_keypair();
+    let block = testkit.create_block_with_transactions(txvec![
+        CreateCandidate::new(&election, &candidate_key, "John", &candidate_sec),
+        CreateElector::new(&election, &elector_key, "Den", &elector_sec),
+    ]);
+
+    for tx in &block.transactions {
+        let tx_status = tx.status().err().expect("Expect error.");
+        assert_eq!(tx_status.description(), Some("Registration is closed"));
+    }
+    assert!(try_get_candidate(&testkit, &election, &candidate_key).is_none());
+    assert!(try_get_elector(&testkit, &election, &elector_key).is_none());
+}
+
+#[test]
+fn test_vote_outside_of_voting_phase() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (den, sec) = create_elector(&mut testkit, &election, "Den");
+
+    let early = Vote::new(&election, den.pub_key(), john.pub_key(), 0, &sec);
+    let block = testkit.create_block_with_transaction(early);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Voting has not started yet"));
+
+    testkit.create_blocks_until(Height(TALLYING_HEIGHT - 1));
+    let late = Vote::new(&election, den.pub_key(), john.pub_key(), 1, &sec);
+    let block = testkit.create_block_with_transaction(late);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Voting is over"));
+
+    assert!(get_elector(&testkit, &election, den.pub_key()).has_vote());
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 0);
+}
+
 #[test]
 fn test_vote() {
     let mut testkit = init_testkit();
-    let (john, _) = create_candidate(&mut testkit, "John");
-    let (den, sec) = create_elector(&mut testkit, "Den");
-    create_vote_tx(&mut testkit, john.pub_key(), den.pub_key(), &sec);
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (den, sec) = create_elector(&mut testkit, &election, "Den");
+    start_voting(&mut testkit, &election);
+    create_vote_tx(&mut testkit, &election, john.pub_key(), den.pub_key(), &sec);
 
-    let elector = get_elector(&testkit, den.pub_key());
+    let elector = get_elector(&testkit, &election, den.pub_key());
     assert_eq!(elector.has_vote(), false);
 
-    let candidate = get_candidate(&testkit, john.pub_key());
+    let candidate = get_candidate(&testkit, &election, john.pub_key());
     assert_eq!(candidate.voices(), 1);
 }
 
 #[test]
 fn double_vote_test() {
     let mut testkit = init_testkit();
-    let (john, _) = create_candidate(&mut testkit, "John");
-    let (john_1, _) = create_candidate(&mut testkit, "John_1");
-    let (den, sec) = create_elector(&mut testkit, "Den");
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+    let (den, sec) = create_elector(&mut testkit, &election, "Den");
+    start_voting(&mut testkit, &election);
 
-    create_vote_tx(&mut testkit, john.pub_key(), den.pub_key(), &sec);
-    create_vote_tx(&mut testkit, john_1.pub_key(), den.pub_key(), &sec);
+    create_vote_tx(&mut testkit, &election, john.pub_key(), den.pub_key(), &sec);
+    create_vote_tx(&mut testkit, &election, john_1.pub_key(), den.pub_key(), &sec);
 
-    let elector = get_elector(&testkit, den.pub_key());
+    let elector = get_elector(&testkit, &election, den.pub_key());
     assert_eq!(elector.has_vote(), false);
 
-    let john = get_candidate(&testkit, john.pub_key());
+    let john = get_candidate(&testkit, &election, john.pub_key());
     assert_eq!(john.voices(), 1);
 
-    let john_1 = get_candidate(&testkit, john_1.pub_key());
+    let john_1 = get_candidate(&testkit, &election, john_1.pub_key());
     assert_eq!(john_1.voices(), 0);
 }
 
-fn create_vote_tx(testkit: &mut TestKit, candidate_pub_key: &PublicKey, elector_key: &PublicKey, elector_seq_key: &SecretKey) -> Vote {
-   let tx = Vote::new(elector_key, candidate_pub_key, elector_seq_key);
+#[test]
+fn test_change_vote() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+    let (den, sec) = create_elector(&mut testkit, &election, "Den");
+    start_voting(&mut testkit, &election);
+    create_vote_tx(&mut testkit, &election, john.pub_key(), den.pub_key(), &sec);
+
+    let tx = ChangeVote::new(&election, den.pub_key(), john_1.pub_key(), 0, &sec);
+    testkit.create_block_with_transaction(tx.clone());
+
+    let elector = get_elector(&testkit, &election, den.pub_key());
+    assert!(!elector.has_vote());
+    assert_eq!(elector.candidate(), john_1.pub_key());
+    assert_eq!(elector.ballot(), &tx.hash());
+
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 0);
+    assert_eq!(get_candidate(&testkit, &election, john_1.pub_key()).voices(), 1);
+
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    assert_eq!(schema.vote_history(&election, john.pub_key()).last(), Some(tx.hash()));
+    assert_eq!(schema.vote_history(&election, john_1.pub_key()).last(), Some(tx.hash()));
+}
+
+#[test]
+fn test_change_vote_rejected() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (den, sec) = create_elector(&mut testkit, &election, "Den");
+    start_voting(&mut testkit, &election);
+
+    let block = testkit.create_block_with_transactions(txvec![
+        ChangeVote::new(&election, den.pub_key(), john.pub_key(), 0, &sec),
+        RevokeVote::new(&election, den.pub_key(), 0, &sec),
+    ]);
+    for tx in &block.transactions {
+        let tx_status = tx.status().err().expect("Expect error.");
+        assert_eq!(tx_status.description(), Some("The voter has not voted yet."));
+    }
+
+    create_vote_tx(&mut testkit, &election, john.pub_key(), den.pub_key(), &sec);
+    let tx = ChangeVote::new(&election, den.pub_key(), john.pub_key(), 1, &sec);
+    let block = testkit.create_block_with_transaction(tx);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The voter already backs this candidate."));
+
+    testkit.create_blocks_until(Height(TALLYING_HEIGHT - 1));
+    let tx = RevokeVote::new(&election, den.pub_key(), 1, &sec);
+    let block = testkit.create_block_with_transaction(tx);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Voting is over"));
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 1);
+}
+
+#[test]
+fn test_revoke_vote() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+    let (den, sec) = create_elector(&mut testkit, &election, "Den");
+    start_voting(&mut testkit, &election);
+    create_vote_tx(&mut testkit, &election, john.pub_key(), den.pub_key(), &sec);
+
+    testkit.create_block_with_transaction(RevokeVote::new(&election, den.pub_key(), 0, &sec));
+
+    let elector = get_elector(&testkit, &election, den.pub_key());
+    assert!(elector.has_vote());
+    assert_eq!(elector.candidate(), &PublicKey::zero());
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 0);
+    assert!(VoteSchema::new(&testkit.snapshot()).vote_record(&election, den.pub_key()).is_none());
+    let counted = VoteSchema::new(&testkit.snapshot()).election(&election).unwrap();
+    assert_eq!((counted.turnout(), counted.total_weight()), (0, 0));
+
+    // The elector may vote again after the revocation.
+    let tx = Vote::new(&election, den.pub_key(), john_1.pub_key(), 1, &sec);
+    testkit.create_block_with_transaction(tx.clone());
+    assert_eq!(get_candidate(&testkit, &election, john_1.pub_key()).voices(), 1);
+    let record = VoteSchema::new(&testkit.snapshot()).vote_record(&election, den.pub_key()).unwrap();
+    assert_eq!(record.tx_hash(), &tx.hash());
+    assert_eq!(record.height(), VOTING_HEIGHT + 2);
+}
+
+#[test]
+fn test_ranked_vote() {
+    let mut testkit = init_testkit_with_config(VoteConfig { voting_scheme: VotingScheme::RankedChoice, ..VoteConfig::default() });
+    let election = create_election_with_seats(&mut testkit, "Board", 1, 2);
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+    let (den, sec) = create_elector(&mut testkit, &election, "Den");
+    start_voting(&mut testkit, &election);
+
+    let ranking = keys_to_hashes(&[*john_1.pub_key(), *john.pub_key()]);
+    let tx = RankedVote::new(&election, den.pub_key(), &ranking, 0, &sec);
+    testkit.create_block_with_transaction(tx.clone());
+
+    let elector = get_elector(&testkit, &election, den.pub_key());
+    assert!(!elector.has_vote());
+    assert_eq!(elector.candidate(), john_1.pub_key());
+    assert_eq!(elector.ballot(), &tx.hash());
+    assert_eq!(get_candidate(&testkit, &election, john_1.pub_key()).voices(), 1);
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 0);
+
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    let ballot = schema.ballot(&election, den.pub_key()).expect("No ballot persisted.");
+    assert_eq!(ballot.choices(), vec![*john_1.pub_key(), *john.pub_key()]);
+    let election = schema.election(&election).unwrap();
+    assert_eq!(election.ballots_hash(), &schema.ballots(election.id()).merkle_root());
+}
+
+#[test]
+fn test_ranked_vote_rejected() {
+    let mut testkit = init_testkit_with_config(VoteConfig { voting_scheme: VotingScheme::RankedChoice, ..VoteConfig::default() });
+    let election = create_election_with_seats(&mut testkit, "Board", 1, 2);
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (den, sec) = create_elector(&mut testkit, &election, "Den");
+    start_voting(&mut testkit, &election);
+
+    let (stranger, _) = crypto::gen_keypair();
+    let block = testkit.create_block_with_transactions(txvec![
+        RankedVote::new(&election, den.pub_key(), &[], 0, &sec),
+        RankedVote::new(&election, den.pub_key(), &keys_to_hashes(&[*john.pub_key(), *john.pub_key()]), 0, &sec),
+        RankedVote::new(&election, den.pub_key(), &keys_to_hashes(&[*john.pub_key(), stranger]), 0, &sec),
+        Vote::new(&election, den.pub_key(), john.pub_key(), 0, &sec),
+    ]);
+
+    let descriptions: Vec<_> = block.transactions.iter()
+        .map(|tx| tx.status().err().expect("Expect error.").description().map(str::to_owned))
+        .collect();
+    assert_eq!(descriptions, vec![
+        Some("Invalid ballot".to_owned()),
+        Some("Invalid ballot".to_owned()),
+        Some("Candidate doesn't exist".to_owned()),
+        Some("The transaction doesn't match the voting scheme of the election".to_owned()),
+    ]);
+    assert!(get_elector(&testkit, &election, den.pub_key()).has_vote());
+}
+
+#[test]
+fn test_approval_vote() {
+    let mut testkit = init_testkit_with_config(VoteConfig { voting_scheme: VotingScheme::Approval, ..VoteConfig::default() });
+    let election = create_election_with_seats(&mut testkit, "Board", 2, 2);
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+    let (john_2, _) = create_candidate(&mut testkit, &election, "John_2");
+    let (den, sec) = create_elector(&mut testkit, &election, "Den");
+    start_voting(&mut testkit, &election);
+
+    let approved = keys_to_hashes(&[*john.pub_key(), *john_2.pub_key()]);
+    let too_many = keys_to_hashes(&[*john.pub_key(), *john_1.pub_key(), *john_2.pub_key()]);
+    let block = testkit.create_block_with_transactions(txvec![
+        ApprovalVote::new(&election, den.pub_key(), &too_many, 0, &sec),
+        ApprovalVote::new(&election, den.pub_key(), &approved, 0, &sec),
+    ]);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Too many choices in the ballot"));
+    assert!(block.transactions[1].status().is_ok());
+
+    assert!(!get_elector(&testkit, &election, den.pub_key()).has_vote());
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 1);
+    assert_eq!(get_candidate(&testkit, &election, john_1.pub_key()).voices(), 0);
+    assert_eq!(get_candidate(&testkit, &election, john_2.pub_key()).voices(), 1);
+
+    testkit.create_block_with_transaction(RevokeVote::new(&election, den.pub_key(), 0, &sec));
+    assert!(get_elector(&testkit, &election, den.pub_key()).has_vote());
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 0);
+    assert_eq!(get_candidate(&testkit, &election, john_2.pub_key()).voices(), 0);
+    assert!(VoteSchema::new(&testkit.snapshot()).ballot(&election, den.pub_key()).is_none());
+}
+
+#[test]
+fn test_weighted_vote() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let mut testkit = init_testkit_with_registrar(&registrar);
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+
+    let (den, den_sec) = crypto::gen_keypair();
+    let (ann, ann_sec) = crypto::gen_keypair();
+    let (bob, _) = crypto::gen_keypair();
+    let block = testkit.create_block_with_transactions(txvec![
+        RegisterElector::new(&election, &registrar, &den, "Den", 3, &registrar_sec),
+        RegisterElector::new(&election, &registrar, &ann, "Ann", 5, &registrar_sec),
+        RegisterElector::new(&election, &registrar, &bob, "Bob", 0, &registrar_sec),
+    ]);
+    let tx_status = block.transactions[2].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Elector weight must be positive"));
+    assert_eq!(get_elector(&testkit, &election, &ann).weight(), 5);
+
+    start_voting(&mut testkit, &election);
+    create_vote_tx(&mut testkit, &election, john.pub_key(), &den, &den_sec);
+    create_vote_tx(&mut testkit, &election, john.pub_key(), &ann, &ann_sec);
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 8);
+
+    testkit.create_block_with_transaction(ChangeVote::new(&election, &ann, john_1.pub_key(), 0, &ann_sec));
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 3);
+    assert_eq!(get_candidate(&testkit, &election, john_1.pub_key()).voices(), 5);
+}
+
+#[test]
+fn test_delegate() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+    let (ann, ann_sec) = create_elector(&mut testkit, &election, "Ann");
+    let (bob, bob_sec) = create_elector(&mut testkit, &election, "Bob");
+    let (den, den_sec) = create_elector(&mut testkit, &election, "Den");
+    let (stranger, _) = crypto::gen_keypair();
+
+    let block = testkit.create_block_with_transactions(txvec![
+        Delegate::new(&election, ann.pub_key(), bob.pub_key(), 0, &ann_sec),
+        Delegate::new(&election, bob.pub_key(), den.pub_key(), 0, &bob_sec),
+        Delegate::new(&election, den.pub_key(), ann.pub_key(), 0, &den_sec),
+        Delegate::new(&election, den.pub_key(), den.pub_key(), 1, &den_sec),
+        Delegate::new(&election, den.pub_key(), &stranger, 2, &den_sec),
+    ]);
+
+    assert!(block.transactions[0].status().is_ok());
+    assert!(block.transactions[1].status().is_ok());
+    let descriptions: Vec<_> = block.transactions[2..].iter()
+        .map(|tx| tx.status().err().expect("Expect error.").description().map(str::to_owned))
+        .collect();
+    assert_eq!(descriptions, vec![
+        Some("Delegation would create a cycle".to_owned()),
+        Some("Elector can't delegate to itself".to_owned()),
+        Some("Elector doesn't exist".to_owned()),
+    ]);
+
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    assert_eq!(schema.delegation(&election, ann.pub_key()).unwrap().to(), bob.pub_key());
+    assert!(schema.delegation(&election, den.pub_key()).is_none());
+    let election = schema.election(&election).unwrap();
+    assert_eq!(election.delegations_hash(), &schema.delegations(election.id()).merkle_root());
+}
+
+#[test]
+fn test_commit_reveal() {
+    let mut testkit = init_testkit_with_config(VoteConfig { voting_scheme: VotingScheme::CommitReveal, ..VoteConfig::default() });
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+    let (den, sec) = create_elector(&mut testkit, &election, "Den");
+    let (ann, ann_sec) = create_elector(&mut testkit, &election, "Ann");
+    start_voting(&mut testkit, &election);
+
+    let salt = crypto::hash(b"salt");
+    let commitment = vote_commitment(&election, den.pub_key(), john.pub_key(), &salt);
+    let commit = CommitVote::new(&election, den.pub_key(), &commitment, 0, &sec);
+    let block = testkit.create_block_with_transactions(txvec![
+        Vote::new(&election, den.pub_key(), john.pub_key(), 0, &sec),
+        commit.clone(),
+        RevealVote::new(&election, den.pub_key(), john.pub_key(), &salt, 0, &sec),
+    ]);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The transaction doesn't match the voting scheme of the election"));
+    assert!(block.transactions[1].status().is_ok());
+    let tx_status = block.transactions[2].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Reveal phase has not started yet"));
+    // The commitment seen on chain is copied by another elector.
+    let copied = CommitVote::new(&election, ann.pub_key(), &commitment, 0, &ann_sec);
+    testkit.create_block_with_transaction(copied.clone());
+
+    // The vote is hidden until the reveal.
+    let elector = get_elector(&testkit, &election, den.pub_key());
+    assert!(!elector.has_vote());
+    assert_eq!(elector.ballot(), &commit.hash());
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 0);
+    assert_eq!(VoteSchema::new(&testkit.snapshot()).election(&election).unwrap().unrevealed(), 2);
+
+    testkit.create_blocks_until(Height(TALLYING_HEIGHT - 1));
+    let reveal = RevealVote::new(&election, den.pub_key(), john.pub_key(), &salt, 1, &sec);
+    let block = testkit.create_block_with_transactions(txvec![
+        RevealVote::new(&election, den.pub_key(), john_1.pub_key(), &salt, 1, &sec),
+        reveal.clone(),
+        RevealVote::new(&election, den.pub_key(), john.pub_key(), &salt, 2, &sec),
+    ]);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The reveal doesn't match the commitment"));
+    assert!(block.transactions[1].status().is_ok());
+    let tx_status = block.transactions[2].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The vote is already revealed"));
+    let block = testkit.create_block_with_transaction(RevealVote::new(&election, ann.pub_key(), john.pub_key(), &salt, 0, &ann_sec));
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The reveal doesn't match the commitment"));
+
+    assert_eq!(get_elector(&testkit, &election, den.pub_key()).candidate(), john.pub_key());
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 1);
+    assert_eq!(get_candidate(&testkit, &election, john_1.pub_key()).voices(), 0);
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    assert!(schema.commitment(&election, den.pub_key()).unwrap().revealed());
+    assert_eq!(schema.election(&election).unwrap().unrevealed(), 1);
+    // Both the commitments and the reveal are ballots, the rejected transactions aren't logged.
+    assert_eq!(schema.ballot_log().iter().collect::<Vec<_>>(), vec![commit.hash(), copied.hash(), reveal.hash()]);
+}
+
+#[test]
+fn test_reveal_for_withdrawn_candidate() {
+    for &withdrawn_votes in &[WithdrawnVotes::Void, WithdrawnVotes::Return] {
+        let mut testkit = init_testkit_with_config(VoteConfig {
+            voting_scheme: VotingScheme::CommitReveal,
+            withdrawn_votes,
+            ..VoteConfig::default()
+        });
+        let election = create_election(&mut testkit, "Board");
+        let (john, john_sec) = create_candidate(&mut testkit, &election, "John");
+        let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+        let (den, den_sec) = create_elector(&mut testkit, &election, "Den");
+        let (ann, ann_sec) = create_elector(&mut testkit, &election, "Ann");
+        start_voting(&mut testkit, &election);
+
+        let salt = crypto::hash(b"salt");
+        testkit.create_block_with_transactions(txvec![
+            CommitVote::new(&election, den.pub_key(), &vote_commitment(&election, den.pub_key(), john.pub_key(), &salt), 0, &den_sec),
+            CommitVote::new(&election, ann.pub_key(), &vote_commitment(&election, ann.pub_key(), john_1.pub_key(), &salt), 0, &ann_sec),
+        ]);
+        testkit.create_block_with_transaction(WithdrawCandidate::new(&election, john.pub_key(), 0, &john_sec));
+
+        testkit.create_blocks_until(Height(TALLYING_HEIGHT - 1));
+        let block = testkit.create_block_with_transactions(txvec![
+            RevealVote::new(&election, den.pub_key(), john.pub_key(), &salt, 0, &den_sec),
+            RevealVote::new(&election, ann.pub_key(), john_1.pub_key(), &salt, 0, &ann_sec),
+        ]);
+        assert!(block.transactions.iter().all(|tx| tx.status().is_ok()));
+
+        let snapshot = testkit.snapshot();
+        let schema = VoteSchema::new(&snapshot);
+        let record = schema.election(&election).unwrap();
+        assert_eq!((record.unrevealed(), record.turnout()), (0, 1));
+        assert_eq!(get_candidate(&testkit, &election, john_1.pub_key()).voices(), 1);
+        let elector = get_elector(&testkit, &election, den.pub_key());
+        if withdrawn_votes == WithdrawnVotes::Void {
+            assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 1);
+            assert!(schema.commitment(&election, den.pub_key()).unwrap().revealed());
+            assert!(!elector.has_vote());
+        } else {
+            assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 0);
+            assert!(schema.commitment(&election, den.pub_key()).is_none());
+            assert!(elector.has_vote());
+        }
+    }
+}
+
+#[test]
+fn test_encryption_key_setup() {
+    let mut testkit = init_testkit_with_validators(VotingScheme::Encrypted, 4);
+    let election = create_election(&mut testkit, "Board");
+    create_candidate(&mut testkit, &election, "John");
+    let (den, den_sec) = create_elector(&mut testkit, &election, "Den");
+
+    // Any three of the four trustees decrypt the votes.
+    let record = VoteSchema::new(testkit.snapshot()).election(&election).unwrap();
+    assert_eq!(record.trustees().len(), 4);
+    assert_eq!(record.threshold(), 3);
+
+    let validators: Vec<_> = (0..4).map(|i| service_keypair(&testkit, i)).collect();
+    let (outsider, outsider_sec) = crypto::gen_keypair();
+    // The proof is made for another trustee.
+    let stolen = {
+        let dealing = dealing(&testkit, &election, &validators[0].0, &validators[0].1);
+        dealing_tx(&election, &validators[1].0, &validators[1].1, &dealing, 0)
+    };
+    let block = testkit.create_block_with_transactions(txvec![
+        key_share_tx(&testkit, &election, &outsider, &outsider_sec, 0),
+        stolen,
+        key_share_tx(&testkit, &election, &validators[0].0, &validators[0].1, 0),
+        key_share_tx(&testkit, &election, &validators[0].0, &validators[0].1, 1),
+        key_share_tx(&testkit, &election, &validators[1].0, &validators[1].1, 0),
+    ]);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The signer is not a trustee of the election"));
+    let tx_status = block.transactions[1].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Invalid zero-knowledge proof"));
+    assert!(block.transactions[2].status().is_ok());
+    let tx_status = block.transactions[3].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The key share is already submitted"));
+    assert!(block.transactions[4].status().is_ok());
+
+    // Only two trustees have dealt in time, the dealings are closed before the voting.
+    testkit.create_blocks_until(Height(VOTING_HEIGHT - 2));
+    let key = VoteSchema::new(&testkit.snapshot()).encryption_key(&election);
+    let block = testkit.create_block_with_transaction(key_share_tx(&testkit, &election, &validators[2].0, &validators[2].1, 0));
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Registration is closed"));
+
+    start_voting(&mut testkit, &election);
+    let block = testkit.create_block_with_transaction(encrypted_vote_tx(&testkit, &key, &election, &den, &den_sec, &[]));
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Not enough trustees have dealt the election key"));
+}
+
+#[test]
+fn test_key_share_complaint() {
+    let mut testkit = init_testkit_with_validators(VotingScheme::Encrypted, 4);
+    let election = create_election(&mut testkit, "Board");
+    let validators: Vec<_> = (0..4).map(|i| service_keypair(&testkit, i)).collect();
+
+    // The first dealer gives the second trustee a share which doesn't match the commitments.
+    let mut corrupted = dealing(&testkit, &election, &validators[0].0, &validators[0].1);
+    let index = VoteSchema::new(testkit.snapshot()).election(&election).unwrap().trustee_index(&validators[1].0).unwrap();
+    corrupted.shares[index as usize - 1] += Scalar::one();
+    testkit.create_block_with_transactions(txvec![
+        dealing_tx(&election, &validators[0].0, &validators[0].1, &corrupted, 0),
+        key_share_tx(&testkit, &election, &validators[1].0, &validators[1].1, 0),
+        key_share_tx(&testkit, &election, &validators[2].0, &validators[2].1, 0),
+        key_share_tx(&testkit, &election, &validators[3].0, &validators[3].1, 0),
+    ]);
+
+    // The proof is made for the Diffie-Hellman key of the trustee, but another key is revealed.
+    let forged = {
+        let honest = complaint_tx(&testkit, &election, &validators[1], &validators[0].0, 1);
+        let shared_key = Hash::new(dkg::shared_key(&validators[2].1, &corrupted.ephemeral).compress().to_bytes());
+        let (trustee, trustee_sec) = &validators[1];
+        ComplainKeyShare::new(&election, trustee, &validators[0].0, &shared_key, honest.challenge(), honest.response(), 1, trustee_sec)
+    };
+    let block = testkit.create_block_with_transactions(txvec![
+        forged,
+        complaint_tx(&testkit, &election, &validators[2], &validators[1].0, 0),
+        complaint_tx(&testkit, &election, &validators[1], &validators[0].0, 0),
+        complaint_tx(&testkit, &election, &validators[3], &validators[0].0, 0),
+    ]);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Invalid zero-knowledge proof"));
+    let tx_status = block.transactions[1].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The complained key share is valid"));
+    assert!(block.transactions[2].status().is_ok());
+    let tx_status = block.transactions[3].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The dealer is disqualified"));
+
+    // The election key is shared by the three qualified dealers.
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    assert!(schema.key_share(&election, &validators[0].0).unwrap().disqualified());
+    let qualified: Vec<PublicKey> = schema.qualified_dealings(&election).iter().map(|dealing| *dealing.validator()).collect();
+    assert_eq!(qualified.len(), 3);
+    assert!(!qualified.contains(&validators[0].0));
+}
+
+#[test]
+fn test_encrypted_tally() {
+    let mut testkit = init_testkit_with_validators(VotingScheme::Encrypted, 4);
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+    let (den, den_sec) = create_elector(&mut testkit, &election, "Den");
+    let (den_1, den_1_sec) = create_elector(&mut testkit, &election, "Den_1");
+    let (den_2, den_2_sec) = create_elector(&mut testkit, &election, "Den_2");
+    let validators: Vec<_> = (0..4).map(|i| service_keypair(&testkit, i)).collect();
+    let dealings: Vec<Box<dyn Transaction>> = validators.iter()
+        .map(|(pub_key, sec)| Box::new(key_share_tx(&testkit, &election, pub_key, sec, 0)) as Box<dyn Transaction>)
+        .collect();
+    testkit.create_block_with_transactions(dealings);
+
+    start_voting(&mut testkit, &election);
+    let key = VoteSchema::new(&testkit.snapshot()).encryption_key(&election);
+    // The ballot chooses more candidates than allowed.
+    let both = {
+        let candidates = sorted_candidates(&testkit, &election);
+        let (choices, proof) = elgamal::encrypt_ballot(&key, &election, den_2.pub_key(), &candidates, &candidates, 2);
+        let (challenges, responses) = proof.to_hashes();
+        EncryptedVote::new(&election, den_2.pub_key(), choices, &challenges, &responses, 0, &den_2_sec)
+    };
+    let block = testkit.create_block_with_transactions(txvec![
+        encrypted_vote_tx(&testkit, &key, &election, &den, &den_sec, &[*john.pub_key()]),
+        encrypted_vote_tx(&testkit, &key, &election, &den_1, &den_1_sec, &[*john.pub_key()]),
+        encrypted_vote_tx(&testkit, &key, &election, &den_1, &den_1_sec, &[*john_1.pub_key()]),
+        both,
+    ]);
+    assert!(block.transactions[0].status().is_ok());
+    assert!(block.transactions[1].status().is_ok());
+    let tx_status = block.transactions[2].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The voter has already voted."));
+    let tx_status = block.transactions[3].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Invalid zero-knowledge proof"));
+
+    // The ballots are not revealed.
+    assert!(!get_elector(&testkit, &election, den.pub_key()).has_vote());
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 0);
+
+    let block = testkit.create_block_with_transaction(decryption_tx(&testkit, &election, &validators[0], 0));
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Decryption starts after the election is closed"));
+
+    // The second trustee is offline, the other three decrypt the votes.
+    testkit.create_blocks_until(Height(CLOSING_HEIGHT - 1));
+    let (outsider, outsider_sec) = crypto::gen_keypair();
+    let block = testkit.create_block_with_transactions(txvec![
+        SubmitDecryption::new(&election, &outsider, Vec::new(), 0, &outsider_sec),
+        decryption_tx(&testkit, &election, &validators[0], 1),
+        decryption_tx(&testkit, &election, &validators[0], 2),
+        decryption_tx(&testkit, &election, &validators[2], 1),
+    ]);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The signer is not a trustee of the election"));
+    assert!(block.transactions[1].status().is_ok());
+    let tx_status = block.transactions[2].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The decryption shares are already submitted"));
+    assert!(block.transactions[3].status().is_ok());
+    assert!(!VoteSchema::new(testkit.snapshot()).election(&election).unwrap().decrypted());
+
+    let block = testkit.create_block_with_transaction(decryption_tx(&testkit, &election, &validators[3], 1));
+    assert!(block.transactions[0].status().is_ok());
+    assert!(VoteSchema::new(testkit.snapshot()).election(&election).unwrap().decrypted());
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 2);
+    assert_eq!(get_candidate(&testkit, &election, john_1.pub_key()).voices(), 0);
+
+    let block = testkit.create_block_with_transaction(decryption_tx(&testkit, &election, &validators[1], 1));
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The votes are already decrypted"));
+
+    // Any three decryption shares give the same sums.
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    let record = schema.election(&election).unwrap();
+    let baby_steps = elgamal::BabySteps::new(3);
+    for (candidate, expected) in &[(john.pub_key(), 2), (john_1.pub_key(), 0)] {
+        let shares: Vec<(u64, RistrettoPoint)> = schema.decryptions(&election).values()
+            .map(|decryption| {
+                let share = decryption.shares().into_iter().find(|share| share.candidate() == *candidate).unwrap();
+                (record.trustee_index(decryption.validator()).unwrap(), elgamal::hash_to_point(share.share()).unwrap())
+            })
+            .collect();
+        let votes = schema.candidate(&election, candidate).unwrap().encrypted_votes();
+        assert_eq!(elgamal::decrypt(&votes, &dkg::combine(&shares), &baby_steps), Some(*expected));
+    }
+}
+
+#[test]
+fn test_anonymous_vote() {
+    let mut testkit = init_testkit_with_config(VoteConfig { voting_scheme: VotingScheme::Anonymous, ..VoteConfig::default() });
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+    let (den, den_sec) = create_elector(&mut testkit, &election, "Den");
+    let (_, den_1_sec) = create_elector(&mut testkit, &election, "Den_1");
+    let (outsider, outsider_sec) = crypto::gen_keypair();
+    start_voting(&mut testkit, &election);
+
+    let ring = sorted_electorate(&testkit, &election);
+    let forged = {
+        let (ballot_key, ballot_sec) = crypto::gen_keypair();
+        let message = anonymous_ballot(&election, john.pub_key(), &ballot_key);
+        let mut ring = ring.clone();
+        ring.push(outsider);
+        let signature = RingSignature::sign(&message, &election, &ring, &outsider_sec).unwrap();
+        let (key_image, challenge, mut responses) = signature.to_hashes();
+        responses.pop();
+        AnonymousVote::new(&election, &ballot_key, john.pub_key(), 0, &key_image, &challenge, &responses, 0, &ballot_sec)
+    };
+    let ballot = anonymous_vote_tx(&election, &ring, 0, john.pub_key(), &den_sec);
+    let block = testkit.create_block_with_transactions(txvec![
+        Vote::new(&election, den.pub_key(), john.pub_key(), 0, &den_sec),
+        forged,
+        ballot.clone(),
+        anonymous_vote_tx(&election, &ring, 0, john_1.pub_key(), &den_sec),
+        anonymous_vote_tx(&election, &ring, 0, john_1.pub_key(), &den_1_sec),
+    ]);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The transaction doesn't match the voting scheme of the election"));
+    let tx_status = block.transactions[1].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Invalid ring signature"));
+    assert!(block.transactions[2].status().is_ok());
+    // The second ballot of the elector has the same key image.
+    let tx_status = block.transactions[3].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The voter has already voted."));
+    assert!(block.transactions[4].status().is_ok());
+
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 1);
+    assert_eq!(get_candidate(&testkit, &election, john_1.pub_key()).voices(), 1);
+    // The ballots are not linked to the electors.
+    assert!(get_elector(&testkit, &election, den.pub_key()).has_vote());
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    assert_eq!(schema.key_images(&election).get(&lsag::key_image(&election, &den_sec)), Some(ballot.hash()));
+
+    // The new key would sign another ballot with another key image.
+    let (new_den, new_den_sec) = crypto::gen_keypair();
+    let tx = rotate_key_tx(&election, (den.pub_key(), &den_sec), (&new_den, &new_den_sec), &PublicKey::zero());
+    let block = testkit.create_block_with_transaction(tx);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The keys can't be rotated during the voting of an anonymous or a token election"));
+    assert!(try_get_elector(&testkit, &election, &new_den).is_none());
+}
+
+#[test]
+fn test_anonymous_sub_rings() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let mut testkit = init_testkit_with_config(VoteConfig {
+        voting_scheme: VotingScheme::Anonymous,
+        registrars: vec![registrar],
+        ring_size: 2,
+        ..VoteConfig::default()
+    });
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let electors: Vec<(PublicKey, SecretKey)> = (0..5).map(|_| crypto::gen_keypair()).collect();
+    let entries = electors.iter().map(|(elector, _)| ElectorEntry::new(elector, "Den", 1)).collect();
+    testkit.create_block_with_transaction(RegisterElectorsBatch::new(&election, &registrar, entries, 0, &registrar_sec));
+    start_voting(&mut testkit, &election);
+
+    // Five electors form the sub-rings of two and three electors in the order of the keys.
+    let ring = sorted_electorate(&testkit, &election);
+    let secret = |key: &PublicKey| electors.iter().find(|(elector, _)| elector == key).unwrap().1.clone();
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    assert!(schema.ring(&election).is_empty());
+    assert_eq!(schema.sub_ring(&election, 0), Some(ring[..2].to_vec()));
+    assert_eq!(schema.sub_ring(&election, 1), Some(ring[2..].to_vec()));
+    assert_eq!(schema.sub_ring(&election, 2), None);
+
+    let block = testkit.create_block_with_transactions(txvec![
+        anonymous_vote_tx(&election, &ring[2..], 0, john.pub_key(), &secret(&ring[2])),
+        anonymous_vote_tx(&election, &ring[2..], 1, john.pub_key(), &secret(&ring[2])),
+        anonymous_vote_tx(&election, &ring[..2], 0, john.pub_key(), &secret(&ring[0])),
+        // The key image is the same in any sub-ring.
+        anonymous_vote_tx(&election, &ring[2..], 1, john.pub_key(), &secret(&ring[2])),
+        RevokeElector::new(&election, &registrar, &ring[4], RevocationReason::Left as u8, 0, &registrar_sec),
+    ]);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Invalid ring signature"));
+    assert!(block.transactions[1].status().is_ok());
+    assert!(block.transactions[2].status().is_ok());
+    let tx_status = block.transactions[3].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The voter has already voted."));
+    let tx_status = block.transactions[4].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The ring of the anonymous election is frozen for the voting"));
+
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    let election = schema.election(&election).unwrap();
+    assert_eq!(schema.ring(election.id()).iter().collect::<Vec<_>>(), ring);
+    assert_eq!(election.ring_hash(), &schema.ring(election.id()).merkle_root());
+    assert_eq!(get_candidate(&testkit, election.id(), john.pub_key()).voices(), 2);
+}
+
+#[test]
+fn test_token_vote() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let mut testkit = init_testkit_with_config(VoteConfig {
+        voting_scheme: VotingScheme::Token,
+        registrars: vec![registrar],
+        ..VoteConfig::default()
+    });
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (den, _) = crypto::gen_keypair();
+    let (den_1, _) = crypto::gen_keypair();
+    let (outsider, outsider_sec) = crypto::gen_keypair();
+    testkit.create_block_with_transactions(txvec![
+        RegisterElector::new(&election, &registrar, &den, "Den", 1, &registrar_sec),
+        RegisterElector::new(&election, &registrar, &den_1, "Den_1", 1, &registrar_sec),
+    ]);
+
+    let (commit, session) = commit_token_tx(&election, &registrar, &registrar_sec, &den, 0);
+    let own_key = CommitToken::new(&election, &registrar, &den_1, &registrar, &Session::random().commitments(), 0, &registrar_sec);
+    let block = testkit.create_block_with_transactions(txvec![
+        commit_token_tx(&election, &outsider, &outsider_sec, &den, 0).0,
+        commit_token_tx(&election, &registrar, &registrar_sec, &outsider, 0).0,
+        commit.clone(),
+        own_key,
+    ]);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The signer is not a registrar"));
+    let tx_status = block.transactions[1].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Elector doesn't exist"));
+    assert!(block.transactions[2].status().is_ok());
+    // The service key of the registrar never signs blindly.
+    let tx_status = block.transactions[3].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Invalid issuing key"));
+
+    let (issue, blinding, (token, token_sec)) = issue_token_tx(&election, &registrar, &registrar_sec, &commit, session, 0);
+    // The nonces for the other elector are not committed.
+    let (uncommitted, uncommitted_session) = commit_token_tx(&election, &registrar, &registrar_sec, &den_1, 0);
+    let block = testkit.create_block_with_transactions(txvec![
+        issue_token_tx(&election, &registrar, &registrar_sec, &uncommitted, uncommitted_session, 0).0,
+        issue.clone(),
+    ]);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The token nonces are not committed for the elector"));
+    assert!(block.transactions[1].status().is_ok());
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    assert_eq!(schema.tokens(&election).get(&den), Some(issue.hash()));
+    assert_eq!(schema.token_session(&election, &den).unwrap().tx_hash(), &commit.hash());
+    assert_eq!(schema.issuing_keys(&election).get(&registrar), Some(*commit.issuing_key()));
+
+    // The issued token can't be traced back, so the elector can't be revoked anymore.
+    let block = testkit.create_block_with_transaction(
+        RevokeElector::new(&election, &registrar, &den, RevocationReason::Left as u8, 0, &registrar_sec),
+    );
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The token nonces are already committed for the elector"));
+
+    start_voting(&mut testkit, &election);
+    let (nonce, response) = blinding.unblind(issue.clause(), issue.response()).unwrap();
+    let (forged, forged_sec) = crypto::gen_keypair();
+    let ballot = VoteWithToken::new(&election, &registrar, &token, john.pub_key(), &nonce, &response, 0, &token_sec);
+    let block = testkit.create_block_with_transactions(txvec![
+        VoteWithToken::new(&election, &registrar, &forged, john.pub_key(), &nonce, &response, 0, &forged_sec),
+        ballot.clone(),
+        VoteWithToken::new(&election, &registrar, &token, john.pub_key(), &nonce, &response, 1, &token_sec),
+    ]);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Invalid token signature"));
+    assert!(block.transactions[1].status().is_ok());
+    let tx_status = block.transactions[2].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The token is already spent"));
+
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 1);
+    assert_eq!(VoteSchema::new(&testkit.snapshot()).spent_tokens(&election).get(&token), Some(ballot.hash()));
+}
+
+#[test]
+fn test_token_requested_twice() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let mut testkit = init_testkit_with_config(VoteConfig {
+        voting_scheme: VotingScheme::Token,
+        registrars: vec![registrar],
+        ..VoteConfig::default()
+    });
+    let election = create_election(&mut testkit, "Board");
+    let other_election = create_election(&mut testkit, "Lunch");
+    let (den, _) = crypto::gen_keypair();
+    testkit.create_block_with_transaction(RegisterElector::new(&election, &registrar, &den, "Den", 1, &registrar_sec));
+
+    // The issuing keys of the elections are independent of each other and of the service key.
+    let issuing_key = IssuingKey::new(&registrar_sec, &election).public_key();
+    assert_ne!(issuing_key, registrar);
+    assert_ne!(issuing_key, IssuingKey::new(&registrar_sec, &other_election).public_key());
+
+    // The elector asks for the nonces twice, every request gets the fresh ones.
+    let (commit, session) = commit_token_tx(&election, &registrar, &registrar_sec, &den, 0);
+    let (second_commit, second_session) = commit_token_tx(&election, &registrar, &registrar_sec, &den, 1);
+    assert_ne!(commit.commitments(), second_commit.commitments());
+    let block = testkit.create_block_with_transactions(txvec![commit.clone(), second_commit.clone()]);
+    assert!(block.transactions[0].status().is_ok());
+    let tx_status = block.transactions[1].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The token nonces are already committed for the elector"));
+
+    // The elector asks for the signature of two tokens, only the first response is accepted.
+    let (issue, blinding, (token, _)) = issue_token_tx(&election, &registrar, &registrar_sec, &commit, session, 0);
+    let (second_issue, ..) = issue_token_tx(&election, &registrar, &registrar_sec, &commit, second_session, 1);
+    let block = testkit.create_block_with_transactions(txvec![issue.clone(), second_issue]);
+    assert!(block.transactions[0].status().is_ok());
+    let tx_status = block.transactions[1].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The token is already issued to the elector"));
+
+    let (nonce, response) = blinding.unblind(issue.clause(), issue.response()).unwrap();
+    assert!(blind::verify_token(&issuing_key, &election, &token, &nonce, &response));
+    assert!(!blind::verify_token(&registrar, &election, &token, &nonce, &response));
+}
+
+#[test]
+fn separate_elections_test() {
+    let mut testkit = init_testkit();
+    let board = create_election(&mut testkit, "Board");
+    let lunch = create_election(&mut testkit, "Lunch");
+
+    let (john, john_sec) = create_candidate(&mut testkit, &board, "John");
+    let (den, den_sec) = create_elector(&mut testkit, &board, "Den");
+
+    // The same identities are independent participants of another election.
+    let john_lunch = CreateCandidate::new(&lunch, john.pub_key(), "John", &john_sec);
+    let den_lunch = CreateElector::new(&lunch, den.pub_key(), "Den", &den_sec);
+    testkit.create_block_with_transactions(txvec![john_lunch, den_lunch]);
+
+    start_voting(&mut testkit, &board);
+    create_vote_tx(&mut testkit, &board, john.pub_key(), den.pub_key(), &den_sec);
+
+    assert!(get_elector(&testkit, &lunch, den.pub_key()).has_vote());
+    assert_eq!(get_candidate(&testkit, &lunch, john.pub_key()).voices(), 0);
+
+    create_vote_tx(&mut testkit, &lunch, john.pub_key(), den.pub_key(), &den_sec);
+    assert_eq!(get_candidate(&testkit, &board, john.pub_key()).voices(), 1);
+    assert_eq!(get_candidate(&testkit, &lunch, john.pub_key()).voices(), 1);
+}
+
+#[test]
+fn test_withdraw_candidate() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+    let (john, john_sec) = create_candidate(&mut testkit, &election, "John");
+    let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+    let (den, den_sec) = create_elector(&mut testkit, &election, "Den");
+    let (ann, ann_sec) = create_elector(&mut testkit, &election, "Ann");
+    start_voting(&mut testkit, &election);
+    create_vote_tx(&mut testkit, &election, john.pub_key(), den.pub_key(), &den_sec);
+
+    let tx = WithdrawCandidate::new(&election, john.pub_key(), 0, &john_sec);
+    testkit.create_block_with_transaction(tx.clone());
+
+    // The votes are void by default, they stay with the withdrawn candidate and aren't counted.
+    let candidate = get_candidate(&testkit, &election, john.pub_key());
+    assert_eq!(candidate.candidate_status(), CandidateStatus::Withdrawn);
+    assert_eq!(candidate.voices(), 1);
+    assert!(!get_elector(&testkit, &election, den.pub_key()).has_vote());
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    let counted = schema.election(&election).unwrap();
+    assert_eq!((counted.turnout(), counted.total_weight()), (0, 0));
+    assert_eq!(schema.vote_history(&election, john.pub_key()).last(), Some(tx.hash()));
+    // The withdrawal is kept in the candidate history only, it isn't a ballot.
+    assert!(!schema.ballot_log().iter().any(|hash| hash == tx.hash()));
+    assert_eq!(schema.ballot_log().len(), 1);
+
+    let block = testkit.create_block_with_transactions(txvec![
+        Vote::new(&election, ann.pub_key(), john.pub_key(), 0, &ann_sec),
+        WithdrawCandidate::new(&election, john.pub_key(), 1, &john_sec),
+    ]);
+    for tx in &block.transactions {
+        let tx_status = tx.status().err().expect("Expect error.");
+        assert_eq!(tx_status.description(), Some("The candidate is withdrawn"));
+    }
+    assert!(get_elector(&testkit, &election, ann.pub_key()).has_vote());
+
+    // The void vote is counted again when it's changed.
+    testkit.create_block_with_transaction(ChangeVote::new(&election, den.pub_key(), john_1.pub_key(), 1, &den_sec));
+    let counted = VoteSchema::new(&testkit.snapshot()).election(&election).unwrap();
+    assert_eq!((counted.turnout(), counted.total_weight()), (1, 1));
+    assert_eq!(get_candidate(&testkit, &election, john_1.pub_key()).voices(), 1);
+}
+
+#[test]
+fn test_withdrawn_votes_returned() {
+    let mut testkit = init_testkit_with_config(VoteConfig { withdrawn_votes: WithdrawnVotes::Return, ..VoteConfig::default() });
+    let election = create_election(&mut testkit, "Board");
+    let (john, john_sec) = create_candidate(&mut testkit, &election, "John");
+    let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+    let (den, den_sec) = create_elector(&mut testkit, &election, "Den");
+
+    // The mode is fixed at the creation of the election.
+    let mut proposal = testkit.configuration_change_proposal();
+    proposal.set_actual_from(Height(5));
+    proposal.set_service_config(SERVICE_NAME, VoteConfig::default());
+    testkit.commit_configuration_change(proposal);
+
+    start_voting(&mut testkit, &election);
+    create_vote_tx(&mut testkit, &election, john.pub_key(), den.pub_key(), &den_sec);
+
+    testkit.create_block_with_transaction(WithdrawCandidate::new(&election, john.pub_key(), 0, &john_sec));
+
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 0);
+    let elector = get_elector(&testkit, &election, den.pub_key());
+    assert!(elector.has_vote());
+    assert_eq!(elector.candidate(), &PublicKey::zero());
+    let counted = VoteSchema::new(&testkit.snapshot()).election(&election).unwrap();
+    assert_eq!((counted.turnout(), counted.total_weight()), (0, 0));
+
+    // The elector may vote for another candidate.
+    create_vote_tx(&mut testkit, &election, john_1.pub_key(), den.pub_key(), &den_sec);
+    assert_eq!(get_candidate(&testkit, &election, john_1.pub_key()).voices(), 1);
+}
+
+#[test]
+fn test_disqualify_candidate() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let mut testkit = init_testkit_with_registrar(&registrar);
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+
+    let (mallory, mallory_sec) = crypto::gen_keypair();
+    let tx = DisqualifyCandidate::new(&election, &mallory, john.pub_key(), 0, &mallory_sec);
+    let block = testkit.create_block_with_transaction(tx);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The signer is not a registrar"));
+    assert!(get_candidate(&testkit, &election, john.pub_key()).is_active());
+
+    let tx = DisqualifyCandidate::new(&election, &registrar, john.pub_key(), 0, &registrar_sec);
+    testkit.create_block_with_transaction(tx);
+    let candidate = get_candidate(&testkit, &election, john.pub_key());
+    assert_eq!(candidate.candidate_status(), CandidateStatus::Disqualified);
+
+    let (den, den_sec) = crypto::gen_keypair();
+    testkit.create_block_with_transaction(RegisterElector::new(&election, &registrar, &den, "Den", 1, &registrar_sec));
+    start_voting(&mut testkit, &election);
+    let block = testkit.create_block_with_transaction(Vote::new(&election, &den, john.pub_key(), 0, &den_sec));
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The candidate is disqualified"));
+}
+
+#[test]
+fn test_withdrawal_closed() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+    let (john, john_sec) = create_candidate(&mut testkit, &election, "John");
+    testkit.create_blocks_until(Height(TALLYING_HEIGHT - 1));
+
+    let tx = WithdrawCandidate::new(&election, john.pub_key(), 0, &john_sec);
+    let block = testkit.create_block_with_transaction(tx);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The candidates can't be withdrawn after the voting"));
+    assert!(get_candidate(&testkit, &election, john.pub_key()).is_active());
+}
+
+#[test]
+fn test_revoke_elector() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let mut testkit = init_testkit_with_registrar(&registrar);
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+    let (den, den_sec) = crypto::gen_keypair();
+    let (ann, ann_sec) = crypto::gen_keypair();
+    testkit.create_block_with_transactions(txvec![
+        RegisterElector::new(&election, &registrar, &den, "Den", 1, &registrar_sec),
+        RegisterElector::new(&election, &registrar, &ann, "Ann", 1, &registrar_sec),
+    ]);
+    start_voting(&mut testkit, &election);
+    create_vote_tx(&mut testkit, &election, john.pub_key(), &den, &den_sec);
+
+    let (mallory, mallory_sec) = crypto::gen_keypair();
+    let block = testkit.create_block_with_transactions(txvec![
+        RevokeElector::new(&election, &mallory, &den, RevocationReason::Left as u8, 0, &mallory_sec),
+        RevokeElector::new(&election, &registrar, &den, 7, 0, &registrar_sec),
+    ]);
+    let errors: Vec<_> = block.transactions.iter()
+        .map(|tx| tx.status().err().expect("Expect error.").description().map(str::to_owned))
+        .collect();
+    assert_eq!(errors, vec![Some("The signer is not a registrar".to_owned()), Some("Unknown revocation reason".to_owned())]);
+
+    let tx = RevokeElector::new(&election, &registrar, &den, RevocationReason::Left as u8, 1, &registrar_sec);
+    testkit.create_block_with_transactions(txvec![
+        tx.clone(),
+        RevokeElector::new(&election, &registrar, &ann, RevocationReason::Duplicate as u8, 1, &registrar_sec),
+    ]);
+
+    // The vote cast before the revocation is counted by default.
+    let elector = get_elector(&testkit, &election, &den);
+    assert!(elector.revoked());
+    assert_eq!(elector.candidate(), john.pub_key());
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 1);
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    // The counted ballot keeps the elector in the electorate size, Ann hasn't voted.
+    let counted = schema.election(&election).unwrap();
+    assert_eq!((counted.electorate_size(), counted.turnout()), (1, 1));
+    let revocations = schema.revocations(&election);
+    assert_eq!(revocations.len(), 2);
+    let revocation = revocations.get(0).unwrap();
+    assert_eq!(revocation.elector(), &den);
+    assert_eq!(revocation.registrar(), &registrar);
+    assert_eq!(RevocationReason::from_u8(revocation.reason()), Some(RevocationReason::Left));
+    assert_eq!(revocation.tx_hash(), &tx.hash());
+    assert!(!revocation.voided());
+
+    let block = testkit.create_block_with_transactions(txvec![
+        Vote::new(&election, &ann, john_1.pub_key(), 0, &ann_sec),
+        ChangeVote::new(&election, &den, john_1.pub_key(), 0, &den_sec),
+        RevokeElector::new(&election, &registrar, &den, RevocationReason::Left as u8, 2, &registrar_sec),
+    ]);
+    for tx in &block.transactions {
+        let tx_status = tx.status().err().expect("Expect error.");
+        assert_eq!(tx_status.description(), Some("The eligibility of the elector is revoked"));
+    }
+    assert_eq!(get_candidate(&testkit, &election, john_1.pub_key()).voices(), 0);
+}
+
+#[test]
+fn test_revoked_vote_voided() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let config = VoteConfig { registrars: vec![registrar], revoked_votes: RevokedVotes::Void, ..VoteConfig::default() };
+    let mut testkit = init_testkit_with_config(config);
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (den, den_sec) = crypto::gen_keypair();
+    testkit.create_block_with_transaction(RegisterElector::new(&election, &registrar, &den, "Den", 3, &registrar_sec));
+
+    // The mode is fixed at the creation of the election.
+    let mut proposal = testkit.configuration_change_proposal();
+    proposal.set_actual_from(Height(5));
+    proposal.set_service_config(SERVICE_NAME, VoteConfig { registrars: vec![registrar], ..VoteConfig::default() });
+    testkit.commit_configuration_change(proposal);
+
+    start_voting(&mut testkit, &election);
+    create_vote_tx(&mut testkit, &election, john.pub_key(), &den, &den_sec);
+
+    let tx = RevokeElector::new(&election, &registrar, &den, RevocationReason::Ineligible as u8, 0, &registrar_sec);
+    testkit.create_block_with_transaction(tx);
+
+    let elector = get_elector(&testkit, &election, &den);
+    assert!(elector.revoked());
+    assert!(elector.has_vote());
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 0);
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    let counted = schema.election(&election).unwrap();
+    assert_eq!((counted.electorate_size(), counted.turnout(), counted.total_weight()), (0, 0, 0));
+    assert!(schema.vote_record(&election, &den).is_none());
+    assert!(schema.revocations(&election).get(0).unwrap().voided());
+}
+
+#[test]
+fn test_rotate_elector_key() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+    let (john, _) = create_candidate(&mut testkit, &election, "John");
+    let (john_1, _) = create_candidate(&mut testkit, &election, "John_1");
+    let (den, den_sec) = create_elector(&mut testkit, &election, "Den");
+    start_voting(&mut testkit, &election);
+    let vote = create_vote_tx(&mut testkit, &election, john.pub_key(), den.pub_key(), &den_sec);
+
+    let (new_den, new_den_sec) = crypto::gen_keypair();
+    let (_, stranger_sec) = crypto::gen_keypair();
+    let signature = crypto::sign(key_rotation(&election, den.pub_key(), &new_den).as_ref(), &stranger_sec);
+    let forged = RotateKey::new(&election, den.pub_key(), &new_den, &PublicKey::zero(), &signature, 0, &den_sec);
+    assert!(!forged.verify());
+
+    let tx = rotate_key_tx(&election, (den.pub_key(), &den_sec), (&new_den, &new_den_sec), &PublicKey::zero());
+    testkit.create_block_with_transaction(tx.clone());
+
+    assert!(try_get_elector(&testkit, &election, den.pub_key()).is_none());
+    let elector = get_elector(&testkit, &election, &new_den);
+    assert_eq!(elector.name(), "Den");
+    assert_eq!(elector.ballot(), &vote.hash());
+    let snapshot = testkit.snapshot();
+    let schema = VoteSchema::new(&snapshot);
+    assert_eq!(schema.vote_record(&election, &new_den).unwrap().tx_hash(), &vote.hash());
+    assert_eq!(schema.resolve_key(&election, den.pub_key()), new_den);
+    let alias = schema.key_aliases(&election).get(den.pub_key()).unwrap();
+    assert_eq!((alias.new_key(), alias.tx_hash()), (&new_den, &tx.hash()));
+
+    let block = testkit.create_block_with_transactions(txvec![
+        ChangeVote::new(&election, den.pub_key(), john_1.pub_key(), 0, &den_sec),
+        ChangeVote::new(&election, &new_den, john_1.pub_key(), 0, &new_den_sec),
+    ]);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("Elector doesn't exist"));
+    assert!(block.transactions[1].status().is_ok());
+    assert_eq!(get_candidate(&testkit, &election, john.pub_key()).voices(), 0);
+    assert_eq!(get_candidate(&testkit, &election, john_1.pub_key()).voices(), 1);
+}
+
+#[test]
+fn test_replaced_key_not_registered() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let mut testkit = init_testkit_with_registrar(&registrar);
+    let election = create_election(&mut testkit, "Board");
+    let (den, den_sec) = crypto::gen_keypair();
+    testkit.create_block_with_transaction(RegisterElector::new(&election, &registrar, &den, "Den", 1, &registrar_sec));
+    let (new_den, new_den_sec) = crypto::gen_keypair();
+    testkit.create_block_with_transaction(rotate_key_tx(&election, (&den, &den_sec), (&new_den, &new_den_sec), &PublicKey::zero()));
+
+    let (bob, _) = crypto::gen_keypair();
+    let electors = vec![ElectorEntry::new(&bob, "Bob", 1), ElectorEntry::new(&den, "Den", 1)];
+    let block = testkit.create_block_with_transactions(txvec![
+        RegisterElector::new(&election, &registrar, &den, "Den", 2, &registrar_sec),
+        CreateCandidate::new(&election, &den, "Den", &den_sec),
+        RegisterElectorsBatch::new(&election, &registrar, electors, 0, &registrar_sec),
+    ]);
+    let errors: Vec<_> = block.transactions.iter()
+        .map(|tx| tx.status().err().expect("Expect error.").description().map(str::to_owned))
+        .collect();
+    assert_eq!(errors, vec![
+        Some("The key is replaced by a key rotation".to_owned()),
+        Some("The key is replaced by a key rotation".to_owned()),
+        Some("The key is replaced by a key rotation at the entries 1".to_owned()),
+    ]);
+    assert!(try_get_elector(&testkit, &election, &den).is_none());
+    assert!(VoteSchema::new(&testkit.snapshot()).candidate(&election, &den).is_none());
+    assert_eq!(VoteSchema::new(&testkit.snapshot()).election(&election).unwrap().electorate_size(), 1);
+}
+
+#[test]
+fn test_rotate_candidate_key() {
+    let mut testkit = init_testkit();
+    let election = create_election(&mut testkit, "Board");
+    let (john, john_sec) = create_candidate(&mut testkit, &election, "John");
+    let (den, den_sec) = create_elector(&mut testkit, &election, "Den");
+    let (ann, ann_sec) = create_elector(&mut testkit, &election, "Ann");
+    start_voting(&mut testkit, &election);
+    create_vote_tx(&mut testkit, &election, john.pub_key(), den.pub_key(), &den_sec);
+
+    let (new_john, new_john_sec) = crypto::gen_keypair();
+    let tx = rotate_key_tx(&election, (john.pub_key(), &john_sec), (&new_john, &new_john_sec), &PublicKey::zero());
+    testkit.create_block_with_transaction(tx.clone());
+
+    assert!(try_get_candidate(&testkit, &election, john.pub_key()).is_none());
+    let candidate = get_candidate(&testkit, &election, &new_john);
+    assert_eq!(candidate.voices(), 1);
+    assert_eq!(get_elector(&testkit, &election, den.pub_key()).candidate(), &new_john);
+    {
+        let snapshot = testkit.snapshot();
+        let schema = VoteSchema::new(&snapshot);
+        let history = schema.vote_history(&election, &new_john);
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.last(), Some(tx.hash()));
+        assert_eq!(candidate.history_hash(), &history.merkle_root());
+        assert_eq!(schema.vote_history(&election, john.pub_key()).len(), 0);
+    }
+
+    // The ballot cast for the old key is taken back from the new one.
+    testkit.create_block_with_transaction(RevokeVote::new(&election, den.pub_key(), 0, &den_sec));
+    assert_eq!(get_candidate(&testkit, &election, &new_john).voices(), 0);
+
+    // The ballot naming the old key is counted for the new one.
+    create_vote_tx(&mut testkit, &election, john.pub_key(), ann.pub_key(), &ann_sec);
+    assert_eq!(get_candidate(&testkit, &election, &new_john).voices(), 1);
+    assert_eq!(get_elector(&testkit, &election, ann.pub_key()).candidate(), &new_john);
+}
+
+#[test]
+fn test_rotate_key_by_registrar() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let mut testkit = init_testkit_with_registrar(&registrar);
+    let election = create_election(&mut testkit, "Board");
+    let (den, _) = crypto::gen_keypair();
+    let (ann, ann_sec) = crypto::gen_keypair();
+    testkit.create_block_with_transactions(txvec![
+        RegisterElector::new(&election, &registrar, &den, "Den", 1, &registrar_sec),
+        RegisterElector::new(&election, &registrar, &ann, "Ann", 1, &registrar_sec),
+    ]);
+
+    let (new_den, new_den_sec) = crypto::gen_keypair();
+    let (mallory, mallory_sec) = crypto::gen_keypair();
+    let block = testkit.create_block_with_transactions(txvec![
+        rotate_key_tx(&election, (&den, &mallory_sec), (&new_den, &new_den_sec), &mallory),
+        rotate_key_tx(&election, (&den, &registrar_sec), (&ann, &ann_sec), &registrar),
+    ]);
+    let errors: Vec<_> = block.transactions.iter()
+        .map(|tx| tx.status().err().expect("Expect error.").description().map(str::to_owned))
+        .collect();
+    assert_eq!(errors, vec![Some("The signer is not a registrar".to_owned()), Some("The new key is already in use".to_owned())]);
+
+    testkit.create_block_with_transaction(rotate_key_tx(&election, (&den, &registrar_sec), (&new_den, &new_den_sec), &registrar));
+    assert!(try_get_elector(&testkit, &election, &den).is_none());
+    assert_eq!(get_elector(&testkit, &election, &new_den).name(), "Den");
+    let alias = VoteSchema::new(&testkit.snapshot()).key_aliases(&election).get(&den).unwrap();
+    assert_eq!(alias.registrar(), &registrar);
+}
+
+#[test]
+fn test_register_electors_batch() {
+    let (registrar, registrar_sec) = crypto::gen_keypair();
+    let mut testkit = init_testkit_with_registrar(&registrar);
+    let election = create_election(&mut testkit, "Board");
+
+    let (den, _) = crypto::gen_keypair();
+    let (ann, _) = crypto::gen_keypair();
+    let electors = vec![ElectorEntry::new(&den, "Den", 3), ElectorEntry::new(&ann, "Ann", 1)];
+    let tx = RegisterElectorsBatch::new(&election, &registrar, electors, 0, &registrar_sec);
+    testkit.create_block_with_transaction(tx);
+
+    assert_eq!(get_elector(&testkit, &election, &den).weight(), 3);
+    assert_eq!(get_elector(&testkit, &election, &ann).name(), "Ann");
+    let counted = VoteSchema::new(&testkit.snapshot()).election(&election).unwrap();
+    assert_eq!(counted.electorate_size(), 2);
+
+    let (bob, _) = crypto::gen_keypair();
+    let (mallory, mallory_sec) = crypto::gen_keypair();
+    let electors = vec![ElectorEntry::new(&bob, "Bob", 1), ElectorEntry::new(&den, "Den", 1), ElectorEntry::new(&bob, "Bob", 2)];
+    let block = testkit.create_block_with_transactions(txvec![
+        RegisterElectorsBatch::new(&election, &registrar, electors.clone(), 1, &registrar_sec),
+        RegisterElectorsBatch::new(&election, &mallory, electors, 1, &mallory_sec),
+    ]);
+    let errors: Vec<_> = block.transactions.iter()
+        .map(|tx| tx.status().err().expect("Expect error.").description().map(str::to_owned))
+        .collect();
+    assert_eq!(errors, vec![
+        Some("Duplicate electors in the batch at the entries 1, 2".to_owned()),
+        Some("The signer is not a registrar".to_owned()),
+    ]);
+    // The batch is applied as a whole.
+    assert!(try_get_elector(&testkit, &election, &bob).is_none());
+
+    let electors: Vec<ElectorEntry> = (0..=MAX_BATCH_SIZE)
+        .map(|_| ElectorEntry::new(&crypto::gen_keypair().0, "Eve", 1))
+        .collect();
+    let tx = RegisterElectorsBatch::new(&election, &registrar, electors, 2, &registrar_sec);
+    let block = testkit.create_block_with_transaction(tx);
+    let tx_status = block.transactions[0].status().err().expect("Expect error.");
+    assert_eq!(tx_status.description(), Some("The batch has too many entries"));
+}
+
+fn create_vote_tx(testkit: &mut TestKit, election: &Hash, candidate_pub_key: &PublicKey, elector_key: &PublicKey, elector_seq_key: &SecretKey) -> Vote {
+   let tx = Vote::new(election, elector_key, candidate_pub_key, 0, elector_seq_key);
     testkit.create_block_with_transaction(tx.clone());
     tx
 }
 
-fn create_candidate(testkit: &mut TestKit, name: &str) -> (CreateCandidate, SecretKey) {
+fn create_election(testkit: &mut TestKit, name: &str) -> Hash {
+    create_election_with_seats(testkit, name, 1, 1)
+}
+
+fn create_election_with_seats(testkit: &mut TestKit, name: &str, seats: u32, max_choices: u32) -> Hash {
+    let (pubkey, key) = crypto::gen_keypair();
+    let tx = CreateElection::new(&pubkey, name, VOTING_HEIGHT, TALLYING_HEIGHT, CLOSING_HEIGHT, seats, max_choices, CONFIGURED_SCHEME, 0, &key);
+    testkit.create_block_with_transaction(tx.clone());
+    tx.hash()
+}
+
+fn start_voting(testkit: &mut TestKit, election: &Hash) {
+    let voting_height = {
+        let snapshot = testkit.snapshot();
+        let election = VoteSchema::new(&snapshot).election(election).expect("No election persisted.");
+        election.voting_height()
+    };
+    testkit.create_blocks_until(Height(voting_height - 1));
+}
+
+fn create_candidate(testkit: &mut TestKit, election: &Hash, name: &str) -> (CreateCandidate, SecretKey) {
     let (pubkey, key) = crypto::gen_keypair();
-    let tx = CreateCandidate::new(&pubkey, name, &key);
+    let tx = CreateCandidate::new(election, &pubkey, name, &key);
     testkit.create_block_with_transaction(tx.clone());
     (tx, key)
 }
 
-fn create_elector(testkit: &mut TestKit, name: &str) -> (CreateElector, SecretKey) {
+fn create_elector(testkit: &mut TestKit, election: &Hash, name: &str) -> (CreateElector, SecretKey) {
     let (pubkey, key) = crypto::gen_keypair();
-    let tx = CreateElector::new(&pubkey, name, &key);
+    let tx = CreateElector::new(election, &pubkey, name, &key);
     testkit.create_block_with_transaction(tx.clone());
     (tx, key)
 }
 
-fn try_get_candidate(testkit: &TestKit, pubkey: &PublicKey) -> Option<Candidate> {
+fn try_get_candidate(testkit: &TestKit, election: &Hash, pubkey: &PublicKey) -> Option<Candidate> {
     let snapshot = testkit.snapshot();
-    VoteSchema::new(&snapshot).candidate(pubkey)
+    VoteSchema::new(&snapshot).candidate(election, pubkey)
 }
 
-fn get_candidate(testkit: &TestKit, pubkey: &PublicKey) -> Candidate {
-    try_get_candidate(testkit, pubkey).expect("No candidate persisted.")
+fn get_candidate(testkit: &TestKit, election: &Hash, pubkey: &PublicKey) -> Candidate {
+    try_get_candidate(testkit, election, pubkey).expect("No candidate persisted.")
 }
 
-fn try_get_elector(testkit: &TestKit, pubkey: &PublicKey) -> Option<Elector> {
+fn try_get_elector(testkit: &TestKit, election: &Hash, pubkey: &PublicKey) -> Option<Elector> {
     let snapshot = testkit.snapshot();
-    VoteSchema::new(&snapshot).elector(pubkey)
+    VoteSchema::new(&snapshot).elector(election, pubkey)
+}
+
+fn get_elector(testkit: &TestKit, election: &Hash, pubkey: &PublicKey) -> Elector {
+    try_get_elector(testkit, election, pubkey).expect("No elector persisted.")
+}
+
+fn service_keypair(testkit: &TestKit, validator: usize) -> (PublicKey, SecretKey) {
+    let (pub_key, sec) = testkit.network().validators()[validator].service_keypair();
+    (*pub_key, sec.clone())
+}
+
+fn key_share_tx(testkit: &TestKit, election: &Hash, validator: &PublicKey, sec: &SecretKey, seed: u64) -> SubmitKeyShare {
+    let dealing = dealing(testkit, election, validator, sec);
+    dealing_tx(election, validator, sec, &dealing, seed)
+}
+
+fn dealing(testkit: &TestKit, election: &Hash, dealer: &PublicKey, sec: &SecretKey) -> Dealing {
+    let election = VoteSchema::new(testkit.snapshot()).election(election).unwrap();
+    Dealing::new(sec, election.id(), dealer, &election.trustee_keys(), election.threshold() as usize).unwrap()
+}
+
+fn dealing_tx(election: &Hash, dealer: &PublicKey, sec: &SecretKey, dealing: &Dealing, seed: u64) -> SubmitKeyShare {
+    let (commitments, ephemeral, shares, challenge, response) = dealing.to_hashes();
+    SubmitKeyShare::new(election, dealer, &commitments, &ephemeral, &shares, &challenge, &response, seed, sec)
+}
+
+fn complaint_tx(testkit: &TestKit, election: &Hash, trustee: &(PublicKey, SecretKey), dealer: &PublicKey, seed: u64) -> ComplainKeyShare {
+    let (pub_key, sec) = trustee;
+    let dealing = VoteSchema::new(testkit.snapshot()).key_share(election, dealer).unwrap();
+    let ephemeral = lsag::hash_to_edwards(dealing.ephemeral()).unwrap();
+    let shared_key = Hash::new(dkg::shared_key(sec, &ephemeral).compress().to_bytes());
+    let context = elgamal::proof_context(election, pub_key, Some(dealer));
+    let (challenge, response) = SharedKeyProof::new(sec, &ephemeral, &context).to_hashes();
+    ComplainKeyShare::new(election, pub_key, dealer, &shared_key, &challenge, &response, seed, sec)
+}
+
+/// Returns the secret share of the trustee for the qualified dealings of the election.
+fn trustee_secret(testkit: &TestKit, election: &Hash, trustee: &(PublicKey, SecretKey)) -> Scalar {
+    let (pub_key, sec) = trustee;
+    let schema = VoteSchema::new(testkit.snapshot());
+    let index = schema.election(election).unwrap().trustee_index(pub_key).unwrap() as usize;
+    let dealings: Vec<_> = schema.qualified_dealings(election).iter()
+        .map(|dealing| {
+            let ephemeral = lsag::hash_to_edwards(dealing.ephemeral()).unwrap();
+            let share = lsag::scalar_from_hash(&dealing.shares()[index - 1]).unwrap();
+            (*dealing.validator(), ephemeral, share)
+        })
+        .collect();
+    dkg::secret_share(sec, election, pub_key, &dealings)
 }
 
-fn get_elector(testkit: &TestKit, pubkey: &PublicKey) -> Elector {
-    try_get_elector(testkit, pubkey).expect("No elector persisted.")
+fn encrypted_vote_tx(
+    testkit: &TestKit,
+    key: &RistrettoPoint,
+    election: &Hash,
+    elector: &CreateElector,
+    sec: &SecretKey,
+    chosen: &[PublicKey],
+) -> EncryptedVote {
+    let candidates = sorted_candidates(testkit, election);
+    let (choices, proof) = elgamal::encrypt_ballot(key, election, elector.pub_key(), &candidates, chosen, 1);
+    let (challenges, responses) = proof.to_hashes();
+    EncryptedVote::new(election, elector.pub_key(), choices, &challenges, &responses, 0, sec)
+}
+
+fn decryption_tx(testkit: &TestKit, election: &Hash, validator: &(PublicKey, SecretKey), seed: u64) -> SubmitDecryption {
+    let (pub_key, sec) = validator;
+    let secret = trustee_secret(testkit, election, validator);
+    let snapshot = testkit.snapshot();
+    let shares = VoteSchema::new(&snapshot).candidates(election).values()
+        .map(|candidate| {
+            let votes = candidate.encrypted_votes();
+            let share = elgamal::point_to_hash(&elgamal::decryption_share(&secret, &votes));
+            let context = elgamal::proof_context(election, pub_key, Some(candidate.pub_key()));
+            let (challenge, response) = EqualityProof::new(&secret, &votes.a, &context).to_hashes();
+            DecryptionShare::new(candidate.pub_key(), &share, &challenge, &response)
+        })
+        .collect();
+    SubmitDecryption::new(election, pub_key, shares, seed, sec)
+}
+
+/// Commits the fresh nonces of the registrar for the token of the elector, returns the transaction and the nonces.
+fn commit_token_tx(election: &Hash, registrar: &PublicKey, registrar_sec: &SecretKey, elector: &PublicKey, seed: u64) -> (CommitToken, Session) {
+    let issuing_key = IssuingKey::new(registrar_sec, election).public_key();
+    let session = Session::random();
+    let tx = CommitToken::new(election, registrar, elector, &issuing_key, &session.commitments(), seed, registrar_sec);
+    (tx, session)
+}
+
+/// Issues a token for the committed nonces, returns the transaction, the blinding and the key pair of the token.
+fn issue_token_tx(
+    election: &Hash,
+    registrar: &PublicKey,
+    registrar_sec: &SecretKey,
+    commit: &CommitToken,
+    session: Session,
+    seed: u64,
+) -> (IssueToken, Blinding, (PublicKey, SecretKey)) {
+    let (token, token_sec) = crypto::gen_keypair();
+    let blinding = Blinding::new(commit.issuing_key(), commit.commitments(), election, &token).unwrap();
+    let (clause, response) = session.respond(&IssuingKey::new(registrar_sec, election), &blinding.challenges()).unwrap();
+    let tx = IssueToken::new(election, registrar, commit.elector(), &blinding.challenges(), clause, &response, seed, registrar_sec);
+    (tx, blinding, (token, token_sec))
+}
+
+/// Moves the key to the new one, the transaction is signed by the old key or by the registrar if it is given.
+fn rotate_key_tx(
+    election: &Hash,
+    (old_key, signer_sec): (&PublicKey, &SecretKey),
+    (new_key, new_sec): (&PublicKey, &SecretKey),
+    registrar: &PublicKey,
+) -> RotateKey {
+    let signature = crypto::sign(key_rotation(election, old_key, new_key).as_ref(), new_sec);
+    RotateKey::new(election, old_key, new_key, registrar, &signature, 0, signer_sec)
+}
+
+fn anonymous_vote_tx(election: &Hash, ring: &[PublicKey], index: u64, candidate: &PublicKey, sec: &SecretKey) -> AnonymousVote {
+    let (ballot_key, ballot_sec) = crypto::gen_keypair();
+    let message = anonymous_ballot(election, candidate, &ballot_key);
+    let signature = RingSignature::sign(&message, election, ring, sec).unwrap();
+    let (key_image, challenge, responses) = signature.to_hashes();
+    AnonymousVote::new(election, &ballot_key, candidate, index, &key_image, &challenge, &responses, 0, &ballot_sec)
+}
+
+fn sorted_electorate(testkit: &TestKit, election: &Hash) -> Vec<PublicKey> {
+    VoteSchema::new(&testkit.snapshot()).electorate(election).keys().collect()
+}
+
+fn sorted_candidates(testkit: &TestKit, election: &Hash) -> Vec<PublicKey> {
+    VoteSchema::new(&testkit.snapshot()).candidates(election).keys().collect()
 }
 
 fn init_testkit() -> TestKit {
     TestKitBuilder::validator()
-        .with_service(vote::service::VoteService)
+        .with_service(VoteService::default())
         .create()
-}
\ No newline at end of file
+}
+
+fn init_testkit_with_registrar(registrar: &PublicKey) -> TestKit {
+    init_testkit_with_config(VoteConfig { registrars: vec![*registrar], ..VoteConfig::default() })
+}
+
+fn init_testkit_with_validators(voting_scheme: VotingScheme, validators: u16) -> TestKit {
+    TestKitBuilder::validator()
+        .with_validators(validators)
+        .with_service(VoteService::new(VoteConfig { voting_scheme, ..VoteConfig::default() }))
+        .create()
+}
+
+fn init_testkit_with_config(config: VoteConfig) -> TestKit {
+    TestKitBuilder::validator()
+        .with_service(VoteService::new(config))
+        .create()
+}